@@ -14,6 +14,26 @@ pub struct SpannedError {
     span: Span,
 }
 
+/// Warning emitted through a deprecated constant, since stable proc macros
+/// can't produce warnings of their own.
+#[derive(Debug)]
+pub struct Warning {
+    message: Cow<'static, str>,
+    span: Span,
+}
+
+impl Warning {
+    pub fn new<M>(message: M, span: Span) -> Self
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        Warning {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
 impl Error {
     pub fn new<M>(message: M) -> Self
     where
@@ -71,3 +91,19 @@ impl ToTokens for SpannedError {
         )
     }
 }
+
+impl ToTokens for Warning {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let message = &*self.message;
+
+        tokens.append_all(
+            quote_spanned!(self.span => {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const logos_warning: () = ();
+
+                logos_warning
+            };)
+        )
+    }
+}
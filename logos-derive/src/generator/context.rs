@@ -50,7 +50,7 @@ impl Context {
         match self.at {
             0 => None,
             n => {
-                let tokens = quote!(lex.bump(#n););
+                let tokens = quote!(lex.bump_unchecked(#n););
                 self.at = 0;
                 self.available = 0;
                 self.bumped = true;
@@ -78,6 +78,12 @@ impl Context {
         self.available = 0;
     }
 
+    /// Keep `n` bytes available after a bump, which have to be
+    /// shifted out of `arr` by the call site.
+    pub fn keep(&mut self, n: usize) {
+        self.available = n;
+    }
+
     const fn backtrack(self) -> Self {
         Context {
             at: 0,
@@ -92,15 +98,29 @@ impl Context {
         match (miss, self.backtrack) {
             (Some(id), _) => gen.goto(id, self).clone(),
             (_, Some(id)) => gen.goto(id, self.backtrack()).clone(),
+            _ if self.at > 0 => {
+                let at = self.at;
+
+                quote!({
+                    lex.bump_unchecked(#at);
+                    lex.error();
+                })
+            },
             _ if self.bumped => quote!(lex.error()),
             _ => quote!(_error(lex)),
         }
     }
 
-    pub fn call_args(&self) -> TokenStream {
-        match self.available {
-            0 | 1 => quote!(),
-            _ => quote!(, arr),
+    /// Arguments passing available bytes on to a function, skipping
+    /// the first `shift` bytes of `arr` that have been bumped.
+    pub fn call_args(&self, shift: usize) -> TokenStream {
+        match (self.available, shift) {
+            (0, _) | (1, _) => quote!(),
+            (_, 0) => quote!(, arr),
+            (_, shift) => quote!(, match ::core::convert::TryFrom::try_from(&arr[#shift..]) {
+                Ok(arr) => arr,
+                Err(_) => ::core::unreachable!(),
+            }),
         }
     }
 
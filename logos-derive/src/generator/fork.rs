@@ -1,12 +1,17 @@
-use proc_macro2::{TokenStream, Literal};
-use quote::quote;
+use proc_macro2::{TokenStream, Literal, Span};
+use quote::{quote, TokenStreamExt};
+use syn::Ident;
 use fnv::FnvHashMap as Map;
 
 use crate::graph::{NodeId, Fork, Range};
-use crate::generator::{Generator, Context};
+use crate::generator::{Generator, Context, Dispatch, Optimize};
 
 type Targets = Map<NodeId, Vec<Range>>;
 
+/// Classes made of more ranges than this are faster to test one byte at a time
+/// with a lookup table than with vector comparisons for every range.
+const MAX_SIMD_RANGES: usize = 4;
+
 impl<'a> Generator<'a> {
     pub fn generate_fork(&mut self, this: NodeId, fork: &Fork, mut ctx: Context) -> TokenStream {
         let mut targets: Targets = Map::default();
@@ -16,9 +21,14 @@ impl<'a> Generator<'a> {
         }
         let loops_to_self = self.meta[this].loop_entry_from.contains(&this);
 
+        if this == self.root && self.dispatch == Dispatch::Table && ctx == Context::default() {
+            return self.generate_fork_dispatch_table(fork, targets, ctx);
+        }
+
         match targets.len() {
             1 if loops_to_self => return self.generate_fast_loop(fork, ctx),
             0..=2 => (),
+            _ if self.optimize == Optimize::Size => (),
             _ => return self.generate_fork_jump_table(this, fork, targets, ctx),
         }
         let miss = ctx.miss(fork.miss, self);
@@ -82,8 +92,60 @@ impl<'a> Generator<'a> {
         }
     }
 
+    /// Dispatch on the first byte by calling a handler for each branch through a
+    /// table of function pointers. The table is an associated const of a local type,
+    /// since a `static` can't be generic over the `Source`.
+    fn generate_fork_dispatch_table(&mut self, fork: &Fork, targets: Targets, mut ctx: Context) -> TokenStream {
+        let miss = ctx.miss(fork.miss, self);
+        let read = ctx.read(0);
+
+        let mut handler = ctx.advance(1);
+        let mut table = vec![quote!(jump_miss::<S>); 256];
+        let mut handlers = quote! {
+            fn jump_miss<'s, S: Src<'s>>(lex: &mut Lexer<'s, S>) {
+                #miss
+            }
+        };
+
+        handler.wipe();
+
+        for (id, ranges) in targets {
+            let ident = Ident::new(&format!("jump{}", id), Span::call_site());
+            let next = self.goto(id, handler);
+
+            for byte in ranges.into_iter().flatten() {
+                table[byte as usize] = quote!(#ident::<S>);
+            }
+
+            handlers.append_all(quote! {
+                fn #ident<'s, S: Src<'s>>(lex: &mut Lexer<'s, S>) {
+                    #next
+                }
+            });
+        }
+
+        self.rendered.append_all(quote! {
+            #handlers
+
+            struct _Table<S>(S);
+
+            impl<'s, S: Src<'s>> _Table<S> {
+                const JUMP: [fn(&mut Lexer<'s, S>); 256] = [#(#table),*];
+            }
+        });
+
+        quote! {
+            let byte = match #read {
+                Some(byte) => byte,
+                None => return _end(lex),
+            };
+
+            _Table::<S>::JUMP[byte as usize](lex)
+        }
+    }
+
     fn fork_end(&self, this: NodeId, miss: &TokenStream) -> TokenStream {
-        if this == self.root {
+        if this == self.root || Some(this) == self.start {
             quote!(_end(lex))
         } else {
             miss.clone()
@@ -135,6 +197,28 @@ impl<'a> Generator<'a> {
     fn generate_fast_loop(&mut self, fork: &Fork, ctx: Context) -> TokenStream {
         let miss = ctx.miss(fork.miss, self);
         let ranges = fork.branches().map(|(range, _)| range).collect::<Vec<_>>();
+
+        if self.optimize == Optimize::Size {
+            let test = self.generate_test(ranges);
+
+            return quote! {
+                while lex.test(#test) {
+                    lex.bump_unchecked(1);
+                }
+
+                #miss
+            };
+        }
+
+        if cfg!(feature = "simd") && ranges.len() <= MAX_SIMD_RANGES {
+            let bounds = ranges.iter().map(|Range(start, end)| quote!((#start, #end)));
+            let test = self.generate_test(ranges.clone());
+
+            return quote! {
+                _simd_loop!(lex, #test, &[#(#bounds),*], #miss);
+            };
+        }
+
         let test = self.generate_test(ranges);
 
         quote! {
@@ -153,20 +237,48 @@ impl<'a> Generator<'a> {
                         if $test(arr[8])  { if $test(arr[9])  { if $test(arr[10]) { if $test(arr[11]) {
                         if $test(arr[12]) { if $test(arr[13]) { if $test(arr[14]) { if $test(arr[15]) {
 
-                        $lex.bump(16); continue;     } $lex.bump(15); return $miss; }
-                        $lex.bump(14); return $miss; } $lex.bump(13); return $miss; }
-                        $lex.bump(12); return $miss; } $lex.bump(11); return $miss; }
-                        $lex.bump(10); return $miss; } $lex.bump(9); return $miss;  }
-                        $lex.bump(8); return $miss;  } $lex.bump(7); return $miss;  }
-                        $lex.bump(6); return $miss;  } $lex.bump(5); return $miss;  }
-                        $lex.bump(4); return $miss;  } $lex.bump(3); return $miss;  }
-                        $lex.bump(2); return $miss;  } $lex.bump(1); return $miss;  }
+                        $lex.bump_unchecked(16); continue;     } $lex.bump_unchecked(15); return $miss; }
+                        $lex.bump_unchecked(14); return $miss; } $lex.bump_unchecked(13); return $miss; }
+                        $lex.bump_unchecked(12); return $miss; } $lex.bump_unchecked(11); return $miss; }
+                        $lex.bump_unchecked(10); return $miss; } $lex.bump_unchecked(9); return $miss;  }
+                        $lex.bump_unchecked(8); return $miss;  } $lex.bump_unchecked(7); return $miss;  }
+                        $lex.bump_unchecked(6); return $miss;  } $lex.bump_unchecked(5); return $miss;  }
+                        $lex.bump_unchecked(4); return $miss;  } $lex.bump_unchecked(3); return $miss;  }
+                        $lex.bump_unchecked(2); return $miss;  } $lex.bump_unchecked(1); return $miss;  }
 
                         return $miss;
                     }
 
                     while $lex.test($test) {
-                        $lex.bump(1);
+                        $lex.bump_unchecked(1);
+                    }
+
+                    $miss
+                };
+            }
+        }
+    }
+
+    pub fn simd_loop_macro() -> TokenStream {
+        if !cfg!(feature = "simd") {
+            return quote!();
+        }
+
+        quote! {
+            macro_rules! _simd_loop {
+                ($lex:ident, $test:ident, $ranges:expr, $miss:expr) => {
+                    while let Some(arr) = $lex.read::<&[u8; 32]>() {
+                        let len = ::logos::internal::prefix_len(arr, $ranges);
+
+                        $lex.bump_unchecked(len);
+
+                        if len < 32 {
+                            return $miss;
+                        }
+                    }
+
+                    while $lex.test($test) {
+                        $lex.bump_unchecked(1);
                     }
 
                     $miss
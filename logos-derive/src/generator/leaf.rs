@@ -1,4 +1,4 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 
 use crate::leaf::Leaf;
@@ -9,28 +9,83 @@ impl<'a> Generator<'a> {
         let bump = ctx.bump();
 
         match leaf {
-            Leaf::Trivia => {
-                let root = self.goto(self.root, Context::default());
+            Leaf::Trivia => quote! {
+                #bump
+                lex.trivia();
+            },
+            // Whatever was read past the start of the token is left for the next one
+            Leaf::Reject => quote!(lex.reject();),
+            Leaf::AtEnd { token, otherwise } => {
+                let token = self.generate_leaf(token, ctx);
+                let otherwise = match otherwise {
+                    Some(leaf) => self.generate_leaf(leaf, ctx),
+                    None => quote!(lex.error();),
+                };
 
                 quote! {
                     #bump
-                    lex.trivia();
-                    return #root;
+                    if lex.read::<u8>().is_none() {
+                        #token
+                    } else {
+                        #otherwise
+                    }
                 }
             },
-            Leaf::Token { ident, callback, .. } => {
+            Leaf::Token { ident, callback, field, lookahead, class, keywords, until, nested, .. } => {
                 let name = self.name;
-                let out = quote! {
-                    #bump
-                    lex.token = #name::#ident;
+                let bump = match (lookahead, class) {
+                    (_, Some(class)) => quote!(#bump lex.bump_while(#class);),
+                    (0, None) => quote!(#bump),
+                    (n, None) => quote!(#bump lex.rewind(#n);),
+                };
+
+                let token = match (callback, field) {
+                    (Some(callback), true) => quote! {
+                        #callback(lex).construct(#name::#ident, lex);
+                    },
+                    (Some(callback), false) => quote! {
+                        #callback(lex).construct(|()| #name::#ident, lex);
+                    },
+                    (None, _) => quote! {
+                        lex.token = #name::#ident;
+                    },
+                };
+
+                let token = match keywords {
+                    Some(table) => quote! {
+                        match lex.keyword(#table) {
+                            Some(keyword) => lex.token = keyword,
+                            None => { #token }
+                        }
+                    },
+                    None => token,
                 };
 
-                match callback {
-                    Some(callback) => quote! {
-                        #out
-                        #callback(lex);
+                match until {
+                    Some(terminator) => {
+                        let terminator = Literal::byte_string(terminator);
+                        let bump_until = match nested {
+                            Some(opening) => {
+                                let opening = Literal::byte_string(opening);
+
+                                quote!(lex.bump_nested(#opening, #terminator))
+                            },
+                            None => quote!(lex.bump_until(#terminator)),
+                        };
+
+                        quote! {
+                            #bump
+                            if #bump_until {
+                                #token
+                            } else {
+                                lex.error();
+                            }
+                        }
+                    },
+                    None => quote! {
+                        #bump
+                        #token
                     },
-                    None => out,
                 }
             },
         }
@@ -13,11 +13,49 @@ mod context;
 
 use self::context::Context;
 
+/// Strategy for dispatching on the first byte of a token.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dispatch {
+    /// Match the byte against the branches, which becomes a jump table
+    /// with a lookup of the branch index when there are more than two.
+    Branch,
+    /// Call a handler function through a 256-entry table of function pointers.
+    Table,
+}
+
+/// Whether the generated code should favor speed or size.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Optimize {
+    /// Use lookup tables, unrolled loops and inlining.
+    Speed,
+    /// Test bytes with plain matches and loop one byte at a time.
+    Size,
+}
+
+/// Inlining hint for the functions matching a variant, set with
+/// `#[logos(inline)]` or `#[logos(noinline)]` on the variant.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Inline {
+    /// Render the functions with `#[inline(always)]`.
+    Always,
+    /// Render the functions with `#[inline(never)]`.
+    Never,
+}
+
 pub struct Generator<'a> {
     /// Name of the type we are implementing the `Logos` trait for
     name: &'a Ident,
     /// Id to the root node
     root: NodeId,
+    /// Id to the root node used at the start of input, if any
+    /// definitions are anchored there with `^`
+    start: Option<NodeId>,
+    /// How the root node dispatches on the first byte
+    dispatch: Dispatch,
+    /// Whether to favor speed or size of the generated code
+    optimize: Optimize,
+    /// Inlining hints of the variants that have one
+    hints: Map<Ident, Inline>,
     /// Reference to the graph with all of the nodes
     graph: &'a Graph<Leaf>,
     /// Meta data collected for the nodes
@@ -34,16 +72,36 @@ pub struct Generator<'a> {
     /// Identifiers for helper functions matching a byte to a given
     /// set of ranges
     tests: Map<Vec<Range>, Ident>,
+    /// Inlining hints shared by all variants reachable from a node
+    node_hints: Map<NodeId, Option<Inline>>,
 }
 
 impl<'a> Generator<'a> {
-    pub fn new(name: &'a Ident, root: NodeId, graph: &'a Graph<Leaf>) -> Self {
-        let rendered = Self::fast_loop_macro();
-        let meta = Meta::analyze(root, graph);
+    pub fn new(
+        name: &'a Ident,
+        root: NodeId,
+        start: Option<NodeId>,
+        dispatch: Dispatch,
+        optimize: Optimize,
+        hints: Map<Ident, Inline>,
+        graph: &'a Graph<Leaf>,
+    ) -> Self {
+        let mut rendered = Self::fast_loop_macro();
+
+        rendered.append_all(Self::simd_loop_macro());
+        let mut meta = Meta::analyze(root, graph);
+
+        if let Some(start) = start {
+            meta.first_pass(start, start, graph, &mut Vec::new());
+        }
 
         Generator {
             name,
             root,
+            start,
+            dispatch,
+            optimize,
+            hints,
             graph,
             meta,
             rendered,
@@ -51,11 +109,26 @@ impl<'a> Generator<'a> {
             idents: Map::default(),
             gotos: Map::default(),
             tests: Map::default(),
+            node_hints: Map::default(),
         }
     }
 
     pub fn generate(&mut self) -> &TokenStream {
         let root = self.goto(self.root, Context::default()).clone();
+        let root = match self.start {
+            Some(start) => {
+                let start = self.goto(start, Context::default()).clone();
+
+                quote! {
+                    if lex.at_start() {
+                        #start
+                    } else {
+                        #root
+                    }
+                }
+            },
+            None => root,
+        };
 
         self.rendered.append_all(root);
         &self.rendered
@@ -72,11 +145,16 @@ impl<'a> Generator<'a> {
             Node::Rope(rope) => self.generate_rope(rope, ctx),
             Node::Leaf(leaf) => self.generate_leaf(leaf, ctx),
         };
+        let inline = match self.node_hint(id) {
+            Some(Inline::Always) => quote!(#[inline(always)]),
+            Some(Inline::Never) => quote!(#[inline(never)]),
+            None => self.inline(),
+        };
         let ident = self.generate_ident(id, ctx);
         let props = ctx.fn_props();
         let out = quote! {
-            #[inline]
-            fn #ident<'s, S: Src<'s>>(lex: &mut Lexer<S> #props) {
+            #inline
+            fn #ident<'s, S: Src<'s>>(lex: &mut Lexer<'s, S> #props) {
                 #body
             }
         };
@@ -91,17 +169,27 @@ impl<'a> Generator<'a> {
             let meta = &self.meta[id];
             let enters_loop = meta.loop_entry_from.len() > 0;
 
+            let (at, remainder) = (ctx.at(), ctx.remainder());
             let bump = if enters_loop || !ctx.can_backtrack() {
                 ctx.switch(self.graph[id].miss())
             } else {
                 None
             };
+            // Bytes past the bumped ones were already read, keep them
+            // available to spare the next node another bounds check
+            let shift = match bump {
+                Some(_) if remainder > 1 => {
+                    ctx.keep(remainder);
+                    at
+                },
+                _ => 0,
+            };
             if meta.min_read == 0 || ctx.remainder() < meta.min_read  {
                 ctx.wipe();
             }
 
             let ident = self.generate_ident(id, ctx);
-            let args = ctx.call_args();
+            let args = ctx.call_args(shift);
             let mut call_site = quote!(#ident(lex #args));
 
             if let Some(bump) = bump {
@@ -126,16 +214,72 @@ impl<'a> Generator<'a> {
         })
     }
 
+    /// Inlining duplicates the bodies of functions at every call site,
+    /// so leave it to the compiler when optimizing for size.
+    fn inline(&self) -> TokenStream {
+        match self.optimize {
+            Optimize::Speed => quote!(#[inline]),
+            Optimize::Size => quote!(),
+        }
+    }
+
+    /// Inlining hint of a node, if every variant it can produce has the same one.
+    fn node_hint(&mut self, node: NodeId) -> Option<Inline> {
+        if self.hints.is_empty() {
+            return None;
+        }
+        if let Some(hint) = self.node_hints.get(&node) {
+            return *hint;
+        }
+
+        let mut hint = None;
+        let mut visited = Set::default();
+        let mut stack = vec![node];
+
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            match &self.graph[id] {
+                Node::Fork(fork) => {
+                    stack.extend(fork.branches().map(|(_, then)| then));
+                    stack.extend(fork.miss);
+                },
+                Node::Rope(rope) => {
+                    stack.push(rope.then);
+                    stack.extend(rope.miss.first());
+                },
+                Node::Leaf(leaf) => {
+                    for ident in leaf.idents() {
+                        match (self.hints.get(ident), hint) {
+                            (Some(&new), None) => hint = Some(new),
+                            (Some(&new), Some(old)) if new == old => (),
+                            _ => {
+                                self.node_hints.insert(node, None);
+                                return None;
+                            },
+                        }
+                    }
+                },
+            }
+        }
+
+        self.node_hints.insert(node, hint);
+        hint
+    }
+
     /// Returns an identifier to a function that matches a byte to any
     /// of the provided ranges. This will generate either a simple
-    /// match expression, or use a lookup table internally.
+    /// match expression, or use a lookup table internally when
+    /// optimizing for speed.
     fn generate_test(&mut self, ranges: Vec<Range>) -> &Ident {
         if !self.tests.contains_key(&ranges) {
             let idx = self.tests.len();
             let ident = Ident::new(&format!("pattern{}", idx), Span::call_site());
 
             let body = match ranges.len() {
-                0..=2 => {
+                len if len <= 2 || self.optimize == Optimize::Size => {
                     quote! {
                         match byte {
                             #(#ranges)|* => true,
@@ -163,8 +307,10 @@ impl<'a> Generator<'a> {
                     }
                 }
             };
+            let inline = self.inline();
+
             self.rendered.append_all(quote! {
-                #[inline]
+                #inline
                 fn #ident(byte: u8) -> bool {
                     #body
                 }
@@ -1,30 +1,51 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::graph::Rope;
+use crate::graph::{Pattern, Rope};
 use crate::generator::{Generator, Context};
 
+/// Largest chunk `Source::read` can produce, ropes longer than this
+/// are split into multiple consecutive reads.
+const MAX_CHUNK: usize = 32;
+
 impl<'a> Generator<'a> {
     pub fn generate_rope(&mut self, rope: &Rope, mut ctx: Context) -> TokenStream {
         let miss = ctx.miss(rope.miss.first(), self);
-        let read = ctx.read(rope.pattern.len());
-        let then = self.goto(rope.then, ctx.advance(rope.pattern.len()));
-
-        let pat = match rope.pattern.to_bytes() {
-            Some(bytes) => byte_slice_literal(&bytes),
-            None => {
-                let ranges = rope.pattern.iter();
-
-                quote!([#(#ranges),*])
-            },
-        };
-
-        return quote! {
-            match #read {
-                Some(#pat) => #then,
-                _ => #miss,
+        let mut reads = Vec::new();
+
+        for chunk in rope.pattern.chunks(MAX_CHUNK) {
+            let pat = match Pattern::from(chunk).to_bytes() {
+                Some(bytes) => byte_slice_literal(&bytes),
+                None => {
+                    let ranges = chunk.iter();
+
+                    quote!([#(#ranges),*])
+                },
+            };
+
+            // Bytes already read by a preceding fork are compared without another bounds check
+            if ctx.remainder() >= chunk.len() {
+                let (at, end) = (ctx.at(), ctx.at() + chunk.len());
+
+                reads.push((quote!(&arr[#at..#end]), pat));
+            } else {
+                reads.push((ctx.read(chunk.len()), quote!(Some(#pat))));
             }
-        };
+            ctx = ctx.advance(chunk.len());
+        }
+
+        let mut then = self.goto(rope.then, ctx).clone();
+
+        for (read, pat) in reads.into_iter().rev() {
+            then = quote! {
+                match #read {
+                    #pat => #then,
+                    _ => #miss,
+                }
+            };
+        }
+
+        then
     }
 }
 
@@ -0,0 +1,111 @@
+use std::fmt::{Debug, Write};
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::graph::rope::Miss;
+
+impl<Leaf: Debug> Graph<Leaf> {
+    /// Render all nodes reachable from `root` as a Graphviz DOT digraph.
+    ///
+    /// Forks and ropes are drawn as circles, leaves as double circles labeled with
+    /// the token they produce. Dashed edges are taken when nothing else matches.
+    pub fn dot(&self, name: &str, root: NodeId) -> String {
+        let mut out = String::new();
+        let mut stack = vec![root];
+        let mut visited = vec![false; self.nodes().len()];
+
+        let _ = writeln!(out, "digraph {} {{", name);
+        let _ = writeln!(out, "    rankdir=LR;");
+        let _ = writeln!(out, "    start [shape=point];");
+        let _ = writeln!(out, "    start -> n{};", root);
+
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut visited[id], true) {
+                continue;
+            }
+
+            let mut edges = Vec::new();
+
+            match self.get(id) {
+                Some(Node::Fork(fork)) => {
+                    let _ = writeln!(out, "    n{} [shape=circle, label=\"{}\"];", id, id);
+
+                    for (range, then) in fork.branches() {
+                        edges.push((then, format!("{}", range), false));
+                    }
+                    if let Some(miss) = fork.miss {
+                        edges.push((miss, String::new(), true));
+                    }
+                },
+                Some(Node::Rope(rope)) => {
+                    let _ = writeln!(out, "    n{} [shape=circle, label=\"{}\"];", id, id);
+
+                    let pattern = rope.pattern.iter().map(ToString::to_string).collect();
+
+                    edges.push((rope.then, pattern, false));
+
+                    match rope.miss {
+                        Miss::First(miss) => edges.push((miss, String::new(), true)),
+                        Miss::Any(miss) => edges.push((miss, "*".to_string(), true)),
+                        Miss::None => (),
+                    }
+                },
+                Some(Node::Leaf(leaf)) => {
+                    let label = escape(&format!("{:?}", leaf));
+
+                    let _ = writeln!(out, "    n{} [shape=doublecircle, label=\"{}\"];", id, label);
+                },
+                None => {
+                    let _ = writeln!(out, "    n{} [shape=circle, label=\"{}\"];", id, id);
+                },
+            }
+
+            for (then, label, miss) in edges {
+                let style = if miss { ", style=dashed" } else { "" };
+
+                let _ = writeln!(out, "    n{} -> n{} [label=\"{}\"{}];", id, then, escape(&label), style);
+
+                stack.push(then);
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Fork, Rope};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dot_output() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LET"));
+        let rope = graph.push(Rope::new("let", leaf));
+        let root = graph.push(Fork::new().branch(b'"', rope).miss(leaf));
+
+        assert_eq!(
+            graph.dot("Token", root),
+            concat!(
+                "digraph Token {\n",
+                "    rankdir=LR;\n",
+                "    start [shape=point];\n",
+                "    start -> n2;\n",
+                "    n2 [shape=circle, label=\"2\"];\n",
+                "    n2 -> n1 [label=\"\\\"\"];\n",
+                "    n2 -> n0 [label=\"\", style=dashed];\n",
+                "    n0 [shape=doublecircle, label=\"\\\"LET\\\"\"];\n",
+                "    n1 [shape=circle, label=\"1\"];\n",
+                "    n1 -> n0 [label=\"let\"];\n",
+                "}\n",
+            ),
+        );
+    }
+}
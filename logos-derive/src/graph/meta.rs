@@ -86,7 +86,8 @@ impl Meta {
                 for (_, id) in fork.branches() {
                     let meta = self.first_pass(id, this, graph, stack);
 
-                    if meta.is_loop_init {
+                    // Nodes with a miss can match without reading any further
+                    if meta.is_loop_init || graph[id].miss().is_some() {
                         min_read = 1;
                     } else {
                         min_read = min(min_read, meta.min_read + 1);
@@ -103,7 +104,7 @@ impl Meta {
                 min_read = rope.pattern.len();
                 let meta = self.first_pass(rope.then, this, graph, stack);
 
-                if !meta.is_loop_init {
+                if !meta.is_loop_init && graph[rope.then].miss().is_none() {
                     min_read += meta.min_read;
                 }
 
@@ -136,7 +137,7 @@ impl Meta {
                 for (_, id) in fork.branches() {
                     let meta = &self[id];
 
-                    if meta.is_loop_init {
+                    if meta.is_loop_init || graph[id].miss().is_some() {
                         min_read = 1;
                     } else {
                         min_read = min(min_read, meta.min_read + 1);
@@ -150,7 +151,7 @@ impl Meta {
                 min_read = rope.pattern.len();
                 let meta = &self[rope.then];
 
-                if !meta.is_loop_init {
+                if !meta.is_loop_init && graph[rope.then].miss().is_none() {
                     min_read += meta.min_read;
                 }
             },
@@ -12,11 +12,14 @@ mod fork;
 mod rope;
 mod range;
 mod regex;
+mod overlap;
+mod dot;
 
 pub use self::meta::Meta;
 pub use self::fork::Fork;
-pub use self::rope::Rope;
+pub use self::rope::{Pattern, Rope};
 pub use self::range::Range;
+pub use self::regex::invalid_utf8;
 
 pub struct Graph<Leaf> {
     /// Internal storage of all allocated nodes. Once a node is
@@ -34,10 +37,21 @@ pub struct Graph<Leaf> {
     /// onto the graph (inserts are exempt), we hash it and find if
     /// an identical(!) node has been created before.
     hashes: Map<u64, NodeId>,
+    /// Pairs of distinct leaves that were merged while having equal
+    /// priority, the first one of the pair being the one that won.
+    ties: Vec<[NodeId; 2]>,
+    /// Restrict regex classes to ASCII, see `Graph::ascii`.
+    ascii: bool,
 }
 
-pub trait Disambiguate {
+pub trait Disambiguate: Sized {
     fn cmp(left: &Self, right: &Self) -> Ordering;
+
+    /// Combine two leaves matching the same input into a new one, instead
+    /// of picking one of them with `cmp`.
+    fn merge(_left: &Self, _right: &Self) -> Option<Self> {
+        None
+    }
 }
 
 /// Unique reserved NodeId. This mustn't implement Clone.
@@ -55,9 +69,17 @@ impl<Leaf> Graph<Leaf> {
             nodes: Vec::new(),
             merges: Vec::new(),
             hashes: Map::new(),
+            ties: Vec::new(),
+            ascii: false,
         }
     }
 
+    /// Only match ASCII characters with regular expressions added from now on,
+    /// dropping all other characters from their classes.
+    pub fn ascii(&mut self) {
+        self.ascii = true;
+    }
+
     /// Reserve an empty slot for a node on the graph and return an
     /// id for it. `ReservedId` cannot be cloned, and must be consumed
     /// by calling `insert` on the graph.
@@ -138,9 +160,19 @@ impl<Leaf> Graph<Leaf> {
             (Some(Node::Leaf(_)), None) => return a,
             (None, Some(Node::Leaf(_))) => return b,
             (Some(Node::Leaf(left)), Some(Node::Leaf(right))) => {
+                if let Some(leaf) = Disambiguate::merge(left, right) {
+                    return self.push(Node::Leaf(leaf));
+                }
+
                 return match Disambiguate::cmp(left, right) {
                     Ordering::Less => b,
-                    Ordering::Equal | Ordering::Greater => a,
+                    Ordering::Greater => a,
+                    Ordering::Equal => {
+                        if !self.ties.contains(&[a, b]) {
+                            self.ties.push([a, b]);
+                        }
+                        a
+                    },
                 };
             },
             _ => (),
@@ -263,17 +295,27 @@ impl<Leaf> Graph<Leaf> {
         }
     }
 
+    /// Pairs of leaves that could be reached by the same input with equal priority.
+    pub fn ties(&self) -> &[[NodeId; 2]] {
+        &self.ties
+    }
+
     pub fn nodes(&self) -> &[Option<Node<Leaf>>] {
         &self.nodes
     }
 
     /// Find all nodes that have no references and remove them.
-    pub fn shake(&mut self, root: NodeId) {
+    pub fn shake<Roots>(&mut self, roots: Roots)
+    where
+        Roots: IntoIterator<Item = NodeId>,
+    {
         let mut filter = vec![false; self.nodes.len()];
 
-        filter[root] = true;
+        for root in roots {
+            filter[root] = true;
 
-        self[root].shake(self, &mut filter);
+            self[root].shake(self, &mut filter);
+        }
 
         for (id, referenced) in filter.into_iter().enumerate() {
             if !referenced {
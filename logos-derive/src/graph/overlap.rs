@@ -0,0 +1,157 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::graph::{Graph, Node, NodeId};
+use crate::graph::rope::Miss;
+
+/// Upper bound on the number of state pairs visited before giving up.
+const MAX_STATES: usize = 4096;
+
+/// Position in the graph, the offset is only meaningful for ropes.
+type State = (NodeId, usize);
+
+impl<Leaf> Graph<Leaf> {
+    /// Find the shortest input on which the patterns starting at `a` and `b`
+    /// both reach a leaf, if there is one. Printable ASCII is preferred, so that
+    /// the result is useful in error messages.
+    pub fn overlap(&self, a: NodeId, b: NodeId) -> Option<Vec<u8>> {
+        let bytes: Vec<u8> = (0x20..0x7F).chain(0..0x20).chain(0x7F..=0xFF).collect();
+
+        let start = (self.closure(vec![(a, 0)]), self.closure(vec![(b, 0)]));
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        visited.insert(start.clone());
+        queue.push_back((start, Vec::new()));
+
+        while let Some(((left, right), input)) = queue.pop_front() {
+            if !input.is_empty() && self.accepts(&left) && self.accepts(&right) {
+                return Some(input);
+            }
+
+            for &byte in &bytes {
+                let left = self.step(&left, byte);
+
+                if left.is_empty() {
+                    continue;
+                }
+
+                let right = self.step(&right, byte);
+
+                if right.is_empty() || visited.len() >= MAX_STATES {
+                    continue;
+                }
+
+                let pair = (left, right);
+
+                if visited.insert(pair.clone()) {
+                    let mut input = input.clone();
+
+                    input.push(byte);
+                    queue.push_back((pair, input));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Add all states reachable by following misses, without consuming input.
+    fn closure(&self, mut states: Vec<State>) -> Vec<State> {
+        let mut i = 0;
+
+        while let Some(&(id, offset)) = states.get(i) {
+            i += 1;
+
+            let miss = match self.get(id) {
+                Some(Node::Fork(fork)) => fork.miss,
+                Some(Node::Rope(rope)) => match rope.miss {
+                    Miss::Any(miss) => Some(miss),
+                    Miss::First(miss) if offset == 0 => Some(miss),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            if let Some(miss) = miss {
+                if !states.contains(&(miss, 0)) {
+                    states.push((miss, 0));
+                }
+            }
+        }
+
+        states.sort_unstable();
+        states
+    }
+
+    fn step(&self, states: &[State], byte: u8) -> Vec<State> {
+        let mut next = Vec::new();
+
+        for &(id, offset) in states {
+            let then = match self.get(id) {
+                Some(Node::Fork(fork)) => fork.contains(byte).map(|then| (then, 0)),
+                Some(Node::Rope(rope)) => {
+                    let range = rope.pattern[offset];
+
+                    if range.0 <= byte && byte <= range.1 {
+                        match offset + 1 == rope.pattern.len() {
+                            true => Some((rope.then, 0)),
+                            false => Some((id, offset + 1)),
+                        }
+                    } else {
+                        None
+                    }
+                },
+                _ => None,
+            };
+
+            if let Some(then) = then {
+                if !next.contains(&then) {
+                    next.push(then);
+                }
+            }
+        }
+
+        self.closure(next)
+    }
+
+    fn accepts(&self, states: &[State]) -> bool {
+        states.iter().any(|&(id, _)| matches!(self.get(id), Some(Node::Leaf(_))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Fork, Rope};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn overlapping_patterns() {
+        let mut graph = Graph::new();
+
+        let word = graph.push(Node::Leaf("WORD"));
+        let id = graph.reserve();
+        let fork = Fork::new().branch('a'..='z', id.get()).miss(word);
+        let word_loop = graph.insert(id, fork);
+        let word = graph.push(Fork::new().branch('a'..='z', word_loop));
+
+        let keyword = graph.push(Node::Leaf("LET"));
+        let keyword = graph.push(Rope::new("let", keyword));
+
+        assert_eq!(graph.overlap(word, keyword), Some(b"let".to_vec()));
+        assert_eq!(graph.overlap(keyword, word), Some(b"let".to_vec()));
+    }
+
+    #[test]
+    fn disjoint_patterns() {
+        let mut graph = Graph::new();
+
+        let digit = graph.push(Node::Leaf("DIGIT"));
+        let digit = graph.push(Fork::new().branch('0'..='9', digit));
+
+        let keyword = graph.push(Node::Leaf("LET"));
+        let keyword = graph.push(Rope::new("let", keyword));
+
+        assert_eq!(graph.overlap(digit, keyword), None);
+    }
+}
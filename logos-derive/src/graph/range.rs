@@ -69,7 +69,7 @@ impl From<ClassUnicodeRange> for Range {
         let start = r.start() as u32;
         let end = r.end() as u32;
 
-        if start >= 128 || end >= 128 && end != 0x0010FFFF {
+        if start >= 128 || end >= 128 {
             panic!("Casting non-ascii ClassUnicodeRange to Range")
         }
 
@@ -2,7 +2,8 @@ use std::fmt::Debug;
 use std::cmp::min;
 use std::convert::TryFrom;
 
-use regex_syntax::hir::{Class, ClassUnicode, Hir, HirKind, Literal, RepetitionKind};
+use regex_syntax::hir::{Class, ClassBytes, ClassBytesRange, ClassUnicode, ClassUnicodeRange};
+use regex_syntax::hir::{Anchor, Hir, HirKind, Literal, RepetitionKind, RepetitionRange};
 use regex_syntax::ParserBuilder;
 use utf8_ranges::Utf8Sequences;
 
@@ -22,6 +23,96 @@ enum Mir {
     Alternation(Vec<Mir>),
     Class(Class),
     Literal(Literal),
+    /// Repetition of a class that stops at the first occurrence of the `terminator`,
+    /// which is consumed along with it. The class doesn't include the terminator bytes.
+    Lazy {
+        class: Class,
+        terminator: Vec<u8>,
+    },
+    /// Non-greedy `*?` and `??` repetitions, resolved into `Lazy` and `Alternation`
+    /// respectively once the literal following them is known.
+    LazyLoop(Box<Mir>),
+    LazyMaybe(Box<Mir>),
+}
+
+impl Mir {
+    fn is_lazy(&self) -> bool {
+        matches!(self, Mir::LazyLoop(_) | Mir::LazyMaybe(_))
+    }
+
+    fn lazy_error() -> Error {
+        "#[regex]: non-greedy repetitions are only supported on a class directly \
+         followed by a literal, such as `.*?\\*/`.".into()
+    }
+
+    /// Whether the pattern can match the empty string, in which case a token
+    /// made of it would never make progress.
+    fn nullable(&self) -> bool {
+        match self {
+            Mir::Empty | Mir::Loop(_) | Mir::Maybe(_) | Mir::LazyLoop(_) | Mir::LazyMaybe(_) => true,
+            Mir::Concat(concat) => concat.iter().all(Mir::nullable),
+            Mir::Alternation(alternation) => alternation.iter().any(Mir::nullable),
+            Mir::Class(_) | Mir::Literal(_) | Mir::Lazy { .. } => false,
+        }
+    }
+
+    fn nullable_error() -> Error {
+        "#[regex]: expression can match empty string.\n\n\
+         hint: consider changing * to +".into()
+    }
+
+    /// Length in bytes of every match, if all matches have the same length.
+    fn fixed_len(&self) -> Option<usize> {
+        match self {
+            Mir::Empty => Some(0),
+            Mir::Concat(concat) => concat.iter().map(Mir::fixed_len).sum(),
+            Mir::Alternation(alternation) => {
+                let len = alternation.first()?.fixed_len()?;
+
+                alternation.iter().all(|mir| mir.fixed_len() == Some(len)).then_some(len)
+            },
+            Mir::Literal(Literal::Unicode(c)) => Some(c.len_utf8()),
+            Mir::Literal(Literal::Byte(_)) | Mir::Class(Class::Bytes(_)) => Some(1),
+            Mir::Class(Class::Unicode(class)) => {
+                let len = class.iter().next()?.start().len_utf8();
+
+                class
+                    .iter()
+                    .all(|range| range.start().len_utf8() == len && range.end().len_utf8() == len)
+                    .then_some(len)
+            },
+            Mir::Loop(_) | Mir::Maybe(_) | Mir::Lazy { .. } | Mir::LazyLoop(_) | Mir::LazyMaybe(_) => None,
+        }
+    }
+
+    /// Restrict all classes to ASCII, for enums with `#[logos(ascii)]`, so that
+    /// every character matched is a single byte.
+    fn into_ascii(self) -> Result<Mir> {
+        let boxed = |mir: Box<Mir>| mir.into_ascii().map(Box::new);
+        let all = |mirs: Vec<Mir>| mirs.into_iter().map(Mir::into_ascii).collect::<Result<Vec<_>>>();
+
+        Ok(match self {
+            Mir::Empty => Mir::Empty,
+            Mir::Loop(mir) => Mir::Loop(boxed(mir)?),
+            Mir::Maybe(mir) => Mir::Maybe(boxed(mir)?),
+            Mir::LazyLoop(mir) => Mir::LazyLoop(boxed(mir)?),
+            Mir::LazyMaybe(mir) => Mir::LazyMaybe(boxed(mir)?),
+            Mir::Concat(concat) => Mir::Concat(all(concat)?),
+            Mir::Alternation(alternation) => Mir::Alternation(all(alternation)?),
+            Mir::Class(class) => Mir::Class(ascii_only(class)?),
+            Mir::Literal(Literal::Unicode(c)) if !c.is_ascii() => {
+                return Err(format!("#[logos(ascii)]: `{}` is not an ASCII character.", c.escape_default()).into());
+            },
+            Mir::Literal(Literal::Byte(byte)) if !byte.is_ascii() => {
+                return Err(format!("#[logos(ascii)]: `\\x{:02X}` is not an ASCII character.", byte).into());
+            },
+            Mir::Literal(literal) => Mir::Literal(literal),
+            Mir::Lazy { class, terminator } => Mir::Lazy {
+                class: ascii_only(class)?,
+                terminator,
+            },
+        })
+    }
 }
 
 impl TryFrom<Hir> for Mir {
@@ -42,14 +133,20 @@ impl TryFrom<Hir> for Mir {
                     }
                 }
 
+                resolve_lazy(&mut out)?;
+
                 Ok(Mir::Concat(out))
             },
             HirKind::Alternation(alternation) => {
-                let alternation = alternation
+                let alternation: Vec<Mir> = alternation
                     .into_iter()
                     .map(Mir::try_from)
                     .collect::<Result<_>>()?;
 
+                if alternation.iter().any(Mir::is_lazy) {
+                    return Err(Mir::lazy_error());
+                }
+
                 Ok(Mir::Alternation(alternation))
             },
             HirKind::Literal(literal) => {
@@ -59,13 +156,27 @@ impl TryFrom<Hir> for Mir {
                 Ok(Mir::Class(class))
             },
             HirKind::Repetition(repetition) => {
-                if !repetition.greedy {
-                    Err("#[regex]: non-greedy parsing is currently unsupported.")?;
-                }
-
                 let kind = repetition.kind;
                 let mir = Mir::try_from(*repetition.hir)?;
 
+                if mir.is_lazy() {
+                    return Err(Mir::lazy_error());
+                }
+
+                if !repetition.greedy {
+                    return match kind {
+                        RepetitionKind::ZeroOrOne => Ok(Mir::LazyMaybe(Box::new(mir))),
+                        RepetitionKind::ZeroOrMore => Ok(Mir::LazyLoop(Box::new(mir))),
+                        RepetitionKind::OneOrMore => Ok(Mir::Concat(vec![
+                            mir.clone(),
+                            Mir::LazyLoop(Box::new(mir)),
+                        ])),
+                        RepetitionKind::Range(_) => {
+                            Err("#[regex]: non-greedy counted repetitions are currently unsupported.")?
+                        },
+                    };
+                }
+
                 match kind {
                     RepetitionKind::ZeroOrOne => {
                         Ok(Mir::Maybe(Box::new(mir)))
@@ -79,8 +190,40 @@ impl TryFrom<Hir> for Mir {
                             Mir::Loop(Box::new(mir)),
                         ]))
                     },
-                    RepetitionKind::Range(..) => {
-                        Err("#[regex]: {n,m} repetition range is currently unsupported.")?
+                    RepetitionKind::Range(range) => {
+                        let (n, m) = match range {
+                            RepetitionRange::Exactly(n) => (n, Some(n)),
+                            RepetitionRange::AtLeast(n) => (n, None),
+                            RepetitionRange::Bounded(n, m) => (n, Some(m)),
+                        };
+
+                        let mut out = Vec::with_capacity(n as usize + 1);
+
+                        for _ in 0..n {
+                            out.push(mir.clone());
+                        }
+
+                        match m {
+                            None => out.push(Mir::Loop(Box::new(mir))),
+                            Some(m) if m > n => {
+                                // Nest the optional repetitions, so that `x{1,3}`
+                                // becomes `x(x(x)?)?`.
+                                let mut maybe = Mir::Maybe(Box::new(mir.clone()));
+
+                                for _ in n + 1..m {
+                                    maybe = Mir::Maybe(Box::new(Mir::Concat(vec![mir.clone(), maybe])));
+                                }
+
+                                out.push(maybe);
+                            },
+                            Some(_) => (),
+                        }
+
+                        Ok(match out.len() {
+                            0 => Mir::Empty,
+                            1 => out.remove(0),
+                            _ => Mir::Concat(out),
+                        })
                     },
                 }
             },
@@ -91,26 +234,111 @@ impl TryFrom<Hir> for Mir {
                 Err("#[regex]: word boundaries are currently unsupported.")?
             },
             HirKind::Anchor(_) => {
-                Err("#[regex]: anchors in #[regex] are currently unsupported.")?
+                Err("#[regex]: anchors are only supported as `^` at the start, \
+                     and `$` at the end of the pattern.")?
             },
         }
     }
 }
 
+/// `^` and `$` anchors found at the start and the end of a pattern.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Anchors {
+    pub start: bool,
+    pub end: bool,
+}
+
+fn strip_anchors(hir: Hir) -> (Hir, Anchors) {
+    let is_anchor = |hir: &Hir, anchor: Anchor| matches!(hir.kind(), HirKind::Anchor(a) if *a == anchor);
+
+    let mut concat = match matches!(hir.kind(), HirKind::Concat(_)) {
+        true => match hir.into_kind() {
+            HirKind::Concat(concat) => concat,
+            _ => unreachable!(),
+        },
+        false => vec![hir],
+    };
+    let anchors = Anchors {
+        start: concat.first().is_some_and(|hir| is_anchor(hir, Anchor::StartText)),
+        end: concat.last().is_some_and(|hir| is_anchor(hir, Anchor::EndText)),
+    };
+
+    if anchors.end {
+        concat.pop();
+    }
+    if anchors.start {
+        concat.remove(0);
+    }
+
+    (Hir::concat(concat), anchors)
+}
+
 impl<Leaf: Disambiguate + Debug> Graph<Leaf> {
     pub fn regex(&mut self, utf8: bool, source: &str, then: NodeId) -> Result<(usize, NodeId)> {
-        let mut builder = ParserBuilder::new();
+        let mir = self.mir(utf8, source)?;
 
-        if !utf8 {
-            builder.allow_invalid_utf8(true).unicode(false);
-        }
+        Ok(self.parse_mir(mir, then, None, None))
+    }
 
-        let hir = builder.build().parse(source)?;
-        let mir = Mir::try_from(hir.clone())?;
+    /// Like `regex`, but rejecting patterns that can match the empty string, such as
+    /// `[a-z]*`, which can't be used for anything consuming input on its own.
+    pub fn non_empty_regex(&mut self, utf8: bool, source: &str, then: NodeId) -> Result<(usize, NodeId)> {
+        let mir = self.mir(utf8, source)?;
+
+        if mir.nullable() {
+            return Err(Mir::nullable_error());
+        }
 
         Ok(self.parse_mir(mir, then, None, None))
     }
 
+    /// Like `regex`, but for the pattern of a `(?=...)` lookahead, which has to match
+    /// a fixed number of bytes. Returns that number instead of the priority.
+    pub fn lookahead(&mut self, utf8: bool, source: &str, then: NodeId) -> Result<(usize, NodeId)> {
+        let mir = self.mir(utf8, source)?;
+        let len = match mir.fixed_len() {
+            Some(0) => Err("#[regex]: lookahead can match empty string.")?,
+            Some(len) => len,
+            None => Err("#[regex]: lookahead has to match a fixed number of bytes.")?,
+        };
+
+        let (_, id) = self.parse_mir(mir, then, None, None);
+
+        Ok((len, id))
+    }
+
+    /// Like `non_empty_regex`, but also accepting `^` at the start and `$` at the end
+    /// of the pattern, which are returned for the caller to handle.
+    pub fn anchored_regex(&mut self, utf8: bool, source: &str, then: NodeId) -> Result<(usize, NodeId, Anchors)> {
+        let (hir, anchors) = strip_anchors(hir(utf8, source)?);
+        let mir = self.lower(hir)?;
+
+        if mir.nullable() {
+            return Err(Mir::nullable_error());
+        }
+
+        let (len, id) = self.parse_mir(mir, then, None, None);
+
+        Ok((len, id, anchors))
+    }
+
+    fn mir(&self, utf8: bool, source: &str) -> Result<Mir> {
+        self.lower(hir(utf8, source)?)
+    }
+
+    fn lower(&self, hir: Hir) -> Result<Mir> {
+        let mir = Mir::try_from(hir)?;
+
+        if mir.is_lazy() {
+            return Err(Mir::lazy_error());
+        }
+
+        match self.ascii {
+            true => mir.into_ascii(),
+            false => Ok(mir),
+        }
+    }
+
     fn parse_mir(
         &mut self,
         mir: Mir,
@@ -140,6 +368,9 @@ impl<Leaf: Disambiguate + Debug> Graph<Leaf> {
                 (0, id)
             },
             Mir::Alternation(alternation) => {
+                // Merging the alternatives into one fork merges their branches on every
+                // shared byte, so literals end up in a trie with their prefixes in common,
+                // while identical remainders are deduplicated when pushed on the graph.
                 let mut fork = Fork::new().miss(miss);
                 let mut shortest = if alternation.len() > 0 { usize::max_value() } else { 0 };
 
@@ -154,6 +385,42 @@ impl<Leaf: Disambiguate + Debug> Graph<Leaf> {
 
                 (shortest, self.insert_or_push(reserved, fork))
             }
+            Mir::Lazy { class, terminator } => {
+                // Match the terminator with a KMP automaton, with a state for every
+                // prefix of it found so far. Any other character of the class resets it.
+                let states = (0..terminator.len()).map(|_| self.reserve()).collect::<Vec<_>>();
+                let ids = states.iter().map(ReservedId::get).collect::<Vec<_>>();
+
+                let (_, other) = self.parse_mir(Mir::Class(class), ids[0], None, None);
+                let other = self.fork_off(other);
+                let mut entry = None;
+
+                for (found, state) in states.into_iter().enumerate() {
+                    let mut fork = other.clone();
+
+                    for &byte in &terminator {
+                        let next = match kmp_next(&terminator, found, byte) {
+                            next if next == terminator.len() => then,
+                            next => ids[next],
+                        };
+
+                        fork.add_branch(byte, next, self);
+                    }
+
+                    if found == 0 {
+                        entry = Some(fork.clone().miss(miss));
+                    }
+
+                    self.insert(state, fork);
+                }
+
+                let entry = entry.expect("Terminator can't be empty");
+
+                (terminator.len() * 2, self.insert_or_push(reserved, entry))
+            },
+            Mir::LazyLoop(_) | Mir::LazyMaybe(_) => {
+                unreachable!("Non-greedy repetitions are resolved while building Mir");
+            },
             Mir::Literal(literal) => {
                 let pattern = match literal {
                     Literal::Unicode(unicode) => {
@@ -309,12 +576,301 @@ impl<Leaf: Disambiguate + Debug> Graph<Leaf> {
     }
 }
 
+/// Replace every non-greedy repetition in `concat` with a `Mir` matching the
+/// shortest input, using the literal directly following it as the terminator.
+fn resolve_lazy(concat: &mut Vec<Mir>) -> Result<()> {
+    while let Some(idx) = concat.iter().position(Mir::is_lazy) {
+        let mut terminator = Vec::new();
+        let mut end = idx + 1;
+
+        while let Some(mir) = concat.get(end) {
+            match mir {
+                Mir::Literal(Literal::Unicode(u)) => {
+                    terminator.extend_from_slice(u.encode_utf8(&mut [0; 4]).as_bytes());
+                },
+                Mir::Literal(Literal::Byte(byte)) => terminator.push(*byte),
+                Mir::Class(Class::Unicode(class)) if is_one_char(class) => {
+                    let c = class.ranges()[0].start();
+
+                    terminator.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                },
+                Mir::Class(Class::Bytes(class))
+                    if class.ranges().len() == 1 && class.ranges()[0].start() == class.ranges()[0].end() =>
+                {
+                    terminator.push(class.ranges()[0].start());
+                },
+                _ => break,
+            }
+            end += 1;
+        }
+
+        let class = match &concat[idx] {
+            Mir::LazyLoop(mir) | Mir::LazyMaybe(mir) if !terminator.is_empty() => match **mir {
+                Mir::Class(ref class) => class.clone(),
+                Mir::Literal(Literal::Unicode(c)) => {
+                    Class::Unicode(ClassUnicode::new(Some(ClassUnicodeRange::new(c, c))))
+                },
+                Mir::Literal(Literal::Byte(byte)) => {
+                    Class::Bytes(ClassBytes::new(Some(ClassBytesRange::new(byte, byte))))
+                },
+                _ => return Err(Mir::lazy_error()),
+            },
+            _ => return Err(Mir::lazy_error()),
+        };
+
+        let literal = terminator.iter().map(|&byte| Mir::Literal(Literal::Byte(byte))).collect::<Vec<_>>();
+        let uniform = terminator.iter().all(|&byte| byte == terminator[0]);
+
+        let mir = match concat[idx] {
+            Mir::LazyLoop(_) => {
+                // Non-ASCII characters in the class can share bytes with a non-ASCII terminator
+                let overlapping = match class {
+                    Class::Unicode(ref class) => {
+                        terminator.iter().any(|&byte| byte >= 128)
+                            && class.ranges().iter().any(|range| range.end() as u32 >= 128)
+                    },
+                    Class::Bytes(_) => false,
+                };
+                let contained = terminator
+                    .iter()
+                    .filter(|&&byte| match class {
+                        Class::Unicode(ref class) => byte < 128 && contains_unicode(class, byte),
+                        Class::Bytes(ref class) => contains_byte(class, byte),
+                    })
+                    .count();
+
+                // Without any of the terminator in the class, the loop can't run past it
+                if contained == 0 && !overlapping {
+                    concat[idx] = Mir::Loop(Box::new(Mir::Class(class)));
+                    continue;
+                }
+
+                if overlapping || contained < terminator.len() {
+                    return Err("#[regex]: a non-greedy `*?` or `+?` has to match either all characters \
+                                of the literal following it, or none. For Unicode classes that literal \
+                                has to be ASCII.".into());
+                }
+
+                Mir::Lazy {
+                    class: without(class, &terminator),
+                    terminator,
+                }
+            },
+            // `x??y` only consumes `x` if `y` doesn't match right away. This can only
+            // happen for the first byte of `y` if it consists of that byte repeated.
+            _ => {
+                let class = match uniform {
+                    true => without(class, &terminator[..1]),
+                    false => class,
+                };
+                let mut longer = vec![Mir::Class(class)];
+
+                longer.extend(literal.iter().cloned());
+
+                Mir::Alternation(vec![Mir::Concat(literal), Mir::Concat(longer)])
+            },
+        };
+
+        concat.splice(idx..end, Some(mir));
+    }
+
+    Ok(())
+}
+
+/// Compute the number of terminator bytes matched after reading `byte`, with `found`
+/// bytes matched before it.
+fn kmp_next(terminator: &[u8], found: usize, byte: u8) -> usize {
+    let mut read = terminator[..found].to_vec();
+
+    read.push(byte);
+
+    (0..=read.len().min(terminator.len()))
+        .rev()
+        .find(|&len| read.ends_with(&terminator[..len]))
+        .unwrap_or(0)
+}
+
+fn contains_unicode(class: &ClassUnicode, byte: u8) -> bool {
+    let c = byte as char;
+
+    class.iter().any(|range| range.start() <= c && c <= range.end())
+}
+
+fn contains_byte(class: &ClassBytes, byte: u8) -> bool {
+    class.iter().any(|range| range.start() <= byte && byte <= range.end())
+}
+
+/// Remove the given bytes from a class, as ASCII characters for Unicode classes.
+fn without(class: Class, bytes: &[u8]) -> Class {
+    match class {
+        Class::Unicode(mut class) => {
+            let bytes = bytes.iter().map(|&byte| ClassUnicodeRange::new(byte as char, byte as char));
+
+            class.difference(&ClassUnicode::new(bytes));
+
+            Class::Unicode(class)
+        },
+        Class::Bytes(mut class) => {
+            let bytes = bytes.iter().map(|&byte| ClassBytesRange::new(byte, byte));
+
+            class.difference(&ClassBytes::new(bytes));
+
+            Class::Bytes(class)
+        },
+    }
+}
+
+/// Remove all non-ASCII characters, or bytes, from a class.
+fn ascii_only(class: Class) -> Result<Class> {
+    let class = match class {
+        Class::Unicode(mut class) => {
+            class.intersect(&ClassUnicode::new(Some(ClassUnicodeRange::new('\0', '\x7F'))));
+
+            Class::Unicode(class)
+        },
+        Class::Bytes(mut class) => {
+            class.intersect(&ClassBytes::new(Some(ClassBytesRange::new(0, 0x7F))));
+
+            Class::Bytes(class)
+        },
+    };
+
+    let empty = match class {
+        Class::Unicode(ref class) => class.iter().next().is_none(),
+        Class::Bytes(ref class) => class.iter().next().is_none(),
+    };
+
+    match empty {
+        true => Err("#[logos(ascii)]: character class doesn't contain any ASCII characters.".into()),
+        false => Ok(class),
+    }
+}
+
+fn hir(utf8: bool, source: &str) -> Result<Hir> {
+    let mut builder = ParserBuilder::new();
+
+    if !utf8 {
+        // For byte strings `.` matches any byte, including `\n`
+        builder.allow_invalid_utf8(true).unicode(false).dot_matches_new_line(true);
+    }
+
+    if !cfg!(feature = "unicode_properties") && uses_unicode_properties(source) {
+        Err("#[regex]: Unicode property classes like \\p{...} require the \
+             `unicode_properties` feature to be enabled.")?;
+    }
+
+    Ok(builder.build().parse(&expand_escapes(source, utf8)?)?)
+}
+
+/// For a byte string pattern that can match invalid UTF-8, describe the bytes in
+/// it responsible, such as `\xFF` or `\x80-\xFF`. This errs on the side of caution,
+/// so a pattern spelling out a valid sequence, like `\xC3\xA9`, is reported as well.
+/// Patterns failing to parse are left for `Graph::regex` to report.
+pub fn invalid_utf8(source: &str) -> Option<String> {
+    fn find(hir: &Hir) -> Option<String> {
+        match hir.kind() {
+            HirKind::Literal(Literal::Byte(byte)) if *byte >= 0x80 => {
+                Some(format!("\\x{:02X}", byte))
+            },
+            HirKind::Class(Class::Bytes(class)) => {
+                class.iter().find(|range| range.end() >= 0x80).map(|range| {
+                    match range.start() == range.end() {
+                        true => format!("\\x{:02X}", range.start()),
+                        false => format!("\\x{:02X}-\\x{:02X}", range.start(), range.end()),
+                    }
+                })
+            },
+            HirKind::Repetition(repetition) => find(&repetition.hir),
+            HirKind::Group(group) => find(&group.hir),
+            HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().find_map(find),
+            _ => None,
+        }
+    }
+
+    let hir = hir(false, source).ok()?;
+
+    match hir.is_always_utf8() {
+        true => None,
+        false => Some(find(&hir).unwrap_or_else(|| String::from("non-UTF-8 bytes"))),
+    }
+}
+
+fn is_one_char(class: &ClassUnicode) -> bool {
+    class.ranges().len() == 1 && class.ranges()[0].start() == class.ranges()[0].end()
+}
+
+fn uses_unicode_properties(source: &str) -> bool {
+    let mut chars = source.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some('p') | Some('P') = chars.next() {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Expand the escapes not supported by `regex_syntax`. `\R` becomes an alternation
+/// matching any line break, with `\r\n` matched as a single unit, including only the
+/// ASCII line breaks for byte strings. `\0` becomes `\x00`, as long as it isn't
+/// followed by another digit, since octal escapes aren't supported.
+fn expand_escapes(source: &str, utf8: bool) -> Result<String> {
+    let line_break = match utf8 {
+        true => r"(?:\r\n|[\n\x0B\x0C\r\x{85}\x{2028}\x{2029}])",
+        false => r"(?:\r\n|[\n\x0B\x0C\r])",
+    };
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut class_depth = 0usize;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('R') if class_depth > 0 => {
+                    Err("#[regex]: \\R can't be used inside a character class.")?
+                },
+                Some('R') => out.push_str(line_break),
+                Some('0') if !matches!(chars.peek(), Some(c) if c.is_ascii_digit()) => {
+                    out.push_str(r"\x00")
+                },
+                Some(escaped) => {
+                    out.push('\\');
+                    out.push(escaped);
+                },
+                None => out.push('\\'),
+            },
+            '[' => {
+                class_depth += 1;
+                out.push('[');
+
+                // A `]` right at the start of a class is a literal
+                if chars.peek() == Some(&'^') {
+                    out.push(chars.next().unwrap());
+                }
+                if chars.peek() == Some(&']') {
+                    out.push(chars.next().unwrap());
+                }
+            },
+            ']' if class_depth > 0 => {
+                class_depth -= 1;
+                out.push(']');
+            },
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
 fn is_ascii(class: &ClassUnicode) -> bool {
     class.iter().all(|range| {
         let start = range.start() as u32;
         let end = range.end() as u32;
 
-        start < 128 && (end < 128 || end == 0x0010_FFFF)
+        start < 128 && end < 128
     })
 }
 
@@ -327,7 +883,7 @@ fn is_one_ascii(class: &ClassUnicode) -> bool {
     let start = range.start() as u32;
     let end = range.end() as u32;
 
-    start < 128 && (end < 128 || end == 0x0010_FFFF)
+    start < 128 && end < 128
 }
 
 #[cfg(test)]
@@ -404,6 +960,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn counted_repetition() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let (len, parsed) = graph.regex(true, "a{3}", leaf).unwrap();
+
+        assert_eq!(len, 6);
+        assert_eq!(
+            graph[parsed],
+            Node::Rope(Rope::new("aaa", leaf)),
+        )
+    }
+
+    #[test]
+    #[cfg(not(feature = "unicode_properties"))]
+    fn unicode_properties_disabled() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+
+        assert!(graph.regex(true, r"\p{Greek}+", leaf).is_err());
+        assert!(graph.regex(true, r"\\p", leaf).is_ok());
+    }
+
     #[test]
     fn priorities() {
         let mut graph = Graph::new();
@@ -417,6 +998,11 @@ mod tests {
             ("(foo)+", 6),
             ("foobar", 12),
             ("(fooz|bar)+qux", 12),
+            ("[0-9a-f]{4}", 4),
+            ("a{3}", 6),
+            ("a{2,}", 4),
+            ("(ab){1,3}", 4),
+            ("'.'", 5),
         ];
 
         for (regex, expected) in regexes.iter() {
@@ -424,4 +1010,181 @@ mod tests {
             assert_eq!(len, *expected);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn kmp() {
+        assert_eq!(kmp_next(b"-->", 0, b'-'), 1);
+        assert_eq!(kmp_next(b"-->", 1, b'-'), 2);
+        assert_eq!(kmp_next(b"-->", 2, b'-'), 2);
+        assert_eq!(kmp_next(b"-->", 2, b'>'), 3);
+        assert_eq!(kmp_next(b"-->", 1, b'>'), 0);
+        assert_eq!(kmp_next(b"abab", 3, b'a'), 1);
+        assert_eq!(kmp_next(b"abab", 3, b'b'), 4);
+    }
+
+    #[test]
+    fn lazy_repetitions() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+
+        assert_eq!(graph.regex(true, r"/\*.*?\*/", leaf).map(|(len, _)| len).ok(), Some(8));
+        assert_eq!(graph.regex(true, "a??ab", leaf).map(|(len, _)| len).ok(), Some(4));
+        assert!(graph.regex(true, "a.*?", leaf).is_err());
+        assert!(graph.regex(true, "(foo)*?bar", leaf).is_err());
+        assert!(graph.regex(true, "a*?|b", leaf).is_err());
+        assert!(graph.regex(true, "[a-z]*?ab1", leaf).is_err());
+        assert!(graph.regex(true, "a{2,3}?b", leaf).is_err());
+        assert!(graph.regex(true, "<(?s:.)*?é", leaf).is_err());
+        assert!(graph.regex(true, "<[a-z]*?é", leaf).is_ok());
+    }
+
+    #[test]
+    fn non_empty() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let non_empty = |graph: &mut Graph<_>, source| graph.non_empty_regex(true, source, leaf).is_ok();
+
+        assert!(!non_empty(&mut graph, "[a-z]*"));
+        assert!(!non_empty(&mut graph, "a?b*"));
+        assert!(!non_empty(&mut graph, "(ab)?c*d?"));
+        assert!(!non_empty(&mut graph, "(a+|b?)"));
+        assert!(!non_empty(&mut graph, "(a|)"));
+        assert!(!non_empty(&mut graph, "a{0,3}"));
+        assert!(non_empty(&mut graph, "a?b"));
+        assert!(non_empty(&mut graph, "x?[a-z]+"));
+        assert!(non_empty(&mut graph, "(a*|b)c"));
+        assert!(non_empty(&mut graph, "a??ab"));
+        assert!(graph.anchored_regex(true, "^[a-z]*$", leaf).is_err());
+        assert!(graph.anchored_regex(true, "^", leaf).is_err());
+    }
+
+    #[test]
+    fn lookahead_len() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let len = |graph: &mut Graph<_>, source| graph.lookahead(true, source, leaf).map(|(len, _)| len).ok();
+
+        assert_eq!(len(&mut graph, "[0-9]"), Some(1));
+        assert_eq!(len(&mut graph, "px|em"), Some(2));
+        assert_eq!(len(&mut graph, "ł[ąę]"), Some(4));
+        assert_eq!(len(&mut graph, "[aą]"), None);
+        assert_eq!(len(&mut graph, "a+"), None);
+        assert_eq!(len(&mut graph, "a?"), None);
+        assert_eq!(len(&mut graph, ""), None);
+    }
+
+    #[test]
+    fn anchors() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let anchors = |graph: &mut Graph<_>, source| {
+            graph.anchored_regex(true, source, leaf).map(|(_, _, anchors)| anchors).ok()
+        };
+
+        assert_eq!(anchors(&mut graph, "foo"), Some(Anchors { start: false, end: false }));
+        assert_eq!(anchors(&mut graph, "^foo"), Some(Anchors { start: true, end: false }));
+        assert_eq!(anchors(&mut graph, "(foo|bar)$"), Some(Anchors { start: false, end: true }));
+        assert_eq!(anchors(&mut graph, "^a+$"), Some(Anchors { start: true, end: true }));
+        assert_eq!(anchors(&mut graph, "foo|^bar"), None);
+        assert_eq!(anchors(&mut graph, "foo$|bar"), None);
+        assert_eq!(anchors(&mut graph, "(?m)^foo"), None);
+        assert!(graph.regex(true, "^foo", leaf).is_err());
+    }
+
+    #[test]
+    fn line_breaks() {
+        let line_break = r"(?:\r\n|[\n\x0B\x0C\r\x{85}\x{2028}\x{2029}])";
+
+        assert_eq!(expand_escapes(r"a\Rb", true).unwrap(), format!("a{}b", line_break));
+        assert_eq!(expand_escapes(r"a\\R", true).unwrap(), r"a\\R");
+        assert_eq!(expand_escapes(r"[\]]\R", true).unwrap(), format!(r"[\]]{}", line_break));
+        assert_eq!(expand_escapes(r"[]\R]", true).ok(), None);
+        assert_eq!(expand_escapes(r"[^\R]", true).ok(), None);
+        assert_eq!(expand_escapes(r"\R", false).unwrap(), r"(?:\r\n|[\n\x0B\x0C\r])");
+    }
+
+    #[test]
+    fn nul_escape() {
+        assert_eq!(expand_escapes(r"a\0", true).unwrap(), r"a\x00");
+        assert_eq!(expand_escapes(r"[\0-\x1F]", false).unwrap(), r"[\x00-\x1F]");
+        assert_eq!(expand_escapes(r"\\0", true).unwrap(), r"\\0");
+        assert_eq!(expand_escapes(r"\01", true).unwrap(), r"\01");
+    }
+
+    #[test]
+    fn invalid_utf8_bytes() {
+        assert_eq!(invalid_utf8(r"[a-z]+|\n"), None);
+        assert_eq!(invalid_utf8(r"ab\xFF"), Some(r"\xFF".into()));
+        assert_eq!(invalid_utf8(r"a(b[\x00-\x1F\x80-\xBF])*"), Some(r"\x80-\xBF".into()));
+        assert_eq!(invalid_utf8(r"a."), Some(r"\x00-\xFF".into()));
+    }
+
+    #[test]
+    fn ascii() {
+        let mut graph = Graph::new();
+
+        graph.ascii();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let (len, parsed) = graph.regex(true, "[^a-z]", leaf).unwrap();
+
+        assert_eq!(len, 1);
+        assert_eq!(
+            graph[parsed],
+            Node::Fork(
+                Fork::new()
+                    .branch(0x00..=b'`', leaf)
+                    .branch(b'{'..=0x7F, leaf)
+            ),
+        );
+
+        assert!(graph.regex(true, r"caf\xE9", leaf).is_err());
+        assert!(graph.regex(true, r"[\xE9-\xFF]", leaf).is_err());
+    }
+
+    /// Count the states of the machine reachable from `id`, a byte of a rope
+    /// being a state of its own.
+    fn states(graph: &mut Graph<&'static str>, id: NodeId) -> usize {
+        graph.shake(Some(id));
+        graph
+            .nodes()
+            .iter()
+            .flatten()
+            .map(|node| match node {
+                Node::Rope(rope) => rope.pattern.len(),
+                Node::Fork(_) => 1,
+                Node::Leaf(_) => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn alternation_trie() {
+        let words = ["function", "fallback", "false", "finally", "for", "foreach"];
+        let naive = words.iter().map(|word| word.len()).sum::<usize>();
+
+        let mut graph = Graph::new();
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let (_, id) = graph.regex(true, &format!("({})", words.join("|")), leaf).unwrap();
+
+        assert_eq!(naive, 38);
+        assert_eq!(states(&mut graph, id), 25);
+    }
+
+    #[test]
+    fn alternation_shared_suffix() {
+        let words = ["sing", "ring", "bring", "string", "spring"];
+        let naive = words.iter().map(|word| word.len()).sum::<usize>();
+
+        let mut graph = Graph::new();
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let (_, id) = graph.regex(true, &format!("({})", words.join("|")), leaf).unwrap();
+
+        assert_eq!(naive, 25);
+        assert_eq!(states(&mut graph, id), 11);
+    }
+}
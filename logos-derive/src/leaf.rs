@@ -3,15 +3,35 @@ use std::fmt::{self, Debug};
 
 use syn::Ident;
 
+use crate::error::{Error, SpannedError};
 use crate::graph::{Node, Disambiguate};
 
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Clone, PartialEq)]
 pub enum Leaf {
     Trivia,
+    /// Dead end produced by `#[token(..., boundary)]`, loses to any other leaf.
+    Reject,
     Token {
         ident: Ident,
         priority: usize,
         callback: Option<Ident>,
+        field: bool,
+        /// Bytes matched by a trailing `(?=...)`, which aren't part of the token.
+        lookahead: usize,
+        /// Predicate consuming further input, from `#[regex(..., class = "...")]`.
+        class: Option<Ident>,
+        /// Table of keywords reclassifying the token, from `#[regex(..., keywords = "...")]`.
+        keywords: Option<Ident>,
+        /// Terminator consumed through after a match, from `#[regex(..., until = "...")]`.
+        until: Option<Vec<u8>>,
+        /// Opening sequence nesting within `until`, from `#[regex(..., nested = "...")]`.
+        nested: Option<Vec<u8>>,
+    },
+    /// Token of a definition ending with `$`, only produced at the end of input.
+    /// Anywhere else the leaf it displaced when merging is used instead, if any.
+    AtEnd {
+        token: Box<Leaf>,
+        otherwise: Option<Box<Leaf>>,
     },
 }
 
@@ -21,13 +41,67 @@ impl Leaf {
             ident: ident.clone(),
             priority: 0,
             callback: None,
+            field: false,
+            lookahead: 0,
+            class: None,
+            keywords: None,
+            until: None,
+            nested: None,
+        }
+    }
+
+    pub fn field(mut self, has_field: bool) -> Self {
+        match self {
+            Leaf::Token { ref mut field, .. } => *field = has_field,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
         }
+        self
     }
 
     pub fn callback(mut self, cb: Option<Ident>) -> Self {
         match self {
             Leaf::Token { ref mut callback, .. } => *callback = cb,
-            Leaf::Trivia => panic!("Oh no :("),
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
+        }
+        self
+    }
+
+    pub fn lookahead(mut self, len: usize) -> Self {
+        match self {
+            Leaf::Token { ref mut lookahead, .. } => *lookahead = len,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
+        }
+        self
+    }
+
+    pub fn class(mut self, predicate: Option<Ident>) -> Self {
+        match self {
+            Leaf::Token { ref mut class, .. } => *class = predicate,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
+        }
+        self
+    }
+
+    pub fn keywords(mut self, table: Option<Ident>) -> Self {
+        match self {
+            Leaf::Token { ref mut keywords, .. } => *keywords = table,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
+        }
+        self
+    }
+
+    pub fn until(mut self, terminator: Option<Vec<u8>>) -> Self {
+        match self {
+            Leaf::Token { ref mut until, .. } => *until = terminator,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
+        }
+        self
+    }
+
+    pub fn nested(mut self, opening: Option<Vec<u8>>) -> Self {
+        match self {
+            Leaf::Token { ref mut nested, .. } => *nested = opening,
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
         }
         self
     }
@@ -35,23 +109,120 @@ impl Leaf {
     pub fn priority(mut self, prio: usize) -> Self {
         match self {
             Leaf::Token { ref mut priority, .. } => *priority = prio,
-            Leaf::Trivia => panic!("Oh no :("),
+            Leaf::Trivia | Leaf::Reject | Leaf::AtEnd { .. } => panic!("Oh no :("),
         }
         self
     }
 }
 
+impl Leaf {
+    pub fn at_end(self) -> Self {
+        Leaf::AtEnd {
+            token: Box::new(self),
+            otherwise: None,
+        }
+    }
+
+    /// Identifiers of the variants this leaf can produce.
+    pub fn idents(&self) -> Vec<&Ident> {
+        match self {
+            Leaf::Token { ident, .. } => vec![ident],
+            Leaf::AtEnd { token, otherwise } => {
+                let mut idents = token.idents();
+
+                if let Some(otherwise) = otherwise {
+                    idents.extend(otherwise.idents());
+                }
+                idents
+            },
+            Leaf::Trivia | Leaf::Reject => Vec::new(),
+        }
+    }
+
+    /// Check if the `token` leaf can be produced by this leaf.
+    pub fn produces(&self, token: &Leaf) -> bool {
+        match self {
+            Leaf::AtEnd { token: at_end, otherwise } => {
+                at_end.produces(token) || matches!(otherwise, Some(leaf) if leaf.produces(token))
+            },
+            leaf => leaf == token,
+        }
+    }
+
+    /// Produce errors for two leaves that can match the same input with equal priority,
+    /// unless they are effectively the same leaf.
+    pub fn tie_error(left: &Leaf, right: &Leaf, example: Option<&[u8]>) -> Vec<SpannedError> {
+        match (left, right) {
+            (
+                Leaf::Token { ident: a, callback: cb_a, priority, .. },
+                Leaf::Token { ident: b, callback: cb_b, .. },
+            ) if a != b || cb_a != cb_b => {
+                let example = match example.map(std::str::from_utf8) {
+                    Some(Ok(string)) => format!(", for example {:?}", string),
+                    Some(Err(_)) => format!(", for example {:02X?}", example.unwrap()),
+                    None => String::new(),
+                };
+
+                vec![
+                    Error::new(format!(
+                        "A definition of variant `{}` can match the same input as another \
+                         definition of variant `{}`{}, with the same priority ({}).\n\n\
+                         hint: Consider giving one definition a higher priority: \
+                         #[regex(..., priority = {})], or acknowledge the ambiguity: \
+                         #[regex(..., allow(ambiguity))]",
+                        a, b, example, priority, priority + 1,
+                    )).span(a.span()),
+                    Error::new(format!("Conflicting definition of variant `{}`:", b)).span(b.span()),
+                ]
+            },
+            (Leaf::AtEnd { token, .. }, other) | (other, Leaf::AtEnd { token, .. }) => {
+                Leaf::tie_error(token, other, example)
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl Disambiguate for Leaf {
     fn cmp(left: &Leaf, right: &Leaf) -> Ordering {
         match (left, right) {
+            (Leaf::AtEnd { token, .. }, _) => Disambiguate::cmp(&**token, right),
+            (_, Leaf::AtEnd { token, .. }) => Disambiguate::cmp(left, &**token),
             (Leaf::Token { priority: left, .. }, Leaf::Token { priority: right, .. }) => {
                 Ord::cmp(left, right)
             },
             (Leaf::Token { .. }, Leaf::Trivia) => Ordering::Greater,
             (Leaf::Trivia, Leaf::Token { .. }) => Ordering::Less,
             (Leaf::Trivia, Leaf::Trivia) => Ordering::Equal,
+            (Leaf::Reject, Leaf::Reject) => Ordering::Equal,
+            (Leaf::Reject, _) => Ordering::Less,
+            (_, Leaf::Reject) => Ordering::Greater,
         }
     }
+
+    fn merge(left: &Leaf, right: &Leaf) -> Option<Leaf> {
+        let (token, otherwise, other) = match (left, right) {
+            (Leaf::AtEnd { .. }, Leaf::AtEnd { .. }) => return None,
+            (Leaf::AtEnd { token, otherwise }, other) | (other, Leaf::AtEnd { token, otherwise }) => {
+                (token, otherwise, other)
+            },
+            _ => return None,
+        };
+
+        if Disambiguate::cmp(&**token, other) != Ordering::Greater {
+            return None;
+        }
+
+        let otherwise = match otherwise {
+            Some(previous) if Disambiguate::cmp(&**previous, other) != Ordering::Less => previous.clone(),
+            _ => Box::new(other.clone()),
+        };
+
+        Some(Leaf::AtEnd {
+            token: token.clone(),
+            otherwise: Some(otherwise),
+        })
+    }
 }
 
 impl From<Leaf> for Node<Leaf> {
@@ -64,6 +235,11 @@ impl Debug for Leaf {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Leaf::Trivia => f.write_str("<trivia>"),
+            Leaf::Reject => f.write_str("<reject>"),
+            Leaf::AtEnd { token, otherwise: None } => write!(f, "{:?} at end", token),
+            Leaf::AtEnd { token, otherwise: Some(otherwise) } => {
+                write!(f, "{:?} at end, else {:?}", token, otherwise)
+            },
             Leaf::Token { ident, callback, .. } => {
                  write!(f, "::{}", ident)?;
 
@@ -15,17 +15,18 @@ mod graph;
 mod util;
 mod leaf;
 
-use error::Error;
-use generator::Generator;
-use graph::{Graph, Fork, Rope};
+use error::{Error, Warning};
+use generator::{Dispatch, Generator, Inline, Optimize};
+use graph::{Graph, Fork, Node, NodeId, Rope, invalid_utf8};
 use leaf::Leaf;
-use util::{Literal, Definition};
+use util::{Literal, Lookahead, Definition, Subpatterns};
 
 use beef::lean::Cow;
+use fnv::FnvHashMap as Map;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{Ident, Fields, ItemEnum};
+use syn::{Ident, Fields, GenericParam, ItemEnum};
 use syn::spanned::Spanned;
 
 enum Mode {
@@ -35,7 +36,7 @@ enum Mode {
 
 #[proc_macro_derive(
     Logos,
-    attributes(logos, extras, error, end, token, regex, extras, callback)
+    attributes(logos, extras, error, end, token, regex, extras, callback, skip)
 )]
 pub fn logos(input: TokenStream) -> TokenStream {
     let item: ItemEnum = syn::parse(input).expect("#[token] can be only applied to enums");
@@ -46,10 +47,39 @@ pub fn logos(input: TokenStream) -> TokenStream {
 
     let mut extras: Option<Ident> = None;
     let mut error = None;
+    let mut error_ty = None;
     let mut end = None;
-    let mut mode = Mode::Utf8;
+    let mut implicit_error = None;
+    let mut implicit_end = None;
+    let mut utf8_only = false;
+    let mut ascii = false;
     let mut errors = Vec::new();
     let mut trivia = Some((true, Cow::borrowed(r"[ \t\f]"), Span::call_site()));
+    let mut skips = Vec::new();
+    let mut subpatterns = Subpatterns::default();
+    let mut unicode = false;
+    let mut serde = false;
+    let mut dispatch = Dispatch::Branch;
+    let mut optimize = Optimize::Speed;
+    let mut ident_class = String::from(util::DEFAULT_BOUNDARY);
+    let mut dump = std::env::var("LOGOS_DUMP").map(|var| var == "dot").unwrap_or(false);
+    let mut print_priorities = std::env::var("LOGOS_PRINT_PRIORITIES").map(|var| var == "1").unwrap_or(false);
+
+    // A lifetime on the enum is tied to the source, so tokens can borrow from it
+    let lifetime = match item.generics.params.iter().collect::<Vec<_>>()[..] {
+        [] => None,
+        [GenericParam::Lifetime(def)] if def.bounds.is_empty() => Some(&def.lifetime),
+        _ => {
+            errors.push(Error::new(
+                "Token enums can only have a single lifetime parameter, which is tied to the source."
+            ).span(item.generics.span()));
+
+            None
+        },
+    };
+    if let Some(clause) = &item.generics.where_clause {
+        errors.push(Error::new("Token enums can't have a where clause.").span(clause.span()));
+    }
 
     for attr in &item.attrs {
         if let Some(ext) = util::value_from_attr("extras", attr) {
@@ -58,16 +88,131 @@ pub fn logos(input: TokenStream) -> TokenStream {
             }
         }
 
+        if let Some(skip) = util::value_from_attr::<Literal>("skip", attr) {
+            skips.push(match skip {
+                Literal::Utf8(string, span) => (true, string, span),
+                Literal::Bytes(bytes, span) => (false, util::bytes_to_regex_string(&bytes), span),
+            });
+        }
+
         if let Some(nested) = util::read_attr("logos", attr) {
             for item in nested {
+                if let util::NestedMeta::Meta(util::Meta::Path(ref path)) = item {
+                    if path.is_ident("unicode") {
+                        unicode = true;
+                        continue;
+                    }
+                    if path.is_ident("utf8") {
+                        utf8_only = true;
+                        continue;
+                    }
+                    if path.is_ident("ascii") {
+                        ascii = true;
+                        continue;
+                    }
+                    if path.is_ident("dump") {
+                        dump = true;
+                        continue;
+                    }
+                    if path.is_ident("print_priorities") {
+                        print_priorities = true;
+                        continue;
+                    }
+                    if path.is_ident("serde") {
+                        if !cfg!(feature = "serde") {
+                            errors.push(
+                                Error::new("#[logos(serde)] requires the `serde` feature of logos.")
+                                    .span(path.span())
+                            );
+                        }
+                        serde = true;
+                        continue;
+                    }
+                }
+
+                if let Some(declarations) = util::read_nested("subpattern", item.clone()) {
+                    for declaration in declarations {
+                        let span = declaration.span();
+                        let (name, pattern) = match declaration {
+                            util::NestedMeta::Meta(util::Meta::NameValue(nval)) => {
+                                let name = nval.path.get_ident().map(ToString::to_string);
+                                let pattern = match nval.lit {
+                                    util::Lit::Str(string) => Some(string.value()),
+                                    util::Lit::ByteStr(bytes) => {
+                                        Some(util::bytes_to_regex_string(&bytes.value()))
+                                    },
+                                    _ => None,
+                                };
+
+                                (name, pattern)
+                            },
+                            _ => (None, None),
+                        };
+
+                        match (name, pattern) {
+                            (Some(name), Some(pattern)) => {
+                                if let Err(err) = subpatterns.add(name, &pattern) {
+                                    errors.push(Error::new(err).span(span));
+                                }
+                            },
+                            _ => errors.push(
+                                Error::new("Expected #[logos(subpattern(name = \"regex\"))]")
+                                    .span(span)
+                            ),
+                        }
+                    }
+                }
+
+                if let Some(strategy) = util::value_from_nested::<Option<Literal>>("dispatch", item.clone()) {
+                    match strategy {
+                        Some(Literal::Utf8(ref strategy, _)) if strategy == "branch" => {
+                            dispatch = Dispatch::Branch;
+                        },
+                        Some(Literal::Utf8(ref strategy, _)) if strategy == "table" => {
+                            dispatch = Dispatch::Table;
+                        },
+                        _ => errors.push(
+                            Error::new("Expected #[logos(dispatch = \"branch\")] or #[logos(dispatch = \"table\")]")
+                                .span(item.span())
+                        ),
+                    }
+                }
+
+                if let Some(goal) = util::value_from_nested::<Option<Literal>>("optimize", item.clone()) {
+                    match goal {
+                        Some(Literal::Utf8(ref goal, _)) if goal == "speed" => {
+                            optimize = Optimize::Speed;
+                        },
+                        Some(Literal::Utf8(ref goal, _)) if goal == "size" => {
+                            optimize = Optimize::Size;
+                        },
+                        _ => errors.push(
+                            Error::new("Expected #[logos(optimize = \"speed\")] or #[logos(optimize = \"size\")]")
+                                .span(item.span())
+                        ),
+                    }
+                }
+
+                if let Some(class) = util::value_from_nested::<Option<Literal>>("ident", item.clone()) {
+                    match class {
+                        Some(Literal::Utf8(class, span)) => {
+                            match subpatterns.add("ident".into(), &class) {
+                                Ok(()) => ident_class = class,
+                                Err(err) => errors.push(Error::new(err).span(span)),
+                            }
+                        },
+                        _ => errors.push(
+                            Error::new("Expected #[logos(ident = \"class\")]").span(item.span())
+                        ),
+                    }
+                }
+
                 if let Some(t) = util::value_from_nested::<Option<Literal>>("trivia", item) {
                     trivia = match t {
                         Some(Literal::Utf8(string, span)) => {
                             Some((true, string.into(), span))
                         },
                         Some(Literal::Bytes(bytes, span)) => {
-                            mode = Mode::Binary;
-
                             Some((false, util::bytes_to_regex_string(&bytes).into(), span))
                         },
                         None => None,
@@ -77,20 +222,35 @@ pub fn logos(input: TokenStream) -> TokenStream {
         }
     }
 
+    let mut names = Vec::new();
     let mut variants = Vec::new();
+    let mut discriminants = Vec::new();
+    let mut variant_priorities = Vec::new();
+    let mut discriminant = 0;
+    let mut definitions = Vec::new();
+    let mut leaves = Vec::new();
+    let mut priorities = Vec::new();
     let mut ropes = Vec::new();
     let mut regex_ids = Vec::new();
+    let mut start_ids = Vec::new();
+    let mut non_utf8 = Vec::new();
+    let mut hints = Map::default();
     let mut graph = Graph::new();
 
-    for variant in &item.variants {
-        variants.push(&variant.ident);
+    if ascii {
+        graph.ascii();
+    }
 
+    for variant in &item.variants {
         let span = variant.span();
+        let mut label: Option<String> = None;
 
         if let Some((_, value)) = &variant.discriminant {
             let span = value.span();
             let value = util::unpack_int(value).unwrap_or(usize::max_value());
 
+            discriminant = value;
+
             if value >= size {
                 errors.push(Error::new(
                     format!(
@@ -102,15 +262,37 @@ pub fn logos(input: TokenStream) -> TokenStream {
             }
         }
 
-        match variant.fields {
-            Fields::Unit => {}
+        let field_ty = match variant.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0].ty),
+            _ => None,
+        };
+        let field = match variant.fields {
+            Fields::Unit => false,
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => true,
             _ => {
                 errors.push(Error::new(
                     format!(
-                        "`{}::{}` has fields. This is not allowed for Tokens.",
+                        "`{}::{}` has fields. Tokens can only have a single unnamed \
+                         field holding the value produced by a callback.",
                         name, variant.ident
                     ),
                 ).span(span));
+
+                false
+            }
+        };
+
+        // Unit variants named `Error` and `End` stand in for the #[error] and #[end]
+        // attributes, as long as they don't have any definitions of their own
+        let plain = variant.attrs.iter().all(|attr| {
+            !["error", "end", "token", "regex"].iter().any(|name| attr.path.is_ident(name))
+        });
+
+        if plain && !field {
+            if variant.ident == "Error" {
+                implicit_error = Some(&variant.ident);
+            } else if variant.ident == "End" {
+                implicit_end = Some(&variant.ident);
             }
         }
 
@@ -122,6 +304,48 @@ pub fn logos(input: TokenStream) -> TokenStream {
             let ident = &attr.path.segments[0].ident;
             let variant = &variant.ident;
 
+            if ident == "end" && field {
+                errors.push(Error::new("#[end] variant can't have fields.").span(span));
+            }
+
+            if ident == "error" && field {
+                match field_ty.filter(|ty| is_error_field(ty)) {
+                    Some(ty) => error_ty = Some(ty),
+                    None => errors.push(Error::new(
+                        "#[error] variant can only hold the range of the error, \
+                         as a `Range<usize>` or a `logos::Span`."
+                    ).span(span)),
+                }
+            }
+
+            if let Some(nested) = util::read_attr("logos", attr) {
+                for item in nested {
+                    let hint = match item {
+                        util::NestedMeta::Meta(util::Meta::Path(ref path)) if path.is_ident("inline") => {
+                            Inline::Always
+                        },
+                        util::NestedMeta::Meta(util::Meta::Path(ref path)) if path.is_ident("noinline") => {
+                            Inline::Never
+                        },
+                        _ => {
+                            errors.push(
+                                Error::new("Expected #[logos(inline)] or #[logos(noinline)] on a variant.")
+                                    .span(item.span())
+                            );
+                            continue;
+                        },
+                    };
+
+                    if let Some(previous) = hints.insert(variant.clone(), hint) {
+                        if previous != hint {
+                            errors.push(Error::new(
+                                format!("`{}::{}` can't be both inline and noinline.", name, variant),
+                            ).span(item.span()));
+                        }
+                    }
+                }
+            }
+
             if ident == "error" {
                 if let Some(previous) = error.replace(variant) {
                     errors.extend(vec![
@@ -142,68 +366,441 @@ pub fn logos(input: TokenStream) -> TokenStream {
 
             let mut with_definition = |definition: Definition<Literal>| {
                 let callback = definition.callback.or_else(|| global_callback.clone());
-                let token = Leaf::token(variant).callback(callback);
 
-                if let Literal::Bytes(..) = definition.value {
-                    mode = Mode::Binary;
+                if let Some(new_label) = definition.name {
+                    match label {
+                        Some(ref previous) if *previous != new_label => {
+                            errors.push(Error::new(
+                                format!(
+                                    "`{}::{}` is already named {:?}.",
+                                    name, variant, previous
+                                ),
+                            ).span(span));
+                        },
+                        _ => label = Some(new_label),
+                    }
+                }
+
+                if field && callback.is_none() {
+                    errors.push(Error::new(
+                        format!(
+                            "`{}::{}` has a field, but no callback to produce its value.",
+                            name, variant
+                        ),
+                    ).span(span));
                 }
 
+                let until = match definition.until {
+                    Some(terminator) => {
+                        let terminator_span = match terminator {
+                            Literal::Utf8(_, span) | Literal::Bytes(_, span) => span,
+                        };
+                        let bytes = terminator.into_bytes();
+
+                        if let Err(err) = std::str::from_utf8(&bytes) {
+                            let byte = format!("\\x{:02X}", bytes[err.valid_up_to()]);
+
+                            non_utf8.push((format!("`{}::{}`", name, variant), terminator_span, byte));
+                        }
+                        if bytes.is_empty() {
+                            errors.push(Error::new("The until terminator can't be empty.").span(terminator_span));
+                        }
+
+                        Some(bytes).filter(|bytes| !bytes.is_empty())
+                    },
+                    None => None,
+                };
+
+                let nested = match definition.nested {
+                    Some(opening) => {
+                        let opening_span = match opening {
+                            Literal::Utf8(_, span) | Literal::Bytes(_, span) => span,
+                        };
+                        let bytes = opening.into_bytes();
+
+                        if let Err(err) = std::str::from_utf8(&bytes) {
+                            let byte = format!("\\x{:02X}", bytes[err.valid_up_to()]);
+
+                            non_utf8.push((format!("`{}::{}`", name, variant), opening_span, byte));
+                        }
+                        if bytes.is_empty() {
+                            errors.push(Error::new("The nested opening can't be empty.").span(opening_span));
+                        } else if until.is_none() {
+                            errors.push(
+                                Error::new("The nested opening requires an until terminator.").span(opening_span)
+                            );
+                        } else if until.as_ref() == Some(&bytes) {
+                            errors.push(
+                                Error::new("The nested opening can't be the same as the until terminator.")
+                                    .span(opening_span)
+                            );
+                        }
+
+                        Some(bytes).filter(|bytes| !bytes.is_empty())
+                    },
+                    None => None,
+                };
+
+                let token = Leaf::token(variant).callback(callback).field(field).until(until).nested(nested);
+
                 (token, definition.value)
             };
 
-            if let Some(definition) = util::value_from_attr("token", attr) {
+            if let Some(mut definition) = util::value_from_attr::<Definition<Literal>>("token", attr) {
+                let ignore_case = definition.ignore_case;
+                let ignore_unicode_case = definition.ignore_unicode_case;
+                let priority = definition.priority;
+                let keyword = definition.keyword;
+                let allow_ambiguity = definition.allow_ambiguity;
+                let allow_unreachable = definition.allow_unreachable;
+                let boundary = definition.boundary.clone();
+                let alternatives = std::mem::take(&mut definition.alternatives);
+                let has_class = definition.class.is_some();
+                let has_keywords = definition.keywords.is_some();
                 let (token, value) = with_definition(definition);
 
-                let value = value.into_bytes();
-                let then = graph.push(token.priority(value.len()));
+                if has_class {
+                    errors.push(
+                        Error::new("#[token]: class is only supported on #[regex] definitions.").span(span)
+                    );
+                }
+                if has_keywords {
+                    errors.push(
+                        Error::new("#[token]: keywords are only supported on #[regex] definitions.").span(span)
+                    );
+                }
+                if keyword && priority.is_some() {
+                    errors.push(
+                        Error::new("#[token]: keyword can't be combined with an explicit priority.").span(span)
+                    );
+                }
+
+                // Every spelling is a separate definition of the same variant
+                for value in std::iter::once(value).chain(alternatives) {
+                    if let Literal::Bytes(ref bytes, span) = value {
+                        if let Err(err) = std::str::from_utf8(bytes) {
+                            let byte = format!("\\x{:02X}", bytes[err.valid_up_to()]);
+
+                            non_utf8.push((format!("`{}::{}`", name, variant), span, byte));
+                        }
+                    }
+
+                    let (utf8, len, span, is_ascii) = match value {
+                        Literal::Utf8(ref string, span) => (true, string.len(), span, string.is_ascii()),
+                        Literal::Bytes(ref bytes, span) => (false, bytes.len(), span, bytes.is_ascii()),
+                    };
+
+                    if len == 0 {
+                        errors.push(Error::new("#[token]: the token can't be empty.").span(span));
+                        continue;
+                    }
+                    if ascii && !is_ascii {
+                        errors.push(
+                            Error::new("#[logos(ascii)]: #[token] can only match ASCII characters.").span(span)
+                        );
+                        continue;
+                    }
+
+                    // Keywords count bytes the same way literals in a #[regex] do, beating
+                    // any regex made of classes alone, which gets 1 per matched class
+                    let len = match keyword {
+                        true => len * 2,
+                        false => len,
+                    };
+                    let leaf = token.clone().priority(priority.unwrap_or(len));
+
+                    if !allow_unreachable {
+                        leaves.push((leaf.clone(), format!("`{}::{}` #[token({})]", name, variant, value.to_source()), span));
+                    }
 
-                ropes.push(Rope::new(value, then));
-            } else if let Some(definition) = util::value_from_attr("regex", attr) {
+                    let then = graph.push(leaf);
+
+                    priorities.push((variant, priority.unwrap_or(len), format!("#[token({})]", value.to_source())));
+                    let end = match boundary {
+                        Some(ref class) => {
+                            let class = class.as_ref().unwrap_or(&ident_class);
+                            let class = match subpatterns.fix(class) {
+                                Ok(class) if unicode => class,
+                                Ok(class) => util::ascii_classes(&class),
+                                Err(err) => {
+                                    errors.push(Error::new(err).span(span));
+                                    continue;
+                                }
+                            };
+
+                            match boundary_node(&mut graph, utf8, &class, then) {
+                                Ok(id) => id,
+                                Err(err) => {
+                                    errors.push(err.span(span));
+                                    continue;
+                                }
+                            }
+                        },
+                        None => then,
+                    };
+
+                    let regex = match (ignore_unicode_case, ignore_case) {
+                        (true, _) => match util::unicode_case_regex(&value) {
+                            Some(regex) => Some(regex),
+                            None => {
+                                errors.push(
+                                    Error::new("#[token]: ignore(unicode_case) is only supported on string literals.")
+                                        .span(span)
+                                );
+                                continue;
+                            },
+                        },
+                        (false, true) => Some(util::ignore_case_regex(&value)),
+                        (false, false) => None,
+                    };
+
+                    if let Some(regex) = regex {
+                        match graph.regex(utf8, &regex, end) {
+                            Ok((_, id)) => {
+                                definitions.push((then, id, allow_ambiguity));
+                                regex_ids.push(id);
+                            },
+                            Err(err) => errors.push(err.span(span)),
+                        }
+                    } else {
+                        let rope = Rope::new(value.into_bytes(), end);
+
+                        definitions.push((then, graph.push(rope.clone()), allow_ambiguity));
+                        ropes.push(rope);
+                    }
+                }
+            } else if let Some(definition) = util::value_from_attr::<Definition<Literal>>("regex", attr) {
+                let ignore_case = definition.ignore_case;
+                let ignore_unicode_case = definition.ignore_unicode_case;
+                let unicode = unicode || definition.unicode;
+                let priority = definition.priority;
+                let keyword = definition.keyword;
+                let allow_ambiguity = definition.allow_ambiguity;
+                let allow_unreachable = definition.allow_unreachable;
+                let alternatives = !definition.alternatives.is_empty();
+                let class = definition.class.clone();
+                let has_class = class.is_some();
+                let keywords = definition.keywords.clone();
+                let has_until = definition.until.is_some();
                 let (token, value) = with_definition(definition);
+                let token = token.class(class).keywords(keywords);
+
+                if ignore_case {
+                    errors.push(
+                        Error::new("#[regex]: ignore(case) is not supported, use the (?i) flag instead.")
+                            .span(span)
+                    );
+                }
+                if alternatives {
+                    errors.push(
+                        Error::new("#[regex]: only one pattern can be given, use | to match alternatives.")
+                            .span(span)
+                    );
+                }
+                if keyword {
+                    errors.push(
+                        Error::new("#[regex]: keyword is only supported on #[token] definitions.").span(span)
+                    );
+                }
 
                 let then = graph.reserve();
+                let pattern = value.to_source();
 
                 let (utf8, regex, span) = match value {
                     Literal::Utf8(string, span) => (true, string, span),
-                    Literal::Bytes(bytes, span) => {
-                        mode = Mode::Binary;
+                    Literal::Bytes(bytes, span) => (false, util::bytes_to_regex_string(&bytes), span),
+                };
+
+                let regex = match subpatterns.fix(&regex) {
+                    Ok(regex) if unicode => regex,
+                    Ok(regex) => util::ascii_classes(&regex),
+                    Err(err) => {
+                        errors.push(Error::new(err).span(span));
+                        continue;
+                    }
+                };
+
+                let regex = match (ignore_unicode_case, utf8) {
+                    (false, _) => regex,
+                    (true, true) => format!("(?i){}", regex),
+                    (true, false) => {
+                        errors.push(
+                            Error::new("#[regex]: ignore(unicode_case) is only supported on string patterns.")
+                                .span(span)
+                        );
+                        continue;
+                    },
+                };
 
-                        (false, util::bytes_to_regex_string(&bytes), span)
+                let (regex, lookahead) = match util::split_lookahead(&regex) {
+                    Ok(split) => split,
+                    Err(err) => {
+                        errors.push(Error::new(err).span(span));
+                        continue;
                     }
                 };
 
-                match graph.regex(utf8, &regex, then.get()) {
-                    Ok((len, mut id)) => {
-                        let then = graph.insert(then, token.priority(len));
+                if let Some(bytes) = invalid_utf8(regex).filter(|_| !utf8) {
+                    non_utf8.push((format!("`{}::{}`", name, variant), span, bytes));
+                }
+
+                let has_lookahead = lookahead.is_some();
+                let (end, token) = match lookahead {
+                    Some(Lookahead { negated: true, pattern }) => {
+                        match boundary_node(&mut graph, utf8, &pattern, then.get()) {
+                            Ok(id) => (id, token),
+                            Err(err) => {
+                                errors.push(err.span(span));
+                                continue;
+                            }
+                        }
+                    },
+                    Some(Lookahead { negated: false, pattern }) => {
+                        match graph.lookahead(utf8, &pattern, then.get()) {
+                            Ok((len, id)) => (id, token.lookahead(len)),
+                            Err(err) => {
+                                errors.push(err.span(span));
+                                continue;
+                            }
+                        }
+                    },
+                    None => (then.get(), token),
+                };
+
+                match graph.anchored_regex(utf8, regex, end) {
+                    Ok((len, mut id, anchors)) => {
+                        // Anchors make a definition more specific than the same unanchored pattern
+                        let len = len + anchors.start as usize + anchors.end as usize;
+                        let token = token.priority(priority.unwrap_or(len));
+
+                        priorities.push((variant, priority.unwrap_or(len), format!("#[regex({})]", pattern)));
+                        if !allow_unreachable {
+                            leaves.push((token.clone(), format!("`{}::{}` #[regex({})]", name, variant, pattern), span));
+                        }
+
+                        let then = match anchors.end {
+                            true => graph.insert(then, token.at_end()),
+                            false => graph.insert(then, token),
+                        };
+
+                        if anchors.end && has_lookahead {
+                            errors.push(
+                                Error::new("#[regex]: `$` can't be combined with a lookahead.").span(span)
+                            );
+                            continue;
+                        }
+                        if has_class && (anchors.end || has_lookahead) {
+                            errors.push(
+                                Error::new("#[regex]: class can't be combined with a lookahead or `$`.").span(span)
+                            );
+                            continue;
+                        }
+                        if has_until && (anchors.end || has_lookahead || has_class) {
+                            errors.push(
+                                Error::new("#[regex]: until can't be combined with a class, lookahead or `$`.")
+                                    .span(span)
+                            );
+                            continue;
+                        }
+
+                        // Definitions anchored with `^` only start from the root used at offset 0
+                        let regex_ids = match anchors.start {
+                            true => &mut start_ids,
+                            false => &mut regex_ids,
+                        };
+
+                        definitions.push((then, id, allow_ambiguity));
                         regex_ids.push(id);
 
                         // Drain recursive miss values.
                         // We need the root node to have straight branches.
                         while let Some(miss) = graph[id].miss() {
-                            if miss == then {
-                                errors.push(
-                                    Error::new("#[regex]: expression can match empty string.\n\n\
-                                                hint: consider changing * to +").span(span)
-                                );
-                                break;
-                            } else {
-                                regex_ids.push(miss);
-                                id = miss;
-                            }
+                            regex_ids.push(miss);
+                            id = miss;
                         }
                     },
                     Err(err) => errors.push(err.span(span)),
                 }
             }
         }
+
+        let ident = &variant.ident;
+        let label = label.unwrap_or_else(|| ident.to_string());
+
+        names.push(match field {
+            true => quote!(#name::#ident(..) => #label),
+            false => quote!(#name::#ident => #label),
+        });
+
+        if !field {
+            variants.push(quote!(#name::#ident));
+        }
+
+        discriminants.push(match field {
+            true => quote!(#name::#ident(..) => #discriminant),
+            false => quote!(#name::#ident => #discriminant),
+        });
+        discriminant = discriminant.wrapping_add(1);
+
+        let priority = priorities
+            .iter()
+            .filter(|(variant, ..)| *variant == ident)
+            .map(|(_, priority, _)| *priority as u32)
+            .max()
+            .unwrap_or(0);
+
+        variant_priorities.push(match field {
+            true => quote!(#name::#ident(..) => #priority),
+            false => quote!(#name::#ident => #priority),
+        });
+    }
+
+    for (utf8, regex, span) in skips {
+        let regex = match subpatterns.fix(&regex) {
+            Ok(regex) if unicode => regex,
+            Ok(regex) => util::ascii_classes(&regex),
+            Err(err) => {
+                errors.push(Error::new(err).span(span));
+                continue;
+            }
+        };
+        let then = graph.push(Leaf::Trivia);
+
+        if let Some(bytes) = invalid_utf8(&regex).filter(|_| !utf8) {
+            non_utf8.push((String::from("#[skip]"), span, bytes));
+        }
+
+        match graph.non_empty_regex(utf8, &regex, then) {
+            Ok((_, mut id)) => {
+                regex_ids.push(id);
+
+                while let Some(miss) = graph[id].miss() {
+                    regex_ids.push(miss);
+                    id = miss;
+                }
+            },
+            Err(err) => errors.push(err.span(span)),
+        }
     }
 
     let mut root = Fork::new();
 
     if let Some((utf8, regex, span)) = trivia {
         let then = graph.push(Leaf::Trivia);
+        let regex = subpatterns.fix(&regex).unwrap_or_else(|err| {
+            errors.push(Error::new(err).span(span));
+            regex.into_owned()
+        });
+        let regex = match unicode {
+            true => regex,
+            false => util::ascii_classes(&regex),
+        };
+
+        if let Some(bytes) = invalid_utf8(&regex).filter(|_| !utf8) {
+            non_utf8.push((String::from("#[logos(trivia)]"), span, bytes));
+        }
 
-        match graph.regex(utf8, &*regex, then) {
+        match graph.non_empty_regex(utf8, &regex, then) {
             Ok((_, id)) => {
                 let trivia = graph.fork_off(id);
 
@@ -213,12 +810,39 @@ pub fn logos(input: TokenStream) -> TokenStream {
         }
     }
 
+    let mode = match non_utf8.is_empty() {
+        true => Mode::Utf8,
+        false => Mode::Binary,
+    };
+
+    if utf8_only {
+        for (definition, span, bytes) in non_utf8.drain(..) {
+            errors.push(Error::new(format!(
+                "{} can match invalid UTF-8 through `{}`, but #[logos(utf8)] requires \
+                 the enum to lex `&str`.\n\n\
+                 hint: remove #[logos(utf8)] to only lex binary sources, such as `&[u8]`",
+                definition, bytes,
+            )).span(span));
+        }
+    }
+
+    let error = error.or(implicit_error);
+    let end = end.or(implicit_end);
+
     if error.is_none() {
-        errors.push(Error::new("missing #[error] token variant.").span(super_span));
+        errors.push(
+            Error::new("missing #[error] token variant.\n\n\
+                        hint: add an `Error` variant, or mark an existing one with #[error]")
+                .span(super_span)
+        );
     }
 
     if end.is_none() {
-        errors.push(Error::new("missing #[end] token variant.").span(super_span));
+        errors.push(
+            Error::new("missing #[end] token variant.\n\n\
+                        hint: add an `End` variant, or mark an existing one with #[end]")
+                .span(super_span)
+        );
     }
 
     if errors.len() > 0 {
@@ -235,6 +859,18 @@ pub fn logos(input: TokenStream) -> TokenStream {
         Some(ext) => quote!(#ext),
         None => quote!(()),
     };
+    let (error_const, error_fn) = match error_ty {
+        Some(ty) => (
+            quote!(#name::#error(<#ty as ::logos::internal::ErrorField>::EMPTY)),
+            quote! {
+                #[inline]
+                fn error(range: ::core::ops::Range<usize>) -> Self {
+                    #name::#error(<#ty as ::logos::internal::ErrorField>::from_range(range))
+                }
+            },
+        ),
+        None => (quote!(#name::#error), quote!()),
+    };
     let source = match mode {
         Mode::Utf8 => quote!(Source),
         Mode::Binary => quote!(BinarySource),
@@ -247,52 +883,275 @@ pub fn logos(input: TokenStream) -> TokenStream {
     for rope in ropes {
         root.merge(rope.into_fork(&mut graph), &mut graph)
     }
+
+    let start = match start_ids.is_empty() {
+        true => None,
+        false => {
+            let mut start = root.clone();
+
+            for id in start_ids {
+                let fork = graph.fork_off(id);
+                start.merge(fork, &mut graph);
+            }
+
+            Some(graph.push(start))
+        },
+    };
     let root = graph.push(root);
 
-    graph.shake(root);
+    let definition = |leaf: NodeId| definitions.iter().find(|(then, ..)| *then == leaf);
+
+    for &[a, b] in graph.ties() {
+        let (entry_a, entry_b) = match (definition(a), definition(b)) {
+            (Some(&(_, _, true)), _) | (_, Some(&(_, _, true))) => continue,
+            (Some(&(_, a, _)), Some(&(_, b, _))) => (a, b),
+            _ => continue,
+        };
+
+        if let (Node::Leaf(left), Node::Leaf(right)) = (&graph[a], &graph[b]) {
+            let example = graph.overlap(entry_a, entry_b);
+
+            errors.extend(Leaf::tie_error(left, right, example.as_deref()));
+        }
+    }
+
+    if serde && lifetime.is_some() {
+        errors.push(Error::new("#[logos(serde)] isn't supported on token enums with a lifetime.").span(super_span));
+    }
+
+    if print_priorities {
+        eprintln!("Priorities of `{}` definitions:", name);
+
+        for (variant, priority, definition) in &priorities {
+            eprintln!("    {} => {}  {}", variant, priority, definition);
+        }
+    }
+
+    if !errors.is_empty() {
+        return quote! {
+            fn _logos_derive_compile_errors() {
+                #(#errors)*
+            }
+        }.into();
+    }
+
+    graph.shake(std::iter::once(root).chain(start));
+
+    // Tokens left out of the state machine lost to other definitions on every input
+    let warnings = leaves
+        .into_iter()
+        .filter(|(token, ..)| {
+            !graph.nodes().iter().any(|node| match node {
+                Some(Node::Leaf(leaf)) => leaf.produces(token),
+                _ => false,
+            })
+        })
+        .map(|(_, definition, span)| {
+            Warning::new(format!("{} is unreachable, no input can produce it", definition), span)
+        })
+        .collect::<Vec<_>>();
+
+    if dump {
+        if let Err(err) = dump_dot(&name.to_string(), graph.dot(&name.to_string(), root)) {
+            let error = Error::new(format!("Failed to dump the state machine: {}", err)).span(super_span);
+
+            return quote! {
+                fn _logos_derive_compile_errors() {
+                    #error
+                }
+            }.into();
+        }
+    }
 
     // panic!("{:#?}\n\n{} nodes", graph, graph.nodes().iter().filter_map(|n| n.as_ref()).count());
 
-    let mut generator = Generator::new(name, root, &graph);
+    let mut generator = Generator::new(name, root, start, dispatch, optimize, hints, &graph);
 
     let body = generator.generate();
 
+    let serde = match serde {
+        true => impl_serde(&item),
+        false => quote!(),
+    };
+
+    let (generics, this, this_in, this_static, source_lifetime) = match lifetime {
+        Some(lifetime) => (
+            quote!(<#lifetime>),
+            quote!(#name<#lifetime>),
+            quote!(#name<'s>),
+            quote!(#name<'static>),
+            quote!(#lifetime),
+        ),
+        None => (quote!(), quote!(#name), quote!(#name), quote!(#name), quote!('source)),
+    };
+
     let tokens = quote! {
-        impl ::logos::Logos for #name {
+        impl #generics ::logos::Logos for #this {
             type Extras = #extras;
 
             const SIZE: usize = #size;
-            const ERROR: Self = #name::#error;
+            const ERROR: Self = #error_const;
             const END: Self = #name::#end;
 
-            fn lex<'source, Source>(lex: &mut ::logos::Lexer<#name, Source>)
-            where
-                Source: ::logos::Source<'source>,
-                Self: ::logos::source::WithSource<Source>,
-            {
-                use ::logos::internal::LexerInternal;
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#names,)*
+                }
+            }
+
+            fn variants() -> &'static [Self] {
+                const VARIANTS: &[#this_static] = &[#(#variants,)*];
+
+                VARIANTS
+            }
+
+            #[inline]
+            fn discriminant(&self) -> usize {
+                #name::discriminant(self)
+            }
+
+            #[inline]
+            fn priority(&self) -> u32 {
+                #name::priority(self)
+            }
+
+            #error_fn
+        }
+
+        impl<#source_lifetime, Source: ::logos::source::#source<#source_lifetime>> ::logos::source::WithSource<Source> for #this {
+            fn lex_with(lex: &mut ::logos::Lexer<Self, Source>) {
+                use ::logos::internal::{LexerInternal, CallbackResult};
                 use ::logos::source::{Source as Src};
 
-                type Lexer<S> = ::logos::Lexer<#name, S>;
+                type Lexer<'s, S> = ::logos::Lexer<#this_in, S>;
 
-                fn _end<'s, S: Src<'s>>(lex: &mut Lexer<S>) {
+                fn _end<'s, S: Src<'s>>(lex: &mut Lexer<'s, S>) {
                     lex.token = #name::#end;
                 }
 
-                fn _error<'s, S: Src<'s>>(lex: &mut Lexer<S>) {
-                    lex.bump(1);
-
-                    lex.token = #name::#error;
+                fn _error<'s, S: Src<'s>>(lex: &mut Lexer<'s, S>) {
+                    lex.bump_unchecked(1);
+                    lex.error();
                 }
 
                 #body
             }
         }
 
-        impl<'source, Source: ::logos::source::#source<'source>> ::logos::source::WithSource<Source> for #name {}
+        impl #generics #this {
+            /// Index of the variant, same as `Logos::discriminant`, usable in `const`
+            /// contexts such as the tables built by `lookup!`.
+            #[inline]
+            pub const fn discriminant(&self) -> usize {
+                match self {
+                    #(#discriminants,)*
+                }
+            }
+
+            /// Highest priority among the definitions of the variant, same as
+            /// `Logos::priority`, usable in `const` contexts.
+            #[inline]
+            pub const fn priority(&self) -> u32 {
+                match self {
+                    #(#variant_priorities,)*
+                }
+            }
+        }
+
+        const _: () = {
+            #(#warnings)*
+        };
+
+        #serde
     };
 
     // panic!("{}", tokens);
 
     TokenStream::from(tokens)
 }
+
+/// Check if the field of the `#[error]` variant is one that can hold its range,
+/// `Range<usize>` or `Span`, going by the last segment of the path.
+fn is_error_field(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => segment.ident == "Range" || segment.ident == "Span",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Create a node that leads to `then`, unless followed by a match of `pattern`.
+fn boundary_node(graph: &mut Graph<Leaf>, utf8: bool, pattern: &str, then: NodeId) -> Result<NodeId, Error> {
+    let reject = graph.push(Leaf::Reject);
+    let (_, id) = graph.regex(utf8, pattern, reject)?;
+    let fork = graph.fork_off(id).miss(then);
+
+    Ok(graph.push(fork))
+}
+
+/// Implement `Serialize` and `Deserialize` by deriving them for a remote copy of
+/// the enum, so tokens are represented by variant names and their fields.
+fn impl_serde(item: &ItemEnum) -> proc_macro2::TokenStream {
+    let name = &item.ident;
+    let remote = name.to_string();
+    let variants = item.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+
+        match variant.fields {
+            Fields::Unnamed(ref fields) => {
+                let types = fields.unnamed.iter().map(|field| &field.ty);
+
+                quote!(#ident(#(#types),*))
+            },
+            _ => quote!(#ident),
+        }
+    });
+
+    quote! {
+        const _: () = {
+            use ::logos::internal::serde;
+
+            #[derive(serde::Serialize, serde::Deserialize)]
+            #[serde(crate = "::logos::internal::serde", remote = #remote, rename = #remote)]
+            enum Remote {
+                #(#variants,)*
+            }
+
+            impl serde::Serialize for #name {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    Remote::serialize(self, serializer)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for #name {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    Remote::deserialize(deserializer)
+                }
+            }
+        };
+    }
+}
+
+/// Write the DOT rendering of the state machine to `target/logos/<Name>.dot`,
+/// respecting `CARGO_TARGET_DIR`.
+fn dump_dot(name: &str, dot: String) -> std::io::Result<()> {
+    use std::path::PathBuf;
+
+    // The compiler doesn't run in the directory of the crate, so relative paths
+    // are resolved from its manifest, and from the workspace for the default
+    let manifest = std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from);
+    let manifest = manifest.map_or_else(std::env::current_dir, Ok)?;
+    let target = match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(target) => manifest.join(target),
+        None => manifest.ancestors()
+            .map(|dir| dir.join("target"))
+            .find(|target| target.is_dir())
+            .unwrap_or_else(|| manifest.join("target")),
+    };
+    let dir = target.join("logos");
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(format!("{}.dot", name)), dot)
+}
@@ -15,9 +15,37 @@ impl<T> OptionExt<T> for Option<T> {
     }
 }
 
+/// Characters that mustn't follow a `#[token(..., boundary)]`, unless the enum
+/// declares its own with `#[logos(ident = "...")]`.
+pub const DEFAULT_BOUNDARY: &str = "[a-zA-Z0-9_]";
+
 pub struct Definition<V: Value> {
     pub value: V,
+    /// Further literals following the first one, as in `#[token("<>", "!=")]`.
+    pub alternatives: Vec<Literal>,
     pub callback: Option<Ident>,
+    pub ignore_case: bool,
+    pub ignore_unicode_case: bool,
+    pub unicode: bool,
+    pub name: Option<String>,
+    pub priority: Option<usize>,
+    /// Literal meant to beat identifier-like regexes, as in `#[token("self", keyword)]`.
+    pub keyword: bool,
+    pub allow_ambiguity: bool,
+    /// Don't warn when no input can produce this definition.
+    pub allow_unreachable: bool,
+    /// Characters that mustn't follow a `#[token]`, `None` standing for the identifier
+    /// characters of the enum.
+    pub boundary: Option<Option<String>>,
+    /// Function consuming further characters after a `#[regex]` matches.
+    pub class: Option<Ident>,
+    /// Sorted table of keywords looked up when a `#[regex]` matches, to reclassify
+    /// the token.
+    pub keywords: Option<Ident>,
+    /// Terminator consumed through, along with everything before it, after a match.
+    pub until: Option<Literal>,
+    /// Opening sequence nesting within the `until` terminator, as in `/* /* */ */`.
+    pub nested: Option<Literal>,
 }
 
 #[derive(Debug)]
@@ -33,6 +61,14 @@ impl Literal {
             Literal::Bytes(bytes, _) => bytes,
         }
     }
+
+    /// The literal as it would be written in Rust source, for messages.
+    pub fn to_source(&self) -> String {
+        match self {
+            Literal::Utf8(string, _) => format!("{:?}", string),
+            Literal::Bytes(bytes, _) => format!("b\"{}\"", bytes.escape_ascii()),
+        }
+    }
 }
 
 pub trait Value {
@@ -82,22 +118,158 @@ impl<V: Value> Value for Definition<V> {
     fn value(value: Option<Literal>) -> Self {
         Definition {
             value: V::value(value),
+            alternatives: Vec::new(),
             callback: None,
+            ignore_case: false,
+            ignore_unicode_case: false,
+            unicode: false,
+            name: None,
+            priority: None,
+            keyword: false,
+            allow_ambiguity: false,
+            allow_unreachable: false,
+            boundary: None,
+            class: None,
+            keywords: None,
+            until: None,
+            nested: None,
         }
     }
 
     fn nested(&mut self, nested: &NestedMeta) {
         match nested {
+            NestedMeta::Lit(Lit::Str(ref v)) => {
+                self.alternatives.push(Literal::Utf8(v.value(), v.span()));
+            }
+            NestedMeta::Lit(Lit::ByteStr(ref v)) => {
+                self.alternatives.push(Literal::Bytes(v.value(), v.span()));
+            }
             NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("callback") => {
                 let callback = match nval.lit {
                     Lit::Str(ref c) => ident(&c.value()),
                     ref lit => panic!("Invalid callback value: {}", quote!(#lit)),
                 };
 
-                self.callback.insert(callback, |_| {
+                OptionExt::insert(&mut self.callback, callback, |_| {
                     panic!("Only one callback can be defined per variant definition!")
                 });
             }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("class") => {
+                let class = match nval.lit {
+                    Lit::Str(ref c) => ident(&c.value()),
+                    ref lit => panic!("Invalid class value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.class, class, |_| {
+                    panic!("Only one class can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("keywords") => {
+                let keywords = match nval.lit {
+                    Lit::Str(ref table) => ident(&table.value()),
+                    ref lit => panic!("Invalid keywords value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.keywords, keywords, |_| {
+                    panic!("Only one keywords table can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("until") => {
+                let until = match nval.lit {
+                    Lit::Str(ref v) => Literal::Utf8(v.value(), v.span()),
+                    Lit::ByteStr(ref v) => Literal::Bytes(v.value(), v.span()),
+                    ref lit => panic!("Invalid until value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.until, until, |_| {
+                    panic!("Only one terminator can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("nested") => {
+                let nested = match nval.lit {
+                    Lit::Str(ref v) => Literal::Utf8(v.value(), v.span()),
+                    Lit::ByteStr(ref v) => Literal::Bytes(v.value(), v.span()),
+                    ref lit => panic!("Invalid nested value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.nested, nested, |_| {
+                    panic!("Only one nested opening can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("name") => {
+                let name = match nval.lit {
+                    Lit::Str(ref name) => name.value(),
+                    ref lit => panic!("Invalid name value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.name, name, |_| {
+                    panic!("Only one name can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("priority") => {
+                let priority = match nval.lit {
+                    Lit::Int(ref int) => int.base10_parse().ok(),
+                    _ => None,
+                };
+
+                match priority {
+                    Some(priority) => self.priority = Some(priority),
+                    None => panic!("Invalid priority value: {}", quote!(#nval)),
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(ref nval)) if nval.path.is_ident("boundary") => {
+                let boundary = match nval.lit {
+                    Lit::Str(ref class) => class.value(),
+                    ref lit => panic!("Invalid boundary value: {}", quote!(#lit)),
+                };
+
+                OptionExt::insert(&mut self.boundary, Some(boundary), |_| {
+                    panic!("Only one boundary can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("boundary") => {
+                OptionExt::insert(&mut self.boundary, None, |_| {
+                    panic!("Only one boundary can be defined per variant definition!")
+                });
+            }
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("unicode") => {
+                self.unicode = true;
+            }
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("keyword") => {
+                self.keyword = true;
+            }
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("ignore") => {
+                for flag in list.nested.iter() {
+                    match flag {
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("case") => {
+                            self.ignore_case = true;
+                        }
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("unicode_case") => {
+                            self.ignore_unicode_case = true;
+                        }
+                        _ => panic!(
+                            "Unexpected ignore flag: {}, expected ignore(case) or ignore(unicode_case)",
+                            quote!(#flag),
+                        ),
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("allow") => {
+                for flag in list.nested.iter() {
+                    match flag {
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("ambiguity") => {
+                            self.allow_ambiguity = true;
+                        }
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("unreachable") => {
+                            self.allow_unreachable = true;
+                        }
+                        _ => panic!(
+                            "Unexpected allow flag: {}, expected allow(ambiguity) or allow(unreachable)",
+                            quote!(#flag),
+                        ),
+                    }
+                }
+            }
             _ => panic!("Unexpected nested attribute: {}", quote!(#nested)),
         }
     }
@@ -207,3 +379,259 @@ pub fn bytes_to_regex_string(bytes: &[u8]) -> String {
 
     string
 }
+
+/// Turn a literal into a regex that matches it ignoring the case of ASCII letters.
+pub fn ignore_case_regex(literal: &Literal) -> String {
+    fn push_byte(regex: &mut String, byte: u8) {
+        if byte.is_ascii_alphabetic() {
+            regex.push('[');
+            regex.push(byte.to_ascii_lowercase() as char);
+            regex.push(byte.to_ascii_uppercase() as char);
+            regex.push(']');
+        } else {
+            regex.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+
+    let mut regex = String::new();
+
+    match literal {
+        Literal::Utf8(string, _) => {
+            for c in string.chars() {
+                if c.is_ascii() {
+                    push_byte(&mut regex, c as u8);
+                } else {
+                    regex.push_str(&regex_syntax::escape(c.encode_utf8(&mut [0; 4])));
+                }
+            }
+        }
+        Literal::Bytes(bytes, _) => {
+            for &byte in bytes {
+                push_byte(&mut regex, byte);
+            }
+        }
+    }
+
+    regex
+}
+
+/// Build a regex matching the `literal` with Unicode simple case folding, which
+/// is only possible for string literals.
+pub fn unicode_case_regex(literal: &Literal) -> Option<String> {
+    match literal {
+        Literal::Utf8(string, _) => Some(format!("(?i){}", regex_syntax::escape(string))),
+        Literal::Bytes(..) => None,
+    }
+}
+
+/// Replace the `\d`, `\w`, `\s` escapes and their negations in `regex`
+/// with their ASCII-only equivalents.
+pub fn ascii_classes(regex: &str) -> String {
+    let mut fixed = String::with_capacity(regex.len());
+    let mut chars = regex.chars().peekable();
+    let mut depth = 0usize;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some(escaped) => escaped,
+                    None => {
+                        fixed.push(c);
+                        break;
+                    }
+                };
+
+                let class = match escaped {
+                    'd' | 'D' => "digit",
+                    'w' | 'W' => "word",
+                    's' | 'S' => "space",
+                    _ => {
+                        fixed.push(c);
+                        fixed.push(escaped);
+                        continue;
+                    }
+                };
+
+                let negated = if escaped.is_uppercase() { "^" } else { "" };
+
+                if depth > 0 {
+                    fixed.push_str(&format!("[:{}{}:]", negated, class));
+                } else {
+                    fixed.push_str(&format!("[{}[:{}:]]", negated, class));
+                }
+            }
+            '[' => {
+                fixed.push(c);
+
+                // POSIX classes such as `[:alpha:]` don't open a nested class
+                if depth > 0 && chars.peek() == Some(&':') {
+                    for c in chars.by_ref() {
+                        fixed.push(c);
+
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+
+                depth += 1;
+
+                // `]` right after the opening bracket is a literal
+                if chars.peek() == Some(&'^') {
+                    fixed.push(chars.next().unwrap());
+                }
+                if chars.peek() == Some(&']') {
+                    fixed.push(chars.next().unwrap());
+                }
+            }
+            ']' if depth > 0 => {
+                fixed.push(c);
+                depth -= 1;
+            }
+            _ => fixed.push(c),
+        }
+    }
+
+    fixed
+}
+
+/// Zero-width `(?=...)` or `(?!...)` group ending a `#[regex]`.
+pub struct Lookahead {
+    pub negated: bool,
+    pub pattern: String,
+}
+
+/// Split the trailing lookahead off `regex`. Lookaheads anywhere else are rejected,
+/// since the state machine can't test input it has already moved past, and so are
+/// lookaheads following a top-level `|`, which would only apply to the last alternative.
+pub fn split_lookahead(regex: &str) -> Result<(&str, Option<Lookahead>), String> {
+    let mut chars = regex.char_indices().peekable();
+    let mut groups = Vec::new();
+    let mut depth = 0usize;
+    let mut split = None;
+    let mut alternation = false;
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => {
+                // POSIX classes such as `[:alpha:]` don't open a nested class
+                if depth > 0 && chars.peek().map(|&(_, c)| c) == Some(':') {
+                    for (_, c) in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+
+                depth += 1;
+
+                // `]` right after the opening bracket is a literal
+                if chars.peek().map(|&(_, c)| c) == Some('^') {
+                    chars.next();
+                }
+                if chars.peek().map(|&(_, c)| c) == Some(']') {
+                    chars.next();
+                }
+            }
+            ']' if depth > 0 => depth -= 1,
+            '|' if depth == 0 && groups.is_empty() => alternation = true,
+            '(' if depth == 0 => {
+                let rest = &regex[idx..];
+                let negated = rest.starts_with("(?!");
+
+                groups.push((idx, negated || rest.starts_with("(?=")));
+            }
+            ')' if depth == 0 => match groups.pop() {
+                Some((_, true)) if alternation && groups.is_empty() => {
+                    return Err(
+                        "#[regex]: lookahead is only supported at the end of the pattern, \
+                        wrap the alternatives before it in a group.".into()
+                    );
+                }
+                Some((start, true)) if groups.is_empty() && idx + 1 == regex.len() => {
+                    split = Some(start);
+                }
+                Some((_, true)) => {
+                    return Err("#[regex]: lookahead is only supported at the end of the pattern.".into());
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    Ok(match split {
+        Some(start) => (&regex[..start], Some(Lookahead {
+            negated: &regex[start..start + 3] == "(?!",
+            pattern: regex[start + 3..regex.len() - 1].into(),
+        })),
+        None => (regex, None),
+    })
+}
+
+/// Named fragments declared with `#[logos(subpattern(name = "..."))]`,
+/// which can be referenced in regular expressions as `(?&name)`.
+#[derive(Default)]
+pub struct Subpatterns {
+    map: Vec<(String, String)>,
+}
+
+impl Subpatterns {
+    pub fn add(&mut self, name: String, pattern: &str) -> Result<(), String> {
+        if self.map.iter().any(|(n, _)| *n == name) {
+            return Err(format!("Subpattern `{}` is already declared.", name));
+        }
+
+        let pattern = self.fix(pattern)?;
+
+        self.map.push((name, pattern));
+
+        Ok(())
+    }
+
+    /// Replace all `(?&name)` references in `regex` with their subpatterns.
+    pub fn fix(&self, regex: &str) -> Result<String, String> {
+        let mut fixed = String::with_capacity(regex.len());
+        let mut rest = regex;
+
+        while let Some(idx) = rest.find(['\\', '(']) {
+            fixed.push_str(&rest[..idx]);
+            rest = &rest[idx..];
+
+            if rest.starts_with('\\') {
+                let len = rest[1..].chars().next().map(char::len_utf8).unwrap_or(0) + 1;
+
+                fixed.push_str(&rest[..len]);
+                rest = &rest[len..];
+            } else if rest.starts_with("(?&") {
+                let end = rest.find(')').ok_or_else(|| {
+                    format!("Unterminated subpattern reference in {:?}.", regex)
+                })?;
+                let name = &rest[3..end];
+
+                let pattern = self.map.iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, pattern)| pattern)
+                    .ok_or_else(|| format!("Subpattern `{}` is not declared.", name))?;
+
+                fixed.push_str("(?:");
+                fixed.push_str(pattern);
+                fixed.push(')');
+                rest = &rest[end + 1..];
+            } else {
+                fixed.push('(');
+                rest = &rest[1..];
+            }
+        }
+
+        fixed.push_str(rest);
+
+        Ok(fixed)
+    }
+}
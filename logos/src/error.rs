@@ -0,0 +1,21 @@
+use std::fmt;
+
+use crate::Span;
+
+/// Error returned by `Lexer::try_advance` in place of the `#[error]` token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexError<Slice> {
+    /// Span of the input that didn't match any definition.
+    pub span: Span,
+    /// Slice of the input that didn't match any definition.
+    pub slice: Slice,
+}
+
+impl<Slice: fmt::Debug> fmt::Display for LexError<Slice> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unrecognized input {:?} at {}..{}", self.slice, self.span.start, self.span.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Slice: fmt::Debug> std::error::Error for LexError<Slice> {}
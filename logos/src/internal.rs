@@ -1,4 +1,12 @@
-use crate::source;
+use std::ops::Range;
+
+use crate::source::{self, WithSource};
+use crate::{Filter, Lexer, Logos, Span};
+
+#[cfg(feature = "serde")]
+pub use serde;
+#[cfg(feature = "simd")]
+pub use crate::simd::prefix_len;
 
 /// Trait used by the functions contained in the `Lexicon`.
 ///
@@ -20,7 +28,22 @@ pub trait LexerInternal<'source> {
     fn test_at<T: source::Chunk<'source>, F: FnOnce(T) -> bool>(&self, n: usize, test: F) -> bool;
 
     /// Bump the position by `size`.
-    fn bump(&mut self, size: usize);
+    fn bump_unchecked(&mut self, size: usize);
+
+    /// Move the position back by `size`, giving back the bytes read by a lookahead.
+    fn rewind(&mut self, size: usize);
+
+    /// Bump the position past all bytes, or characters, accepted by the `predicate`.
+    fn bump_while<Arg, P: Predicate<Arg>>(&mut self, predicate: P);
+
+    /// Bump the position past the next occurrence of `terminator`. If there is none,
+    /// bump to the end of input and return `false`.
+    fn bump_until(&mut self, terminator: &[u8]) -> bool;
+
+    /// Bump the position past the `terminator` balancing out an already consumed `opening`,
+    /// counting any further `opening`s in between. If there is none, bump to the end of
+    /// input and return `false`.
+    fn bump_nested(&mut self, opening: &[u8], terminator: &[u8]) -> bool;
 
     /// Reset `token_start` to `token_end`.
     fn trivia(&mut self);
@@ -28,4 +51,178 @@ pub trait LexerInternal<'source> {
     /// Set the current token to appropriate `#[error]` variant.
     /// Guarantee that `token_end` is at char boundary for `&str`.
     fn error(&mut self);
+
+    /// Give up on the token being matched, and set the `#[error]` variant for
+    /// its first character only.
+    fn reject(&mut self);
+
+    /// Look up the current token in a `table` of keywords sorted by their bytes.
+    fn keyword<Token: Clone>(&self, table: &[(&str, Token)]) -> Option<Token>;
+}
+
+/// Trait used by the code produced by `#[derive(Logos)]` to handle values
+/// returned from callbacks.
+///
+/// # WARNING!
+///
+/// **This trait, and it's methods, are not meant to be used outside of the
+/// code produced by `#[derive(Logos)]` macro.**
+pub trait CallbackResult<P, Token: Logos> {
+    /// Construct the token from the value returned by the callback using
+    /// the provided `constructor`, and put it on the `Lexer`.
+    fn construct<'source, Source, Constructor>(
+        self,
+        constructor: Constructor,
+        lex: &mut Lexer<Token, Source>,
+    ) where
+        Source: source::Source<'source>,
+        Token: WithSource<Source>,
+        Constructor: Fn(P) -> Token;
+}
+
+impl<P, Token: Logos> CallbackResult<P, Token> for P {
+    #[inline]
+    fn construct<'source, Source, Constructor>(
+        self,
+        constructor: Constructor,
+        lex: &mut Lexer<Token, Source>,
+    ) where
+        Source: source::Source<'source>,
+        Token: WithSource<Source>,
+        Constructor: Fn(P) -> Token,
+    {
+        lex.token = constructor(self);
+    }
+}
+
+impl<Token: Logos> CallbackResult<(), Token> for Token {
+    #[inline]
+    fn construct<'source, Source, Constructor>(
+        self,
+        _: Constructor,
+        lex: &mut Lexer<Token, Source>,
+    ) where
+        Source: source::Source<'source>,
+        Token: WithSource<Source>,
+        Constructor: Fn(()) -> Token,
+    {
+        lex.set_token(self);
+    }
+}
+
+impl<P, E, Token: Logos> CallbackResult<P, Token> for Result<P, E> {
+    #[inline]
+    fn construct<'source, Source, Constructor>(
+        self,
+        constructor: Constructor,
+        lex: &mut Lexer<Token, Source>,
+    ) where
+        Source: source::Source<'source>,
+        Token: WithSource<Source>,
+        Constructor: Fn(P) -> Token,
+    {
+        match self {
+            Ok(value) => lex.token = constructor(value),
+            Err(_) => lex.error(),
+        }
+    }
+}
+
+impl<P, Token: Logos> CallbackResult<P, Token> for Filter<P> {
+    #[inline]
+    fn construct<'source, Source, Constructor>(
+        self,
+        constructor: Constructor,
+        lex: &mut Lexer<Token, Source>,
+    ) where
+        Source: source::Source<'source>,
+        Token: WithSource<Source>,
+        Constructor: Fn(P) -> Token,
+    {
+        match self {
+            Filter::Emit(value) => lex.token = constructor(value),
+            Filter::Skip => lex.trivia(),
+        }
+    }
+}
+
+/// Trait implemented for the types the `#[error]` variant can hold,
+/// filled with the range of the error.
+///
+/// # WARNING!
+///
+/// **This trait, and it's methods, are not meant to be used outside of the
+/// code produced by `#[derive(Logos)]` macro.**
+pub trait ErrorField: Sized {
+    /// Value held by `Logos::ERROR`.
+    const EMPTY: Self;
+
+    /// Convert the range of an error.
+    fn from_range(range: Range<usize>) -> Self;
+}
+
+impl ErrorField for Range<usize> {
+    const EMPTY: Self = 0..0;
+
+    #[inline]
+    fn from_range(range: Range<usize>) -> Self {
+        range
+    }
+}
+
+impl ErrorField for Span {
+    const EMPTY: Self = Span { start: 0, end: 0 };
+
+    #[inline]
+    fn from_range(range: Range<usize>) -> Self {
+        range.into()
+    }
+}
+
+/// Trait implemented for the functions given to `#[regex(..., class = "...")]`,
+/// testing either single bytes or whole characters.
+///
+/// # WARNING!
+///
+/// **This trait, and it's methods, are not meant to be used outside of the
+/// code produced by `#[derive(Logos)]` macro.**
+pub trait Predicate<Arg> {
+    /// Number of leading bytes accepted by the predicate.
+    fn accepted(&self, bytes: &[u8]) -> usize;
+}
+
+impl<F: Fn(u8) -> bool> Predicate<u8> for F {
+    #[inline]
+    fn accepted(&self, bytes: &[u8]) -> usize {
+        bytes.iter().position(|&byte| !self(byte)).unwrap_or(bytes.len())
+    }
+}
+
+impl<F: Fn(char) -> bool> Predicate<char> for F {
+    #[inline]
+    fn accepted(&self, bytes: &[u8]) -> usize {
+        let mut len = 0;
+
+        while let Some(c) = decode(&bytes[len..]) {
+            if !self(c) {
+                break;
+            }
+            len += c.len_utf8();
+        }
+
+        len
+    }
+}
+
+/// Decode the first character of `bytes`, if they start with valid UTF-8.
+fn decode(bytes: &[u8]) -> Option<char> {
+    let width = match *bytes.first()? {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return None,
+    };
+
+    std::str::from_utf8(bytes.get(..width)?).ok()?.chars().next()
 }
@@ -0,0 +1,120 @@
+//! Adapter for using a `Lexer` as an external lexer of a [LALRPOP](https://github.com/lalrpop/lalrpop)
+//! parser, enabled with the `lalrpop` feature.
+//!
+//! LALRPOP expects an iterator of `Result<(Loc, Tok, Loc), Error>`, which `LalrpopAdapter`
+//! provides with byte offsets for `Loc`, the `Logos` enum for `Tok`, and `LexicalError`
+//! for `Error`. The grammar declares them in its `extern` block:
+//!
+//! ```text
+//! extern {
+//!     type Location = usize;
+//!     type Error = logos::lalrpop::LexicalError;
+//!
+//!     enum Token {
+//!         "+" => Token::Plus,
+//!         "number" => Token::Number(<u64>),
+//!     }
+//! }
+//! ```
+//!
+//! The parser is then called with `Parser::new().parse(LalrpopAdapter::new(lexer))`.
+//! The `#[end]` token ends the iteration, and is never passed to the parser.
+
+use std::fmt;
+use std::mem;
+
+use crate::source::{self, WithSource};
+use crate::{Lexer, Logos, Span};
+
+/// Iterator over the tokens of a `Lexer`, in the form LALRPOP external lexers produce.
+///
+/// ```rust
+/// use logos::Logos;
+/// use logos::lalrpop::{LalrpopAdapter, LexicalError};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// fn main() {
+///     let tokens: Vec<_> = LalrpopAdapter::new(Token::lexer("foo ! bar")).collect();
+///
+///     assert_eq!(tokens, &[
+///         Ok((0, Token::Word, 3)),
+///         Err(LexicalError { span: (4..5).into() }),
+///         Ok((6, Token::Word, 9)),
+///     ]);
+/// }
+/// ```
+pub struct LalrpopAdapter<Token: Logos, Source> {
+    lexer: Lexer<Token, Source>,
+}
+
+/// Error produced by `LalrpopAdapter` in place of the `#[error]` token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexicalError {
+    /// Span of the input that didn't match any definition.
+    pub span: Span,
+}
+
+impl fmt::Display for LexicalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unrecognized input at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LexicalError {}
+
+impl<'source, Token, Source> LalrpopAdapter<Token, Source>
+where
+    Token: Logos + WithSource<Source>,
+    Source: source::Source<'source>,
+{
+    /// Create a new adapter, starting at the current token of the `lexer`.
+    pub fn new(lexer: Lexer<Token, Source>) -> Self {
+        LalrpopAdapter { lexer }
+    }
+
+    /// Get the underlying `Lexer`, positioned at the token the next call to `next`
+    /// is going to return.
+    pub fn lexer(&self) -> &Lexer<Token, Source> {
+        &self.lexer
+    }
+
+    /// Consume the adapter, returning the underlying `Lexer`.
+    pub fn into_lexer(self) -> Lexer<Token, Source> {
+        self.lexer
+    }
+}
+
+impl<'source, Token, Source> Iterator for LalrpopAdapter<Token, Source>
+where
+    Token: Logos + WithSource<Source>,
+    Source: source::Source<'source>,
+{
+    type Item = Result<(usize, Token, usize), LexicalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if mem::discriminant(&self.lexer.token) == mem::discriminant(&Token::END) {
+            return None;
+        }
+
+        let span = self.lexer.span();
+        let token = self.lexer.advance_take();
+
+        if mem::discriminant(&token) == mem::discriminant(&Token::ERROR) {
+            return Some(Err(LexicalError { span }));
+        }
+
+        Some(Ok((span.start, token, span.end)))
+    }
+}
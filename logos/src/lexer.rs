@@ -1,12 +1,19 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use std::cell::Cell;
+use std::mem;
 use std::ops::Range;
 
-use super::internal::LexerInternal;
-use super::Logos;
-use crate::source::{self, Source, WithSource};
+use super::internal::{LexerInternal, Predicate};
+use super::{LexError, Logos, Span};
+use crate::source::{self, Slice, Source, WithSource};
+#[cfg(feature = "alloc")]
+use crate::source::SharedSlice;
+#[cfg(feature = "alloc")]
+use crate::PeekableLexer;
 
 /// `Lexer` is the main struct of the crate that allows you to read through a
 /// `Source` and produce tokens for enums implementing the `Logos` trait.
-#[derive(Clone)]
 pub struct Lexer<Token: Logos, Source> {
     /// Source from which the Lexer is reading tokens.
     pub source: Source,
@@ -14,11 +21,115 @@ pub struct Lexer<Token: Logos, Source> {
     /// Current token. Call the `advance` method to get a new token.
     pub token: Token,
 
-    /// Extras associated with the `Token`.
+    /// Extras associated with the `Token`. Prefer the `extras` and `extras_mut`
+    /// methods, this field may become private in the future.
     pub extras: Token::Extras,
 
     token_start: usize,
     token_end: usize,
+    /// Window of the `Source` being lexed, see `Lexer::new_in`.
+    window_start: usize,
+    window_end: usize,
+    /// Offset of the `Source` in the input read by a `StreamingLexer`.
+    offset: usize,
+    /// Set when reading past the end of the window was attempted, for a `StreamingLexer`
+    /// to know the token might continue past its buffer.
+    touched_end: Cell<bool>,
+    lines: Cell<Lines>,
+    queued: Option<(Token, Range<usize>)>,
+    last: Option<Token>,
+    errors: usize,
+    /// Set when input has been skipped, for `Logos::lex` to start over after it.
+    skipped: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<'source, Token, Shared> Lexer<Token, &'source Shared>
+where
+    Token: Logos + WithSource<&'source Shared>,
+    &'source Shared: Source<'source>,
+    Shared: Clone + std::ops::Deref<Target = str>,
+{
+    /// Get a slice of the current token that keeps the reference counted source
+    /// alive, instead of borrowing from it, see `SharedSlice`.
+    #[inline]
+    pub fn shared_slice(&self) -> SharedSlice<Shared> {
+        SharedSlice::new(Shared::clone(self.source), self.range())
+            .expect("Token ranges are always on char boundaries")
+    }
+}
+
+/// Cloning a `Lexer` forks it into an independent cursor over the same source, which
+/// can be advanced without affecting the original. For `&str` and `&[u8]` sources this
+/// is O(1), only copying the source reference and offsets along with cloning the token
+/// and `Extras`, making it cheap to backtrack to an earlier point.
+///
+/// ```rust
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+///
+///     #[token = "("]
+///     ParenOpen,
+/// }
+///
+/// fn main() {
+///     let mut lex = Token::lexer("call (args");
+///
+///     // Speculatively look past the identifier
+///     let mut fork = lex.clone();
+///     fork.advance();
+///
+///     assert_eq!(fork.token, Token::ParenOpen);
+///     assert_eq!(lex.token, Token::Word);
+///     assert_eq!(lex.slice(), "call");
+/// }
+/// ```
+impl<Token, Source> Clone for Lexer<Token, Source>
+where
+    Token: Logos + Clone,
+    Token::Extras: Clone,
+    Source: Clone,
+{
+    fn clone(&self) -> Self {
+        Lexer {
+            source: self.source.clone(),
+            token: self.token.clone(),
+            extras: self.extras.clone(),
+            token_start: self.token_start,
+            token_end: self.token_end,
+            window_start: self.window_start,
+            window_end: self.window_end,
+            offset: self.offset,
+            touched_end: self.touched_end.clone(),
+            lines: self.lines.clone(),
+            queued: self.queued.clone(),
+            last: self.last.clone(),
+            errors: self.errors,
+            skipped: self.skipped,
+        }
+    }
+}
+
+/// Cache used by `Lexer::position` so that consecutive calls only need
+/// to scan the source from the previously queried position.
+#[derive(Clone, Copy, Default)]
+struct Lines {
+    /// Offset up to which the source has been scanned for line breaks.
+    scanned: usize,
+    /// Number of line breaks found before `scanned`.
+    breaks: usize,
+    /// Offset at which the last found line starts.
+    line_start: usize,
 }
 
 impl<'source, Token, Source> Lexer<Token, Source>
@@ -26,49 +137,864 @@ where
     Token: self::Logos + WithSource<Source>,
     Source: self::Source<'source>,
 {
-    /// Create a new `Lexer`.
+    /// Create a new `Lexer`, with default `Extras`.
     ///
     /// Due to type inference, it might be more ergonomic to construct
     /// it by calling `Token::lexer(source)`, where `Token` implements `Logos`.
-    pub fn new(source: Source) -> Self {
-        let mut lex = Lexer {
+    pub fn new(source: Source) -> Self
+    where
+        Token::Extras: Default,
+    {
+        Lexer::with_extras(source, Default::default())
+    }
+
+    /// Create a new `Lexer` with given `Extras`, such as ones seeded with configuration
+    /// that callbacks need. Unlike `Lexer::new`, this doesn't require the `Extras` to
+    /// implement `Default`.
+    ///
+    /// ```rust
+    /// use logos::{Extras, Lexer, Logos};
+    ///
+    /// struct Limits {
+    ///     max_words: usize,
+    ///     words: usize,
+    /// }
+    ///
+    /// impl Extras for Limits {}
+    ///
+    /// fn word<S>(lex: &mut Lexer<Token, S>) -> Result<(), ()> {
+    ///     lex.extras.words += 1;
+    ///
+    ///     if lex.extras.words > lex.extras.max_words {
+    ///         Err(())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[extras = "Limits"]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex("[a-z]+", callback = "word")]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer_with_extras("one two", Limits { max_words: 1, words: 0 });
+    ///
+    ///     assert_eq!(lex.token, Token::Word);
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.token, Token::Error);
+    ///     assert_eq!(lex.slice(), "two");
+    /// }
+    /// ```
+    pub fn with_extras(source: Source, extras: Token::Extras) -> Self {
+        let mut lex = Lexer::prepare(source, extras);
+
+        lex.on_source();
+        lex.lex_next();
+
+        lex
+    }
+
+    /// Create a new `Lexer` that skips a UTF-8 byte order mark (`EF BB BF`) at the
+    /// start of the `source`, if there is one. Ranges of all tokens are still byte
+    /// offsets into the whole `source`, so the first token starts at 3 after a skipped
+    /// mark. Definitions anchored with `^` match right after a skipped mark.
+    ///
+    /// Due to type inference, it might be more ergonomic to construct it by calling
+    /// `Token::lexer_skip_bom(source)`.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let lex = Token::lexer_skip_bom("\u{FEFF}foo");
+    ///
+    ///     assert_eq!(lex.token, Token::Word);
+    ///     assert_eq!(lex.range(), 3..6);
+    ///
+    ///     let lex = Token::lexer_skip_bom("\u{FEFF}");
+    ///
+    ///     assert_eq!(lex.token, Token::End);
+    ///     assert_eq!(lex.range(), 3..3);
+    /// }
+    /// ```
+    pub fn new_skip_bom(source: Source) -> Self
+    where
+        Token::Extras: Default,
+    {
+        let mut lex = Lexer::prepare(source, Default::default());
+
+        lex.on_source();
+
+        if lex.read::<&[u8; 3]>() == Some(&[0xEF, 0xBB, 0xBF]) {
+            lex.window_start = 3;
+            lex.token_end = 3;
+        }
+
+        lex.lex_next();
+
+        lex
+    }
+
+    /// Create a new `Lexer` that only lexes the `range` of the `source`, producing the
+    /// `#[end]` token at the end of the `range`. Ranges of all tokens are still byte
+    /// offsets into the whole `source`, and definitions anchored with `^` match at the
+    /// start of the `range`. `Extras::on_source` is still called with the whole `source`.
+    ///
+    /// Due to type inference, it might be more ergonomic to construct it by calling
+    /// `Token::lexer_in(source, range)`.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let source = "foo bar\nbaz";
+    ///     let mut lex = Token::lexer_in(source, 4..7);
+    ///
+    ///     assert_eq!(lex.token, Token::Word);
+    ///     assert_eq!(lex.slice(), "bar");
+    ///     assert_eq!(lex.range(), 4..7);
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.token, Token::End);
+    ///     assert_eq!(lex.range(), 7..7);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `range` ends beyond the end of the `source`, or if either of its
+    /// bounds is in the middle of a UTF-8 code point (does not apply when lexing binary
+    /// sources such as `&[u8]`).
+    pub fn new_in(source: Source, range: Range<usize>) -> Self
+    where
+        Token::Extras: Default,
+    {
+        assert!(
+            range.start <= range.end
+                && range.end <= source.len()
+                && source.find_boundary(range.start) == range.start
+                && source.find_boundary(range.end) == range.end,
+            "Invalid Lexer window",
+        );
+
+        let mut lex = Lexer::prepare(source, Default::default());
+
+        lex.on_source();
+        lex.window_start = range.start;
+        lex.window_end = range.end;
+        lex.token_end = range.start;
+        lex.lex_next();
+
+        lex
+    }
+
+    /// Show the whole `Source` to the `Extras`, see `Extras::on_source`.
+    fn on_source(&mut self) {
+        let bytes = unsafe { self.source.slice_unchecked(0..self.source.len()) }.as_bytes();
+
+        self.extras.on_source(bytes);
+    }
+
+    /// Create a new `Lexer` with given `Extras`, without producing a token.
+    pub(crate) fn prepare(source: Source, extras: Token::Extras) -> Self {
+        let window_end = source.len();
+
+        Lexer {
             source,
             token: Token::ERROR,
-            extras: Default::default(),
+            extras,
             token_start: 0,
             token_end: 0,
-        };
+            window_start: 0,
+            window_end,
+            offset: 0,
+            touched_end: Cell::default(),
+            lines: Cell::default(),
+            queued: None,
+            last: None,
+            errors: 0,
+            skipped: false,
+        }
+    }
+
+    /// Advance the `Lexer`, moving out the token it was at, which is still available
+    /// through `last` to callbacks producing the next token.
+    #[cfg(any(feature = "alloc", feature = "lalrpop"))]
+    pub(crate) fn advance_take(&mut self) -> Token {
+        self.advance();
 
-        lex.advance();
+        self.last.take().expect("Advancing moves the current token to `last`")
+    }
+
+    /// Create a new `Lexer` for the part of a stream starting at `offset`, with given
+    /// `Extras`, without producing a token.
+    #[cfg(feature = "std")]
+    pub(crate) fn prepare_at(source: Source, extras: Token::Extras, offset: usize) -> Self {
+        let mut lex = Lexer::prepare(source, extras);
 
+        lex.offset = offset;
         lex
     }
 
+    /// Break the `Lexer` into the current token, extras, token range, and the token
+    /// queued with `Lexer::emit_next`, if any.
+    #[cfg(feature = "std")]
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn into_parts(self) -> (Token, Token::Extras, Range<usize>, Option<(Token, Range<usize>)>) {
+        let range = self.range();
+
+        (self.token, self.extras, range, self.queued)
+    }
+
+    /// Check if producing the current token attempted to read past the end of the window.
+    #[cfg(feature = "std")]
+    pub(crate) fn touched_end(&self) -> bool {
+        self.touched_end.get()
+    }
+
+    /// Point the `Lexer` at a new `source`, starting over from its first token.
+    ///
+    /// Unlike creating a new `Lexer`, this keeps the `Extras` around, calling
+    /// `Extras::reset` on them instead, so that any buffers they hold can be reused.
+    pub fn reset(&mut self, source: Source)
+    where
+        Token::Extras: Default,
+    {
+        self.window_start = 0;
+        self.window_end = source.len();
+        self.source = source;
+        self.token_start = 0;
+        self.token_end = 0;
+        self.lines = Cell::default();
+        self.queued = None;
+        self.last = None;
+        self.errors = 0;
+        self.extras.reset();
+        self.on_source();
+
+        self.lex_next();
+    }
+
     /// Advance the `Lexer` and attempt to produce the next `Token`.
     #[inline]
     pub fn advance(&mut self) {
+        self.last = Some(mem::replace(&mut self.token, Token::ERROR));
+        self.lex_next();
+    }
+
+    /// Advance the `Lexer`, returning the next `Token`, or a `LexError` in place of the
+    /// `#[error]` token. This suits parsers that treat lexical errors as hard failures
+    /// and propagate them with `?`, while `advance` keeps errors in the token stream.
+    ///
+    /// ```rust
+    /// use logos::{LexError, Logos, Span};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(trivia = " ")]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo bar ! baz");
+    ///
+    ///     assert_eq!(lex.try_advance(), Ok(&Token::Word));
+    ///     assert_eq!(lex.try_advance(), Err(LexError { span: Span::new(8, 9), slice: "!" }));
+    ///     assert_eq!(lex.try_advance(), Ok(&Token::Word));
+    ///     assert_eq!(lex.try_advance(), Ok(&Token::End));
+    /// }
+    /// ```
+    pub fn try_advance(&mut self) -> Result<&Token, LexError<Source::Slice>> {
+        self.advance();
+
+        if mem::discriminant(&self.token) == mem::discriminant(&Token::ERROR) && !self.is_at_end() {
+            return Err(LexError {
+                span: self.span(),
+                slice: self.slice(),
+            });
+        }
+
+        Ok(&self.token)
+    }
+
+    /// Produce the next `Token`, without keeping the current one as the `last` token,
+    /// which would only be a placeholder when creating a `Lexer`.
+    #[inline]
+    fn lex_next(&mut self) {
         self.token_start = self.token_end;
         self.extras.on_advance();
 
-        Token::lex(self);
+        match self.queued.take() {
+            Some((token, range)) => {
+                self.set_token(token);
+                self.token_start = range.start;
+                self.token_end = range.end;
+            }
+            None => Token::lex(self),
+        }
+
+        let slice = self.slice();
+
+        self.extras.on_token(&self.token, &slice);
+    }
+
+    /// If the current token is the `#[error]` token, extend it over all errors directly
+    /// following it, so that a run of unrecognized input is reported as a single error.
+    /// Lexing resumes at the first position a token, or skipped input, can start at.
+    ///
+    /// To look ahead, the remaining source is lexed with a separate `Lexer`, using a clone
+    /// of the `Extras`, so callbacks may be invoked on input following the error. Changes
+    /// they make to the `Extras` are discarded.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("@#%foo");
+    ///
+    ///     assert_eq!(lex.token, Token::Error);
+    ///     assert_eq!(lex.slice(), "@");
+    ///
+    ///     lex.recover();
+    ///
+    ///     assert_eq!(lex.token, Token::Error);
+    ///     assert_eq!(lex.slice(), "@#%");
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.token, Token::Word);
+    ///     assert_eq!(lex.slice(), "foo");
+    /// }
+    /// ```
+    pub fn recover(&mut self)
+    where
+        Source: Clone,
+        Token::Extras: Clone,
+    {
+        let error = mem::discriminant(&Token::ERROR);
+
+        if mem::discriminant(&self.token) != error {
+            return;
+        }
+
+        let mut probe = Lexer::<Token, Source>::prepare(self.source.clone(), self.extras.clone());
+
+        probe.window_start = self.window_start;
+        probe.window_end = self.window_end;
+        probe.offset = self.offset;
+
+        loop {
+            probe.token_start = self.token_end;
+            probe.token_end = self.token_end;
+
+            Token::lex(&mut probe);
+
+            // An empty token is the end of input, which can share its variant with errors
+            if probe.token_start != self.token_end
+                || probe.token_end == self.token_end
+                || mem::discriminant(&probe.token) != error
+            {
+                break;
+            }
+
+            self.token_end = probe.token_end;
+        }
+
+        self.token = Token::error(self.range());
     }
 
     /// Get the range for the current token in `Source`.
+    ///
+    /// For the `#[error]` token this always covers at least one whole character
+    /// of the source, see the crate documentation for details.
     #[inline]
     pub fn range(&self) -> Range<usize> {
         self.token_start..self.token_end
     }
 
+    /// Get the `Span` of the current token in `Source`, same as `range`.
+    #[inline]
+    pub fn span(&self) -> Span {
+        Span::new(self.token_start, self.token_end)
+    }
+
     /// Get a string slice of the current token.
     #[inline]
     pub fn slice(&self) -> Source::Slice {
         unsafe { self.source.slice_unchecked(self.range()) }
     }
 
+    /// Get a slice of the current token along with its range in `Source`, for
+    /// callbacks that need both, such as to key interned symbols by position.
+    ///
+    /// ```rust
+    /// use logos::{Lexer, Logos, Source};
+    ///
+    /// fn offset<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> usize {
+    ///     let (_, range) = lex.slice_span();
+    ///
+    ///     range.start
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex("[a-z]+", callback = "offset")]
+    ///     Word(usize),
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo bar");
+    ///
+    ///     assert_eq!(lex.slice_span(), ("foo", 0..3));
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.token, Token::Word(4));
+    ///     assert_eq!(lex.slice_span(), ("bar", 4..7));
+    /// }
+    /// ```
+    #[inline]
+    pub fn slice_span(&self) -> (Source::Slice, Range<usize>) {
+        (self.slice(), self.range())
+    }
+
+    /// Get the whole `Source` this lexer is reading from, such as for slicing
+    /// an arbitrary earlier range when reporting an error.
+    ///
+    /// ```rust
+    /// use logos::{Logos, Source};
+    ///
+    /// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo bar");
+    ///     let start = lex.range().start;
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.source(), &"foo bar");
+    ///     assert_eq!(lex.source().slice(start..lex.range().end), Some("foo bar"));
+    /// }
+    /// ```
+    #[inline]
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// Get the token produced before the current one, if there was any. In callbacks this
+    /// is the last token produced before the one being lexed, which helps with decisions
+    /// that depend on context, such as whether `/` divides or starts a regex literal in
+    /// JavaScript. Skipped input doesn't count as a token.
+    ///
+    /// ```rust
+    /// use logos::{Lexer, Logos, Slice, Source};
+    ///
+    /// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    /// #[logos(trivia = " ")]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex("[a-z0-9]+")]
+    ///     Value,
+    ///
+    ///     #[token("=")]
+    ///     Assign,
+    ///
+    ///     #[token("/", callback = "slash")]
+    ///     Divide,
+    ///
+    ///     Regex,
+    /// }
+    ///
+    /// fn slash<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) -> Token {
+    ///     if lex.last() == Some(&Token::Value) {
+    ///         return Token::Divide;
+    ///     }
+    ///
+    ///     let len = lex.remainder().as_bytes().iter().position(|&byte| byte == b'/');
+    ///
+    ///     match len {
+    ///         Some(len) => {
+    ///             lex.bump(len + 1);
+    ///             Token::Regex
+    ///         }
+    ///         None => Token::Error,
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("a = b / 2 = /a b/");
+    ///
+    ///     for (token, range) in [
+    ///         (Token::Value, 0..1),
+    ///         (Token::Assign, 2..3),
+    ///         (Token::Value, 4..5),
+    ///         (Token::Divide, 6..7),
+    ///         (Token::Value, 8..9),
+    ///         (Token::Assign, 10..11),
+    ///         (Token::Regex, 12..17),
+    ///     ] {
+    ///         assert_eq!((lex.token, lex.range()), (token, range));
+    ///         lex.advance();
+    ///     }
+    ///
+    ///     assert_eq!(lex.token, Token::End);
+    /// }
+    /// ```
+    #[inline]
+    pub fn last(&self) -> Option<&Token> {
+        self.last.as_ref()
+    }
+
+    /// Get the number of `#[error]` tokens produced so far, including the current token,
+    /// such as to give up on input with too many errors without collecting the tokens.
+    /// A run of errors merged by `recover` counts as a single error.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo @ bar # baz");
+    ///
+    ///     while lex.token != Token::End {
+    ///         lex.advance();
+    ///     }
+    ///
+    ///     assert_eq!(lex.error_count(), 2);
+    /// }
+    /// ```
+    #[inline]
+    pub fn error_count(&self) -> usize {
+        self.errors
+    }
+
+    /// Set the current token, counting it if it's the `#[error]` token. Tokens produced
+    /// by the generated code are counted by `LexerInternal::error` instead.
+    #[inline]
+    pub(crate) fn set_token(&mut self, token: Token) {
+        if mem::discriminant(&token) == mem::discriminant(&Token::ERROR) {
+            self.errors += 1;
+        }
+
+        self.token = token;
+    }
+
+    /// Check whether input has been skipped since the last call, clearing the flag.
+    #[inline]
+    pub(crate) fn take_skipped(&mut self) -> bool {
+        mem::replace(&mut self.skipped, false)
+    }
+
+    /// Get the `Extras` associated with the `Token`.
+    #[inline]
+    pub fn extras(&self) -> &Token::Extras {
+        &self.extras
+    }
+
+    /// Get mutable access to the `Extras` associated with the `Token`, such as for
+    /// resetting state before lexing a new section of the source.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut Token::Extras {
+        &mut self.extras
+    }
+
+    /// Get the line and column at which the current token starts, both 1-based.
+    ///
+    /// Lines are broken on `\n`, so `\r\n` counts as a single line break.
+    /// Columns count UTF-8 characters rather than bytes.
+    ///
+    /// Line breaks are counted lazily, scanning the source from the position of
+    /// the previous call, so there is no overhead if this is never used.
+    pub fn position(&self) -> (usize, usize) {
+        let mut lines = self.lines.get();
+
+        if self.token_start < lines.scanned {
+            lines = Lines::default();
+        }
+
+        let scan = unsafe { self.source.slice_unchecked(lines.scanned..self.token_start) };
+
+        for (idx, &byte) in scan.as_bytes().iter().enumerate() {
+            if byte == b'\n' {
+                lines.breaks += 1;
+                lines.line_start = lines.scanned + idx + 1;
+            }
+        }
+
+        lines.scanned = self.token_start;
+        self.lines.set(lines);
+
+        let line = unsafe { self.source.slice_unchecked(lines.line_start..self.token_start) };
+
+        (lines.breaks + 1, source::column(line.as_bytes()))
+    }
+
+    /// Get a slice of remaining source, starting at the end of the current token.
+    ///
+    /// Once the `#[end]` token has been produced this will be an empty slice.
+    #[inline]
+    pub fn remainder(&self) -> Source::Slice {
+        let start = self.source.find_boundary(self.token_end);
+
+        unsafe { self.source.slice_unchecked(start..self.window_end) }
+    }
+
+    /// Check if the current token starts at the beginning of the `Source`, of the window
+    /// given to `Lexer::new_in`, or right after a mark skipped by `Lexer::new_skip_bom`,
+    /// which is where definitions anchored with `^` can match. This is `false` for the
+    /// first token if any input was skipped before it, and for any token past the start
+    /// of the input of a `StreamingLexer`.
+    #[inline]
+    pub fn at_start(&self) -> bool {
+        self.offset + self.token_start == self.window_start
+    }
+
+    /// Check if the whole `Source`, or the window given to `Lexer::new_in`, has been
+    /// consumed, which is when the current token is the `#[end]` token. Unlike comparing
+    /// the token with `Logos::END`, this doesn't require the token type to implement
+    /// `PartialEq`.
+    #[inline]
+    pub fn is_at_end(&self) -> bool {
+        self.token_start == self.window_end
+    }
+
+    /// Bump the end of the current token by `n` bytes. The next call to `advance`
+    /// will start lexing from the new position.
+    ///
+    /// This is useful in callbacks for consuming input that can't be matched by
+    /// the token definitions themselves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if adding `n` to the current offset would place the `Lexer` beyond
+    /// the end of the `Source`, or in the middle of a UTF-8 code point (does not
+    /// apply when lexing binary sources such as `&[u8]`).
+    pub fn bump(&mut self, n: usize) {
+        let end = self.token_end + n;
+
+        assert!(
+            end <= self.window_end && self.source.find_boundary(end) == end,
+            "Invalid Lexer bump",
+        );
+
+        self.token_end = end;
+    }
+
+    /// Bump the end of the current token past the next occurrence of `terminator`,
+    /// returning `true`. If there is none, bump to the end of input and return `false`.
+    ///
+    /// This works like the `until` option of a definition, for terminators that depend
+    /// on the matched input, such as the closing `"##` of a Rust raw string opened with
+    /// `r##"`. Returning `Err` from the callback when this returns `false` produces the
+    /// `#[error]` token spanning the unterminated token through the end of input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `terminator` is empty, or if it ends in the middle of a UTF-8 code point
+    /// of the `Source` (does not apply when lexing binary sources such as `&[u8]`).
+    pub fn bump_past(&mut self, terminator: &[u8]) -> bool {
+        assert!(!terminator.is_empty(), "The terminator can't be empty");
+
+        let found = self.bump_until(terminator);
+
+        assert!(
+            self.source.find_boundary(self.token_end) == self.token_end,
+            "Invalid Lexer bump",
+        );
+
+        found
+    }
+
+    /// Get the byte right after the end of the current token, without consuming it.
+    /// Returns `None` at the end of input.
+    ///
+    /// This is useful in callbacks that need to look ahead to decide how much input
+    /// to `bump` or what token to produce.
+    #[inline]
+    pub fn peek_byte(&self) -> Option<u8> {
+        self.read_window(self.token_end)
+    }
+
+    /// Get the next `N` bytes after the end of the current token, without consuming
+    /// them. Returns `None` if fewer than `N` bytes are left before the end of input.
+    ///
+    /// `N` can be anywhere from 1 to 32.
+    #[inline]
+    pub fn peek_bytes<const N: usize>(&self) -> Option<[u8; N]>
+    where
+        &'source [u8; N]: source::Chunk<'source>,
+    {
+        self.read_window::<&'source [u8; N]>(self.token_end).copied()
+    }
+
+    /// Queue up a `token` spanning `range` to be produced by the next call to
+    /// `advance`, instead of lexing it from the source. Lexing resumes at the end
+    /// of `range` after that.
+    ///
+    /// This is useful in callbacks for splitting a single match into multiple
+    /// tokens. If `range` starts before the end of the current token, the current
+    /// token is shortened to end where `range` starts.
+    ///
+    /// ```rust
+    /// use logos::{Lexer, Logos};
+    ///
+    /// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[token(">>", callback = "split")]
+    ///     ShiftRight,
+    ///
+    ///     #[token = ">"]
+    ///     Greater,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Ident,
+    /// }
+    ///
+    /// fn split<'source, S: logos::Source<'source>>(lex: &mut Lexer<Token, S>) -> Token {
+    ///     let end = lex.range().end;
+    ///
+    ///     lex.emit_next(Token::Greater, end - 1..end);
+    ///
+    ///     Token::Greater
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("a>>b");
+    ///
+    ///     for (token, range) in [
+    ///         (Token::Ident, 0..1),
+    ///         (Token::Greater, 1..2),
+    ///         (Token::Greater, 2..3),
+    ///         (Token::Ident, 3..4),
+    ///     ] {
+    ///         assert_eq!((lex.token, lex.range()), (token, range));
+    ///         lex.advance();
+    ///     }
+    ///
+    ///     assert_eq!(lex.token, Token::End);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a token is already queued, if `range` starts before the current
+    /// token or after its end, if it ends beyond the end of the `Source`, or if either
+    /// of its bounds is in the middle of a UTF-8 code point (does not apply when lexing
+    /// binary sources such as `&[u8]`).
+    pub fn emit_next(&mut self, token: Token, range: Range<usize>) {
+        assert!(self.queued.is_none(), "A token is already queued");
+        assert!(
+            self.token_start <= range.start
+                && range.start <= self.token_end
+                && range.start <= range.end
+                && range.end <= self.window_end
+                && self.source.find_boundary(range.start) == range.start
+                && self.source.find_boundary(range.end) == range.end,
+            "Invalid range for a queued token",
+        );
+
+        self.token_end = range.start;
+        self.queued = Some((token, range));
+    }
+
     /// Turn this lexer into a lexer for a new token type.
     ///
     /// The new lexer continues to point at the same span as the current lexer,
     /// and the current token becomes the error token of the new token type.
+    /// A token queued with `Lexer::emit_next` is dropped.
     /// If you want to start reading from the new lexer immediately,
     /// consider using `Lexer::advance_as` instead.
     pub fn morph<Token2>(self) -> Lexer<Token2, Source>
@@ -78,10 +1004,19 @@ where
     {
         Lexer {
             source: self.source,
-            token: Token2::ERROR,
+            token: Token2::error(self.token_start..self.token_end),
             extras: self.extras.into(),
             token_start: self.token_start,
             token_end: self.token_end,
+            window_start: self.window_start,
+            window_end: self.window_end,
+            offset: self.offset,
+            touched_end: self.touched_end,
+            lines: self.lines,
+            queued: None,
+            last: None,
+            errors: self.errors,
+            skipped: self.skipped,
         }
     }
 
@@ -95,15 +1030,68 @@ where
         Token::Extras: Into<Token2::Extras>,
     {
         let mut lex = self.morph();
-        lex.advance();
+        lex.lex_next();
         lex
     }
+
+    /// Drive the lexer to completion, collecting the current token and all following
+    /// ones along with their ranges, up to but excluding the `#[end]` token. The
+    /// `#[error]` token is included wherever the input couldn't be matched.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let tokens = Token::lexer("foo @ bar").into_vec();
+    ///
+    ///     assert_eq!(tokens, &[
+    ///         (Token::Word, 0..3),
+    ///         (Token::Error, 4..5),
+    ///         (Token::Word, 6..9),
+    ///     ]);
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_vec(mut self) -> Vec<(Token, Range<usize>)> {
+        let end = mem::discriminant(&Token::END);
+        let mut tokens = Vec::new();
+
+        while mem::discriminant(&self.token) != end {
+            let range = self.range();
+
+            tokens.push((self.advance_take(), range));
+        }
+
+        tokens
+    }
+
+    /// Turn this lexer into a `PeekableLexer`, which can look up to `k` tokens
+    /// past the current one.
+    #[cfg(feature = "alloc")]
+    pub fn peekable(self, k: usize) -> PeekableLexer<Token, Source>
+    where
+        Token::Extras: Clone,
+    {
+        PeekableLexer::new(self, k)
+    }
 }
 
 /// Helper trait that can be injected into the `Lexer` to handle things that
 /// aren't necessarily tokens, such as comments or Automatic Semicolon Insertion
 /// in JavaScript.
-pub trait Extras: Sized + Default {
+pub trait Extras: Sized {
     /// Method called by the `Lexer` when a new token is about to be produced.
     #[inline]
     fn on_advance(&mut self) {}
@@ -111,11 +1099,149 @@ pub trait Extras: Sized + Default {
     /// Method called by the `Lexer` when a white space byte has been encountered.
     #[inline]
     fn on_whitespace(&mut self) {}
+
+    /// Method called by the `Lexer` after a token has been produced, including the
+    /// `#[error]` and `#[end]` tokens, along with its slice of the source. Since the
+    /// same `Extras` can be used with multiple token enums, the token can be told
+    /// apart with `Logos::name` or `Logos::discriminant`.
+    ///
+    /// ```rust
+    /// use logos::{Extras, Logos, Slice};
+    ///
+    /// #[derive(Default)]
+    /// struct Stats {
+    ///     words: usize,
+    ///     longest: usize,
+    /// }
+    ///
+    /// impl Extras for Stats {
+    ///     fn on_token<'source, T: Logos, S: Slice<'source>>(&mut self, token: &T, slice: &S) {
+    ///         if token.name() == "Word" {
+    ///             self.words += 1;
+    ///             self.longest = self.longest.max(slice.as_bytes().len());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[extras = "Stats"]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("lorem ipsum dolor sit");
+    ///
+    ///     while lex.token != Token::End {
+    ///         lex.advance();
+    ///     }
+    ///
+    ///     assert_eq!(lex.extras.words, 4);
+    ///     assert_eq!(lex.extras.longest, 5);
+    /// }
+    /// ```
+    #[inline]
+    fn on_token<'source, Token, Slice>(&mut self, _token: &Token, _slice: &Slice)
+    where
+        Token: self::Logos,
+        Slice: source::Slice<'source>,
+    {
+    }
+
+    /// Method called by the `Lexer` with the bytes of the whole source when it's created,
+    /// or pointed at a new source by `Lexer::reset`, before producing the first token.
+    /// Override it for `Extras` that depend on the source, such as an index of the lines
+    /// in it.
+    ///
+    /// ```rust
+    /// use logos::{Extras, Logos};
+    ///
+    /// #[derive(Default)]
+    /// struct Lines {
+    ///     starts: Vec<usize>,
+    /// }
+    ///
+    /// impl Extras for Lines {
+    ///     fn on_source(&mut self, source: &[u8]) {
+    ///         let breaks = source.iter().enumerate().filter(|(_, &byte)| byte == b'\n');
+    ///
+    ///         self.starts.clear();
+    ///         self.starts.push(0);
+    ///         self.starts.extend(breaks.map(|(idx, _)| idx + 1));
+    ///     }
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[extras = "Lines"]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo\nbar\nbaz");
+    ///
+    ///     assert_eq!(lex.extras.starts, &[0, 4, 8]);
+    ///
+    ///     lex.reset("foo\nbar");
+    ///
+    ///     assert_eq!(lex.extras.starts, &[0, 4]);
+    /// }
+    /// ```
+    ///
+    /// A `StreamingLexer`, which never has the whole source at hand, doesn't call it.
+    #[inline]
+    fn on_source(&mut self, _source: &[u8]) {}
+
+    /// Method called by `Lexer::reset`, before `on_source` with the new source. By default
+    /// this replaces `self` with `Default::default()`, override it to clear any buffers
+    /// without freeing them.
+    #[inline]
+    fn reset(&mut self)
+    where
+        Self: Default,
+    {
+        *self = Self::default();
+    }
 }
 
 /// Default `Extras` with no logic
 impl Extras for () {}
 
+impl<'source, Token, Source> Lexer<Token, Source>
+where
+    Token: self::Logos,
+    Source: self::Source<'source>,
+{
+    /// Read a `Chunk` at `offset`, unless it extends past the end of the window.
+    #[inline]
+    fn read_window<Chunk>(&self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: source::Chunk<'source>,
+    {
+        match offset + Chunk::SIZE <= self.window_end {
+            true => self.source.read(offset),
+            false => {
+                self.touched_end.set(true);
+                None
+            }
+        }
+    }
+}
+
 #[doc(hidden)]
 /// # WARNING!
 ///
@@ -133,7 +1259,7 @@ where
     where
         Chunk: source::Chunk<'source>,
     {
-        self.source.read(self.token_end)
+        self.read_window(self.token_end)
     }
 
     /// Read a `Chunk` at a position offset by `n`.
@@ -142,7 +1268,7 @@ where
     where
         Chunk: source::Chunk<'source>,
     {
-        self.source.read(self.token_end + n)
+        self.read_window(self.token_end + n)
     }
 
     /// Test a chunk at current position with a closure.
@@ -152,7 +1278,7 @@ where
         T: source::Chunk<'source>,
         F: FnOnce(T) -> bool,
     {
-        match self.source.read::<T>(self.token_end) {
+        match self.read_window::<T>(self.token_end) {
             Some(chunk) => test(chunk),
             None => false,
         }
@@ -165,7 +1291,7 @@ where
         T: source::Chunk<'source>,
         F: FnOnce(T) -> bool,
     {
-        match self.source.read::<T>(self.token_end + n) {
+        match self.read_window::<T>(self.token_end + n) {
             Some(chunk) => test(chunk),
             None => false,
         }
@@ -173,20 +1299,100 @@ where
 
     /// Bump the position `Lexer` is reading from by `size`.
     #[inline]
-    fn bump(&mut self, size: usize) {
+    fn bump_unchecked(&mut self, size: usize) {
         debug_assert!(
-            self.token_end + size <= self.source.len(),
+            self.token_end + size <= self.window_end,
             "Bumping out of bounds!"
         );
 
         self.token_end += size;
     }
 
-    /// Reset `token_start` to `token_end`.
+    /// Move the position `Lexer` is reading from back by `size`.
+    #[inline]
+    fn rewind(&mut self, size: usize) {
+        debug_assert!(
+            self.token_start + size <= self.token_end,
+            "Rewinding past the start of the token!"
+        );
+
+        self.token_end -= size;
+    }
+
+    /// Bump the position past all bytes, or characters, accepted by the `predicate`.
+    #[inline]
+    fn bump_while<Arg, P: Predicate<Arg>>(&mut self, predicate: P) {
+        let rest = unsafe { self.source.slice_unchecked(self.token_end..self.window_end) };
+        let mut end = self.token_end + predicate.accepted(rest.as_bytes());
+
+        if end == self.window_end {
+            self.touched_end.set(true);
+        }
+
+        // Byte predicates can accept part of a character, which `&str` can't end on
+        while self.source.find_boundary(end) != end {
+            end -= 1;
+        }
+
+        self.token_end = end;
+    }
+
+    /// Bump the position past the next occurrence of `terminator`. If there is none,
+    /// bump to the end of input and return `false`.
+    #[inline]
+    fn bump_until(&mut self, terminator: &[u8]) -> bool {
+        let rest = unsafe { self.source.slice_unchecked(self.token_end..self.window_end) };
+
+        match rest.as_bytes().windows(terminator.len()).position(|window| window == terminator) {
+            Some(pos) => {
+                self.token_end += pos + terminator.len();
+                true
+            },
+            None => {
+                self.token_end = self.window_end;
+                self.touched_end.set(true);
+                false
+            },
+        }
+    }
+
+    /// Bump the position past the `terminator` balancing out an already consumed `opening`,
+    /// counting any further `opening`s in between. If there is none, bump to the end of
+    /// input and return `false`.
+    fn bump_nested(&mut self, opening: &[u8], terminator: &[u8]) -> bool {
+        let rest = unsafe { self.source.slice_unchecked(self.token_end..self.window_end) };
+        let rest = rest.as_bytes();
+        let mut depth = 1;
+        let mut pos = 0;
+
+        while pos < rest.len() {
+            if rest[pos..].starts_with(terminator) {
+                pos += terminator.len();
+                depth -= 1;
+
+                if depth == 0 {
+                    self.token_end += pos;
+                    return true;
+                }
+            } else if rest[pos..].starts_with(opening) {
+                pos += opening.len();
+                depth += 1;
+            } else {
+                pos += 1;
+            }
+        }
+
+        self.token_end = self.window_end;
+        self.touched_end.set(true);
+        false
+    }
+
+    /// Reset `token_start` to `token_end`, marking the input as skipped.
     #[inline]
     fn trivia(&mut self) {
         self.extras.on_whitespace();
         self.token_start = self.token_end;
+        self.skipped = true;
     }
 
     /// Set the current token to appropriate `#[error]` variant.
@@ -194,6 +1400,32 @@ where
     #[inline]
     fn error(&mut self) {
         self.token_end = self.source.find_boundary(self.token_end);
-        self.token = Token::ERROR;
+        self.token = Token::error(self.token_start..self.token_end);
+        self.errors += 1;
+    }
+
+    /// Give up on the token being matched, and set the `#[error]` variant for
+    /// its first character only.
+    #[inline]
+    fn reject(&mut self) {
+        self.token_end = self.token_start + 1;
+        self.error();
+    }
+
+    /// Look up the current token in a `table` of keywords sorted by their bytes.
+    #[inline]
+    fn keyword<T: Clone>(&self, table: &[(&str, T)]) -> Option<T> {
+        debug_assert!(
+            table.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "Keywords must be sorted, without duplicates",
+        );
+
+        let slice = unsafe { self.source.slice_unchecked(self.token_start..self.token_end) };
+        let slice = slice.as_bytes();
+
+        table
+            .binary_search_by(|(keyword, _)| keyword.as_bytes().cmp(slice))
+            .ok()
+            .map(|idx| table[idx].1.clone())
     }
 }
@@ -26,7 +26,9 @@
 //!     End,
 //!
 //!     // ...and one for errors. Those can be named anything
-//!     // you wish as long as the attributes are there.
+//!     // you wish as long as the attributes are there, or
+//!     // the attributes can be left out for variants named
+//!     // `End` and `Error`.
 //!     #[error]
 //!     Error,
 //!
@@ -79,6 +81,22 @@
 //! }
 //! ```
 //!
+//! The `#[end]` and `#[error]` variants are part of the enum rather than hidden states
+//! of the lexer, since a derive can't add variants to the enum it's applied to, and the
+//! `Lexer` has to hold something in `token` at the end of input or on an error. Unit
+//! variants named `End` and `Error` without any definitions of their own are picked up
+//! without the attributes.
+//!
+//! A variant can have any number of `#[token]` and `#[regex]` attributes. Literals with
+//! several spellings can also be listed in a single attribute, as in `#[token("<>", "!=")]`,
+//! each of them being a separate definition with its own priority. A callback given in
+//! such an attribute is shared by all of the spellings, and can tell them apart by `slice`.
+//!
+//! Every definition has to consume at least one byte, so an empty `#[token("")]`, or a
+//! regex that can match the empty string such as `[a-z]*` or `(a|b?)`, is a compile error.
+//! Optional parts are fine as long as something else is required, as in `-?[0-9]+`. The
+//! same goes for `#[skip]` and `#[logos(trivia)]` patterns.
+//!
 //! ### Callbacks
 //!
 //! On top of using the enum variants, **Logos** can also call arbitrary functions whenever a pattern is matched:
@@ -148,6 +166,505 @@
 //! }
 //! ```
 //!
+//! Callbacks can also return a value. If the variant has a single unnamed field,
+//! that value is going to be stored in it:
+//!
+//! ```rust
+//! use logos::{Logos, Lexer, Slice, Source};
+//!
+//! fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> u64 {
+//!     lex.slice()
+//!         .as_bytes()
+//!         .iter()
+//!         .fold(0, |acc, byte| acc * 10 + (byte - b'0') as u64)
+//! }
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[0-9]+", callback = "number")]
+//!     Number(u64),
+//! }
+//!
+//! fn main() {
+//!     let mut lexer = Token::lexer("42 9001");
+//!
+//!     assert_eq!(lexer.token, Token::Number(42));
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::Number(9001));
+//! }
+//! ```
+//!
+//! Tokens can also borrow from the source, with the enum taking a single lifetime parameter
+//! that is tied to it. `Slice::as_str` gets a `&str` out of the slice of any `Source`,
+//! returning `None` only for invalid UTF-8 in binary sources:
+//!
+//! ```rust
+//! use logos::{Logos, Lexer, Slice, Source};
+//!
+//! fn ident<'s, S: Source<'s>>(lex: &mut Lexer<Token<'s>, S>) -> Result<&'s str, ()> {
+//!     lex.slice().as_str().ok_or(())
+//! }
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token<'source> {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+", callback = "ident")]
+//!     Ident(&'source str),
+//! }
+//!
+//! fn main() {
+//!     let mut lexer = Token::lexer("foo bar");
+//!
+//!     assert_eq!(lexer.token, Token::Ident("foo"));
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::Ident("bar"));
+//! }
+//! ```
+//!
+//! Both can be combined, with a callback mutating the `extras` to compute the value it
+//! returns. A symbol table in the `extras` can intern identifiers as they are lexed, with
+//! `lex.extras.interner.intern(lex.slice())` in the callback producing an
+//! `Identifier(Symbol)` token.
+//!
+//! If the callback returns a `Result`, an `Err` will produce the `#[error]` token
+//! instead, covering the entire matched input. Returning a `Filter` lets the callback
+//! decide whether the token should be produced at all, or whether the matched input
+//! should be skipped.
+//!
+//! A callback can also consume input past the end of the match with `Lexer::bump`, looking
+//! ahead with `Lexer::remainder`, for constructs such as here-docs or nested comments that
+//! regular expressions can't describe. The `range` and `slice` of the produced token, and
+//! the input skipped by `Filter::Skip`, then include the consumed input. For deciding on
+//! just the next few bytes, `Lexer::peek_byte` and `Lexer::peek_bytes` read them without
+//! consuming anything, returning `None` at the end of input.
+//!
+//! Callbacks can also depend on the token produced before, available through `Lexer::last`,
+//! such as to tell a regex literal from division in JavaScript.
+//!
+//! To split a single match into multiple tokens, such as `>>` closing two generic
+//! argument lists, a callback can queue up a token with `Lexer::emit_next`. The next call
+//! to `advance` produces it without reading the source, shortening the current token if
+//! the ranges overlap.
+//!
+//! A callback on a variant without a field can also return the token itself, which
+//! is then produced instead of the variant the definition is on. This is handy for
+//! looking up keywords in a table after matching a generic identifier:
+//!
+//! ```rust
+//! use logos::{Logos, Lexer, Slice, Source};
+//!
+//! fn keyword<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Token {
+//!     match lex.slice().as_bytes() {
+//!         b"fn" => Token::Fn,
+//!         b"let" => Token::Let,
+//!         _ => Token::Ident,
+//!     }
+//! }
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+", callback = "keyword")]
+//!     Ident,
+//!
+//!     Fn,
+//!
+//!     Let,
+//! }
+//!
+//! fn main() {
+//!     let mut lexer = Token::lexer("let foo");
+//!
+//!     assert_eq!(lexer.token, Token::Let);
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::Ident);
+//!     assert_eq!(lexer.slice(), "foo");
+//! }
+//! ```
+//!
+//! ### Errors
+//!
+//! When no definition matches the input, the `#[error]` token is produced. Its `range`
+//! and `slice` cover the input at which lexing got stuck: at least one whole character
+//! (a single byte for binary sources), or everything consumed by a definition that
+//! matched partway before failing, such as an unterminated string. Calling `advance`
+//! continues right after it, so all errors in the source can be reported in one pass.
+//! The range of an error is never empty, and never overlaps the tokens around it, so a
+//! loop calling `advance` until the `#[end]` token always terminates, having covered
+//! all of the input with either tokens, errors, or skipped trivia.
+//! Calling `Lexer::recover` on an error extends it over any errors directly following,
+//! reporting a run of unrecognized input as a single error instead.
+//! `Lexer::error_count` tells how many errors have been produced so far, such as to give
+//! up on input that's not worth lexing any further. Parsers that treat any error as a hard
+//! failure can call `Lexer::try_advance` instead of `advance`, which returns a `LexError`
+//! holding the span and slice of the error, ready to be propagated with `?`.
+//!
+//! The `#[error]` variant can hold its own range, as either `Error(Range<usize>)` or
+//! `Error(logos::Span)`, so errors collected along with other tokens keep track of where
+//! they came from. The field is filled in whenever the error token is produced, and holds
+//! `0..0` in `Logos::ERROR`. Borrowing the slice instead isn't supported, since it has
+//! to be created from the range alone.
+//!
+//! Lexers that don't need to tell errors apart from the end of input can put both `#[end]`
+//! and `#[error]` on a single unit variant. Lexing then stops at the first error, which
+//! `Lexer::is_at_end` can still distinguish from having consumed all of the input.
+//!
+//! ### Skipping input
+//!
+//! By default spaces, tabs and form feeds between tokens are skipped. Any other input
+//! that shouldn't produce tokens, such as comments, can be skipped by putting one or
+//! more `#[skip = "regex"]` attributes on the enum. Should a skip pattern match exactly
+//! the same input as a token definition, the token takes precedence.
+//!
+//! ### Terminated tokens
+//!
+//! Tokens running until a closing sequence, such as block comments, are awkward to
+//! describe with a regex. Instead, `#[token("/*", until = "*/")]` (or the same option on
+//! a `#[regex]`) consumes everything following the match up to and including the first
+//! `*/`, scanning for it directly rather than going through the state machine. The search
+//! starts after the match, and doesn't take part in picking the longest match or resolving
+//! priorities. When the input ends without the terminator, the `#[error]` token is produced
+//! instead, spanning the rest of the input.
+//!
+//! For comments that nest, as in Rust or OCaml, the opening sequence can be given with
+//! the `nested` option. `#[token("/*", until = "*/", nested = "/*")]` keeps count of every
+//! `/*` found along the way, so that `/* a /* b */ c */` is a single token ending at the
+//! `*/` balancing out the first one. Unbalanced input produces the `#[error]` token the same
+//! way a missing terminator does.
+//!
+//! When the terminator depends on the opening sequence, such as the number of `#`s in a Rust
+//! raw string, or of `=`s in a Lua long bracket, a callback can build it from the `slice`
+//! and consume the input through it with `Lexer::bump_past`:
+//!
+//! ```rust
+//! use logos::{Logos, Lexer, Slice, Source};
+//!
+//! fn raw_string<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<(), ()> {
+//!     // The opening `r##"` is closed by `"##`
+//!     let mut terminator = lex.slice().as_bytes()[1..].to_vec();
+//!
+//!     terminator.rotate_right(1);
+//!
+//!     match lex.bump_past(&terminator) {
+//!         true => Ok(()),
+//!         false => Err(()),
+//!     }
+//! }
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex(r##"r#*""##, callback = "raw_string")]
+//!     RawString,
+//! }
+//!
+//! fn main() {
+//!     let mut lexer = Token::lexer(r###"r#"say "hi""#r##"a"#b"##"###);
+//!
+//!     assert_eq!(lexer.token, Token::RawString);
+//!     assert_eq!(lexer.slice(), r##"r#"say "hi""#"##);
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::RawString);
+//!     assert_eq!(lexer.slice(), r###"r##"a"#b"##"###);
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::End);
+//! }
+//! ```
+//!
+//! ### Subpatterns
+//!
+//! Fragments shared by multiple regular expressions can be declared once on the enum
+//! with `#[logos(subpattern(name = "regex"))]`, and then referenced as `(?&name)`
+//! in any `#[regex]`, `#[skip]`, or later subpattern:
+//!
+//! ```rust
+//! use logos::Logos;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(subpattern(digits = "[0-9][0-9_]*"))]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex = "(?&digits)"]
+//!     Integer,
+//!
+//!     #[regex = "(?&digits)\\.(?&digits)"]
+//!     Float,
+//! }
+//!
+//! fn main() {
+//!     let mut lexer = Token::lexer("1_000 3.14");
+//!
+//!     assert_eq!(lexer.token, Token::Integer);
+//!
+//!     lexer.advance();
+//!
+//!     assert_eq!(lexer.token, Token::Float);
+//! }
+//! ```
+//!
+//! ### Case insensitivity
+//!
+//! `#[token("select", ignore(case))]` will match the literal regardless of the case of
+//! any ASCII letters in it, so `SELECT` and `Select` produce the same token, with the
+//! same priority as the literal itself.
+//!
+//! `ignore(unicode_case)` extends this to Unicode simple case folding, for both `#[token]`
+//! and `#[regex]`, so `#[token("λέξη", ignore(unicode_case))]` also matches `ΛΈΞΗ`, with
+//! `ς`, `σ` and `Σ` all matching each other. On a `#[regex]` it's the same as starting
+//! the pattern with the `(?i)` flag. Simple folding maps single characters to single
+//! characters, so `ß` matches `ẞ`, but not `SS`. Only string literals and patterns can
+//! be folded this way. The folding tables are only used by the derive macro while
+//! compiling, what ends up in the lexer is a state machine matching every spelling.
+//!
+//! Inline flag groups only apply to the enclosed part of the pattern, so
+//! `#[regex("(?i:0x)[0-9a-f]+")]` accepts both `0xff` and `0Xff`, but not `0XFF`.
+//! Likewise `(?-i:...)` turns case insensitivity back off inside an `(?i)` pattern.
+//!
+//! ### Non-greedy repetitions
+//!
+//! A character class repeated with `*?`, `+?` or `??` and followed by a literal stops at
+//! the first occurrence of that literal, so `#[regex(r"/\*(?s:.)*?\*/")]` matches a block
+//! comment up to the first `*/`, rather than the last one. The class must either match
+//! every byte of the literal or none of them, and for Unicode classes the literal has to
+//! be ASCII. Counted repetitions such as `{2,5}?` aren't supported.
+//!
+//! ### Lookahead
+//!
+//! A `#[regex]` can end with a `(?=...)` group, which has to match for the definition
+//! to produce its token, but isn't included in it. With `#[regex("-(?=[0-9])")]` a `-`
+//! only becomes a sign when followed by a digit. The lookahead has to match a fixed number
+//! of bytes, and it counts towards the length of the match when picking the longest one.
+//!
+//! A negative `(?!...)` group at the end works like the `boundary` modifier on tokens:
+//! `#[regex("let(?![a-z])")]` doesn't match the start of `letter`. The group can match more
+//! than one character, and nothing it reads is consumed, so `#[regex("x(?!ab)")]` still
+//! produces its token for the `x` in `xa`, leaving the `a` to the next one. Lookaheads in
+//! any other position of the pattern aren't supported.
+//!
+//! ### Anchors
+//!
+//! A `#[regex]` starting with `^` only matches at the very start of the input, such as
+//! `#[regex("^#![^\n]*")]` for a shebang line, and one ending with `$` only matches when
+//! the input ends right after it. Anywhere else, input matching a `$` definition produces
+//! the token of another definition matching the same input, or the `#[error]` token.
+//! Either anchor raises the default priority of a definition by one, so that it wins over
+//! the same pattern without the anchor. Anchors in any other position of the pattern,
+//! and the multi-line `(?m)` flag, aren't supported.
+//!
+//! ### Word boundaries
+//!
+//! `#[token("in", boundary)]` only matches the literal if it isn't directly followed
+//! by an ASCII letter, digit or underscore, so that `in` isn't produced for the start of
+//! `inside`, whether there is an identifier definition or not. Without one, `in2` lexes
+//! as the `#[error]` token for `i`, and lexing carries on from `n`. A different set of
+//! characters can be given as a regex class with `#[token("in", boundary = "[a-z$]")]`.
+//!
+//! To change what counts as an identifier character for the whole enum, declare the class
+//! once with `#[logos(ident = "[a-zA-Z0-9_$]")]`. It's then used by every `boundary` without
+//! a class of its own, and can be referenced in any `#[regex]` as the `(?&ident)` subpattern.
+//!
+//! ### Character classes
+//!
+//! The `\d`, `\w` and `\s` escapes, along with their negations, only match ASCII
+//! characters by default, keeping the generated state machine small. They can be made
+//! Unicode-aware for a single definition with `#[regex("\\w+", unicode)]`, or for the
+//! whole enum with `#[logos(unicode)]`. Earlier versions of Logos matched the Unicode
+//! classes by default, grammars relying on that need `#[logos(unicode)]` to keep them.
+//!
+//! Over binary sources, a byte string `#[regex]` can decode part of the input as UTF-8 with
+//! the `u` flag, so that `#[regex(b"\\x02(?u:\\w+)\\x03", unicode)]` matches a run of
+//! Unicode word characters between two control bytes. Bytes that aren't valid UTF-8 don't
+//! match the class, producing the `#[error]` token instead.
+//!
+//! Characters can be written with hexadecimal escapes, both on their own and in classes,
+//! as in `[\0-\x08\x0E-\x1F]`, with `\0` standing for NUL. In string patterns `\xE9` and
+//! `\x{1F980}` are Unicode code points, matched by their UTF-8 encoding, while in byte string
+//! patterns `\x80` to `\xFF` are single bytes. Bytes that aren't valid UTF-8 on their own,
+//! such as `(?-u:\xFF)`, can only be matched by byte string patterns.
+//!
+//! An enum with a byte string definition that can match invalid UTF-8 can only lex binary
+//! sources, such as `&[u8]`. This is decided conservatively, so `b"\xC3\xA9"` counts too,
+//! while `b"let"` or `b"[0-9]+"` don't restrict the enum. Adding `#[logos(utf8)]` to the
+//! enum makes each definition that can match invalid UTF-8 a compile error instead,
+//! pointing out the bytes responsible.
+//!
+//! If the input is known to be ASCII, `#[logos(ascii)]` on the enum drops all non-ASCII
+//! characters from classes, `.` and negated classes included, so that the state machine
+//! never has to handle multi-byte UTF-8 sequences. This can make lexing text with long
+//! strings or comments several times faster. A non-ASCII character in the input then
+//! produces the `#[error]` token, still covering the whole character when lexing `&str`.
+//! Definitions with a non-ASCII literal, or a class without any ASCII characters, are
+//! a compile error.
+//!
+//! The `\R` escape matches a single line break: `\n`, `\r`, vertical tab, form feed, or
+//! one of `\u{85}`, `\u{2028}` and `\u{2029}`, with `\r\n` always matched as one unit.
+//! Byte string patterns only include the ASCII line breaks. `\R` can't be used inside
+//! a character class.
+//!
+//! Unicode property classes such as `\p{XID_Start}` or `\P{Greek}` need the Unicode
+//! tables to be compiled into the derive macro, which is done by enabling the
+//! `unicode_properties` feature of this crate.
+//!
+//! Classes that are awkward to spell out as a regex can be given as a function instead.
+//! `#[regex("[a-zA-Z_:]", class = "is_name_char")]` matches a single character with the
+//! regex, then keeps consuming input for as long as `is_name_char` accepts it. The function
+//! can take either a `char`, decoding the input as UTF-8, or a `u8`, and returns a `bool`.
+//! Over a `&str`, a `u8` function accepting only part of a character stops before it.
+//! The input consumed by the function doesn't take part in picking the longest match or
+//! resolving priorities, those are settled by the regex alone.
+//!
+//! ### Keywords
+//!
+//! Languages with many keywords can keep the state machine small by matching them all as
+//! identifiers, then looking each identifier up in a table. With
+//! `#[regex("[a-z_]+", keywords = "KEYWORDS")]` the slice is searched for in `KEYWORDS`, a
+//! `&[(&str, Token)]` sorted by the keyword bytes, and reclassified as the token found there.
+//! Identifiers not in the table become the annotated variant, running its callback if there
+//! is one. Only matches of that one definition are looked up.
+//!
+//! ```rust
+//! use logos::Logos;
+//!
+//! #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z_]+", keywords = "KEYWORDS")]
+//!     Ident,
+//!
+//!     Fn,
+//!     Let,
+//! }
+//!
+//! static KEYWORDS: &[(&str, Token)] = &[("fn", Token::Fn), ("let", Token::Let)];
+//!
+//! let mut lex = Token::lexer("let");
+//! assert_eq!(lex.token, Token::Let);
+//!
+//! lex = Token::lexer("lettuce");
+//! assert_eq!(lex.token, Token::Ident);
+//! ```
+//!
+//! ### Serialization
+//!
+//! With the `serde` feature of this crate enabled, putting `#[logos(serde)]` on the enum
+//! implements `serde::Serialize` and `serde::Deserialize` for it. Tokens are represented
+//! by their variant names, with the value of a variant's field if it has one, so that
+//! `Token::Number(42)` becomes `{"Number":42}` in JSON.
+//!
+//! ### SIMD
+//!
+//! Enabling the `simd` feature of this crate makes the lexer consume repetitions of small
+//! classes, such as `[0-9]+` or `[ \t\n\r]+`, 32 bytes at a time using SSE2 on x86_64,
+//! or AVX2 if it's detected at runtime with the `std` feature enabled. Classes made of more
+//! than 4 ranges keep using a lookup table, and other targets compare one byte at a time.
+//!
+//! ### Dispatch
+//!
+//! By default the first byte of a token is matched against all the ways a token can start,
+//! which compiles to a jump table with an extra lookup once there are more than two of them.
+//! Putting `#[logos(dispatch = "table")]` on the enum instead calls a handler through a
+//! table of 256 function pointers indexed by the byte. The table trades the branching of
+//! the `match` for an indirect call, which can pay off for grammars with many different
+//! ways to start a token, but not for a handful of them. Measure with your own input
+//! before switching.
+//!
+//! ### Optimizing for size
+//!
+//! The generated code favors speed, using 256-byte lookup tables to match bytes against
+//! classes and branches, unrolling loops and marking its functions `#[inline]`. Where code
+//! size matters more, such as in WebAssembly modules, `#[logos(optimize = "size")]` on the
+//! enum matches bytes with plain `match` expressions and loops over one byte at a time
+//! instead. This makes the lexer smaller, at the cost of lexing slower, so check both
+//! the size and the speed with your own grammar before switching.
+//!
+//! ### Inlining
+//!
+//! Putting `#[logos(inline)]` or `#[logos(noinline)]` on a variant marks the functions
+//! matching it with `#[inline(always)]` or `#[inline(never)]`. A function is only affected
+//! when every variant it can lead to has the same hint, so a branch shared between `in` and
+//! `instanceof` keeps the default unless both have it. This is meant for large grammars,
+//! where keeping rare keywords out of line can relieve the instruction cache.
+//!
+//! ### Testing
+//!
+//! The `testing` feature of this crate exports `logos::testing::assert_lex`, which lexes
+//! a source and compares every token along with its slice and range to a list of expected
+//! ones, reporting the first mismatch. It's meant to be enabled for `[dev-dependencies]`.
+//!
+//! ### LALRPOP
+//!
+//! The `lalrpop` feature of this crate exports `logos::lalrpop::LalrpopAdapter`, turning
+//! a `Lexer` into the iterator LALRPOP parsers take from an external lexer, with byte
+//! offsets as locations and `logos::lalrpop::LexicalError` for the `#[error]` token.
+//!
+//! ### Non-exhaustive enums
+//!
+//! Token enums exported from a library can be marked `#[non_exhaustive]`, so that adding
+//! tokens doesn't break `match` expressions in other crates, which then need a `_` arm.
+//! Everything `#[derive(Logos)]` generates keeps working across crates. Keep in mind that
+//! `Logos::SIZE`, and the indices returned by `discriminant`, are constants of the version
+//! of the library a crate was compiled against. Tables sized with `SIZE` or built with
+//! `lookup!` in other crates are only resized when those are recompiled, and adding
+//! variants anywhere but at the end shifts the indices of the ones following them.
+//!
+//! ### Unsafe code
+//!
+//! The code generated by `#[derive(Logos)]` doesn't contain any `unsafe` blocks, so
+//! it compiles in crates with `#![forbid(unsafe_code)]` as is, without a special mode
+//! or an exception for the token enum. Unchecked reads and slicing only happen inside
+//! this crate, behind the `Source` trait, where the bounds have already been checked
+//! once by the state machine. Lints like `unsafe_code` apply to the crate being
+//! compiled, and don't reach into dependencies.
+//!
+//! ### `no_std`
+//!
+//! Without the default `std` feature the crate is `no_std`, and lexing with `advance` and
+//! `slice` never allocates. The `alloc` feature, implied by `std`, brings back the parts
+//! that need an allocator: `Lexer::into_vec`, `PeekableLexer`, `OwnedLexer`, `LineIndex`,
+//! and sources and slices over `Cow<str>`, `Arc<str>` and `Rc<str>`. `StreamingLexer`
+//! reads from `std::io`, and so requires `std`.
+//!
 //! ## Token disambiguation
 //!
 //! Rule of thumb is:
@@ -161,11 +678,60 @@
 //!
 //! This is done by comparing numeric priority attached to each definition. Every consecutive,
 //! non-repeating single byte adds 2 to the priority, while every range or regex class adds 1.
-//! Loops or optional blocks are ignored, while alternations count the shortest alternative:
+//! Loops or optional blocks are ignored, counted repetitions like `{n,m}` count `n` times,
+//! while alternations count the shortest alternative:
 //!
 //! + `[a-zA-Z]+` has a priority of 1 (lowest possible), because at minimum it can match a single byte to a class.
 //! + `foobar` has a priority of 12.
 //! + `(foo|hello)(bar)?` has a priority of 6, `foo` being it's shortest possible match.
+//! + `[0-9a-f]{4}` has a priority of 4.
+//!
+//! Priorities only decide between definitions matching input of the same length. They
+//! don't affect how much input a definition matches: the longest match always wins, also
+//! within a single definition, so `foo|foobar` matches all of `foobar`, despite having
+//! a priority of 6 from its shortest alternative. When a longer definition fails partway,
+//! the longest one that did match is produced, so with `.` and `...` defined, `..` lexes
+//! as two `.` tokens.
+//!
+//! Should the computed priority not be what you want, it can be set explicitly on any
+//! definition with `#[regex("[a-z]+", priority = 3)]` or `#[token("let", priority = 3)]`.
+//!
+//! A `#[token]` gets a priority equal to its length in bytes, which can tie with a regex
+//! of classes matching input of the same length, such as `[a-z]{10}` for `instanceof`.
+//! Marking it with `#[token("instanceof", keyword)]` counts 2 for every byte instead, just
+//! like literal bytes in a `#[regex]`, so that it beats any unanchored regex made of
+//! classes alone, without having to pick a number.
+//!
+//! Two definitions of different variants that can match the same input with the same
+//! priority are a compile error, with an example of such input included in the message.
+//! If the ambiguity is intentional, it can be acknowledged on either definition with
+//! `#[regex("[a-z]+", allow(ambiguity))]`, in which case either of the variants may be
+//! produced for the shared input.
+//!
+//! A definition that loses to other definitions on every input it can match, such as
+//! `#[token("let")]` next to `#[regex("[a-z]+", priority = 10)]`, can never be produced.
+//! Such definitions are reported with a warning pointing at them. Since procedural macros
+//! can't emit warnings of their own on stable Rust, it shows up as the use of a deprecated
+//! constant, and can be silenced on the definition with `#[token("let", allow(unreachable))]`.
+//!
+//! ## Inspecting the state machine
+//!
+//! When a definition doesn't match what you expect, it might help to look at the state
+//! machine `#[derive(Logos)]` produced. Adding `#[logos(dump)]` to the enum, or compiling
+//! with the `LOGOS_DUMP=dot` environment variable set, writes it as a Graphviz DOT file
+//! to `logos/<Enum>.dot` in the `target` directory of the crate, or of the workspace it's
+//! part of, respecting `CARGO_TARGET_DIR`. Edges are labeled with the bytes they match,
+//! dashed edges are taken when nothing else does, and double circles mark the states
+//! producing a token.
+//!
+//! The priorities computed for each definition can be printed during compilation with
+//! `#[logos(print_priorities)]` on the enum, or by setting `LOGOS_PRINT_PRIORITIES=1`,
+//! one line per definition such as `Fast => 4  #[token("fast")]`. Cargo only shows
+//! these when the crate is rebuilt.
+//!
+//! The same numbers are available at runtime from `Logos::priority`, giving the highest
+//! priority among the definitions of the variant. `#[derive(Logos)]` also generates it
+//! as an inherent `const fn priority(&self) -> u32`, which can be used in `const` contexts.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
@@ -173,17 +739,50 @@
 #[cfg(not(feature = "std"))]
 extern crate core as std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(feature = "export_derive")]
 pub use logos_derive::Logos;
 
+mod error;
+#[cfg(feature = "lalrpop")]
+pub mod lalrpop;
 mod lexer;
+#[cfg(feature = "alloc")]
+mod normalized;
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "alloc")]
+mod peekable;
+#[cfg(feature = "simd")]
+mod simd;
 pub mod source;
+mod span;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "alloc")]
+mod utf16;
 
 #[doc(hidden)]
 pub mod internal;
 
+pub use self::error::LexError;
 pub use self::lexer::{Extras, Lexer};
+#[cfg(feature = "alloc")]
+pub use self::normalized::NormalizedSource;
+#[cfg(feature = "alloc")]
+pub use self::owned::OwnedLexer;
+#[cfg(feature = "alloc")]
+pub use self::peekable::PeekableLexer;
+#[cfg(feature = "std")]
+pub use self::stream::StreamingLexer;
 pub use self::source::{Slice, Source};
+pub use self::span::Span;
+#[cfg(feature = "alloc")]
+pub use self::utf16::Utf16Source;
 
 /// Trait implemented for an enum representing all tokens. You should never have
 /// to implement it manually, use the `#[derive(Logos)]` attribute on your enum.
@@ -203,15 +802,163 @@ pub trait Logos: Sized {
     /// Helper `const` of the variant marked as `#[end]`.
     const END: Self;
 
-    /// Helper `const` of the variant marked as `#[error]`.
+    /// Helper `const` of the variant marked as `#[error]`. If the variant holds
+    /// the range of the error, the range here is `0..0`.
     const ERROR: Self;
 
+    /// The `#[error]` token for the given `range` of the source. This is `ERROR`,
+    /// with the `range` put in the field of the variant if it has one.
+    ///
+    /// ```rust
+    /// use logos::{Logos, Span};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error(Span),
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Word,
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut lex = Token::lexer("foo!");
+    ///
+    ///     assert_eq!(lex.token, Token::Word);
+    ///
+    ///     lex.advance();
+    ///
+    ///     assert_eq!(lex.token, Token::Error(Span::new(3, 4)));
+    ///     assert_eq!(Token::error(3..4), lex.token);
+    /// }
+    /// ```
+    #[inline]
+    fn error(range: std::ops::Range<usize>) -> Self {
+        let _ = range;
+
+        Self::ERROR
+    }
+
+    /// Human readable name of the token, useful for error messages. This is the name
+    /// of the variant, unless a definition overrides it with `name = "..."`:
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[token("(", name = "open paren")]
+    ///     ParenOpen,
+    ///
+    ///     #[token = "=="]
+    ///     Equals,
+    /// }
+    ///
+    /// fn main() {
+    ///     assert_eq!(Token::ParenOpen.name(), "open paren");
+    ///     assert_eq!(Token::Equals.name(), "Equals");
+    /// }
+    /// ```
+    fn name(&self) -> &'static str;
+
+    /// All variants of the enum in order of declaration, including the `#[end]` and
+    /// `#[error]` variants. Variants holding a value produced by a callback are left out,
+    /// since there is no value to construct them with.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[token = "+"]
+    ///     Plus,
+    ///
+    ///     #[token = "-"]
+    ///     Minus,
+    /// }
+    ///
+    /// fn main() {
+    ///     let kinds: Vec<_> = Token::variants()
+    ///         .iter()
+    ///         .filter(|token| **token != Token::END && **token != Token::ERROR)
+    ///         .map(Token::name)
+    ///         .collect();
+    ///
+    ///     assert_eq!(kinds, &["Plus", "Minus"]);
+    /// }
+    /// ```
+    fn variants() -> &'static [Self]
+    where
+        Self: 'static;
+
+    /// Index of the variant, same as casting a unit variant with `as usize`, regardless
+    /// of the value the variant holds. Unlike the cast, this also works for enums with
+    /// variants holding values. `#[derive(Logos)]` also generates it as an inherent
+    /// `const fn discriminant(&self) -> usize`, which can be used in `const` contexts:
+    ///
+    /// ```rust
+    /// use logos::{Logos, Span};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error(Span),
+    ///
+    ///     #[token = "+"]
+    ///     Plus,
+    /// }
+    ///
+    /// fn main() {
+    ///     assert_eq!(Token::Error(Span::new(3, 4)).discriminant(), 1);
+    ///     assert_eq!(Token::Plus.discriminant(), 2);
+    ///
+    ///     const PLUS: usize = Token::Plus.discriminant();
+    ///
+    ///     assert_eq!(PLUS, 2);
+    /// }
+    /// ```
+    fn discriminant(&self) -> usize;
+
+    /// Highest priority among the definitions of the variant, or `0` for variants
+    /// without any, such as `#[end]` and `#[error]`. These are the numbers shown by
+    /// `#[logos(print_priorities)]`.
+    fn priority(&self) -> u32;
+
     /// The heart of Logos. Called by the `Lexer`. The implementation for this function
-    /// is generated by the `logos-derive` crate.
+    /// is generated by the `logos-derive` crate, as part of `WithSource`, and returns
+    /// after skipping input, to be called again in a loop rather than recursively.
+    #[inline]
     fn lex<'source, Source>(lexer: &mut Lexer<Self, Source>)
     where
         Source: self::Source<'source>,
-        Self: source::WithSource<Source>;
+        Self: source::WithSource<Source>,
+    {
+        loop {
+            <Self as source::WithSource<Source>>::lex_with(lexer);
+
+            if !lexer.take_skipped() {
+                break;
+            }
+        }
+    }
 
     /// Create a new instance of a `Lexer` that will produce tokens implementing
     /// this `Logos`.
@@ -219,9 +966,136 @@ pub trait Logos: Sized {
     where
         Source: self::Source<'source>,
         Self: source::WithSource<Source>,
+        Self::Extras: Default,
     {
         Lexer::new(source)
     }
+
+    /// Create a new instance of a `Lexer` that skips a leading UTF-8 byte order mark,
+    /// see `Lexer::new_skip_bom`.
+    fn lexer_skip_bom<'source, Source>(source: Source) -> Lexer<Self, Source>
+    where
+        Source: self::Source<'source>,
+        Self: source::WithSource<Source>,
+        Self::Extras: Default,
+    {
+        Lexer::new_skip_bom(source)
+    }
+
+    /// Create a new instance of a `Lexer` that only lexes the `range` of the `source`,
+    /// see `Lexer::new_in`.
+    fn lexer_in<'source, Source>(source: Source, range: std::ops::Range<usize>) -> Lexer<Self, Source>
+    where
+        Source: self::Source<'source>,
+        Self: source::WithSource<Source>,
+        Self::Extras: Default,
+    {
+        Lexer::new_in(source, range)
+    }
+
+    /// Create a new instance of a `Lexer` with given `Extras`, see `Lexer::with_extras`.
+    fn lexer_with_extras<'source, Source>(source: Source, extras: Self::Extras) -> Lexer<Self, Source>
+    where
+        Source: self::Source<'source>,
+        Self: source::WithSource<Source>,
+    {
+        Lexer::with_extras(source, extras)
+    }
+
+    /// Get the token matching the whole `source` as a single token, without any
+    /// leading or trailing trivia. Returns `None` for an empty `source`, or if it
+    /// produces the `#[error]` token or more than one token.
+    ///
+    /// ```rust
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[end]
+    ///     End,
+    ///
+    ///     #[error]
+    ///     Error,
+    ///
+    ///     #[token = "fn"]
+    ///     Fn,
+    ///
+    ///     #[regex = "[a-z]+"]
+    ///     Ident,
+    /// }
+    ///
+    /// fn main() {
+    ///     assert_eq!(Token::from_exact("fn"), Some(Token::Fn));
+    ///     assert_eq!(Token::from_exact("fnord"), Some(Token::Ident));
+    ///     assert_eq!(Token::from_exact("fn fn"), None);
+    ///     assert_eq!(Token::from_exact("Fn"), None);
+    /// }
+    /// ```
+    fn from_exact<'source, Source>(source: Source) -> Option<Self>
+    where
+        Source: self::Source<'source>,
+        Self: source::WithSource<Source>,
+        Self::Extras: Default,
+    {
+        let len = source.len();
+        let lexer = Lexer::<Self, Source>::new(source);
+        let range = lexer.range();
+
+        if len == 0 || range != (0..len) || std::mem::discriminant(&lexer.token) == std::mem::discriminant(&Self::ERROR) {
+            return None;
+        }
+
+        Some(lexer.token)
+    }
+}
+
+/// Type that can be returned from a callback, informing the `Lexer` whether
+/// to produce a token or skip the matched input and continue lexing.
+///
+/// ```rust
+/// use logos::{Logos, Lexer, Filter, Slice, Source};
+///
+/// fn doc_comment<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Filter<()> {
+///     if lex.slice().as_bytes().starts_with(b"///") {
+///         Filter::Emit(())
+///     } else {
+///         Filter::Skip
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(trivia = "[ \\n]")]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex("//[^\n]*", callback = "doc_comment")]
+///     DocComment,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// fn main() {
+///     let mut lexer = Token::lexer("// comment\n/// docs\nfoo");
+///
+///     assert_eq!(lexer.token, Token::DocComment);
+///     assert_eq!(lexer.slice(), "/// docs");
+///
+///     lexer.advance();
+///
+///     assert_eq!(lexer.token, Token::Word);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter<T> {
+    /// Produce a token, storing the value in its field if it has one.
+    Emit(T),
+    /// Skip the matched input, as if it was trivia.
+    Skip,
 }
 
 /// Macro for creating lookup tables where index matches the token variant
@@ -231,6 +1105,10 @@ pub trait Logos: Sized {
 /// function pointers, enabling an O(1) branching at the cost of introducing some
 /// indirection.
 ///
+/// Enums with variants holding values can't be cast with `as usize`, index into the
+/// table with `LUT[lexer.token.discriminant()]` instead, see `Logos::discriminant`.
+/// Keys of the table have to be unit variants.
+///
 /// ```rust
 /// use logos::{Logos, lookup};
 ///
@@ -282,9 +1160,9 @@ macro_rules! lookup {
     ( $enum:ident::$variant:ident => $value:expr, $( $e:ident::$var:ident => $val:expr ,)* _ => $def:expr $(,)? ) => ({
         let mut table = [$def; $enum::SIZE];
 
-        table[$enum::$variant as usize] = $value;
+        table[$enum::$variant.discriminant()] = $value;
         $(
-            table[$e::$var as usize] = $val;
+            table[$e::$var.discriminant()] = $val;
         )*
 
         table
@@ -0,0 +1,244 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::ops::Range;
+
+use crate::source::{Chunk, Source, WithSource};
+use crate::{Lexer, Logos};
+
+/// A `Source` holding a normalized copy of the input, such as one with CRLF line breaks
+/// turned into LF or tabs expanded into spaces, that can map ranges back to the original.
+///
+/// The normalized text is built once up front, recording every edit made to the original,
+/// so only the parts that have actually changed cost anything to map. Offsets that fall
+/// inside a replacement map to the start of the text it replaced, and ranges ending inside
+/// of one are extended to cover all of it.
+///
+/// ```rust
+/// use logos::{Logos, NormalizedSource};
+/// use std::borrow::Cow;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(trivia = r"\s")]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// fn main() {
+///     let source = NormalizedSource::new("foo\r\nbar\r\n", |line| match line.strip_suffix("\r\n") {
+///         Some(line) => Cow::Owned(format!("{}\n", line)),
+///         None => Cow::Borrowed(line),
+///     });
+///     let mut lex = Token::lexer(&source);
+///
+///     assert_eq!(source.as_str(), "foo\nbar\n");
+///
+///     lex.advance();
+///
+///     assert_eq!(lex.slice(), "bar");
+///     assert_eq!(lex.range(), 4..7);
+///     assert_eq!(lex.range_original(), 5..8);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedSource {
+    text: String,
+    /// Ranges replaced in the original, next to the ranges of their replacements, in order.
+    edits: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl NormalizedSource {
+    /// Normalize the `source` one line at a time, passing each line, including its line
+    /// break, to the `normalize` function. Lines returned as `Cow::Borrowed` are kept
+    /// verbatim. For lines returned as `Cow::Owned`, only the part between what they
+    /// have in common with the original at the start and at the end is recorded as
+    /// replaced.
+    ///
+    /// Offsets within a line that got rewritten in several places, such as one with
+    /// multiple tabs expanded, lose precision between the first and the last change.
+    /// Such normalizations can use `from_edits` instead.
+    pub fn new<F>(source: &str, mut normalize: F) -> Self
+    where
+        F: FnMut(&str) -> Cow<str>,
+    {
+        let mut normalized = NormalizedSource {
+            text: String::with_capacity(source.len()),
+            edits: Vec::new(),
+        };
+        let mut offset = 0;
+
+        for line in source.split_inclusive('\n') {
+            match normalize(line) {
+                Cow::Borrowed(line) => normalized.text.push_str(line),
+                Cow::Owned(replacement) => {
+                    let prefix = common_prefix(line, &replacement);
+                    let suffix = common_suffix(&line[prefix..], &replacement[prefix..]);
+
+                    normalized.text.push_str(&replacement[..prefix]);
+                    normalized.replace(
+                        offset + prefix..offset + line.len() - suffix,
+                        &replacement[prefix..replacement.len() - suffix],
+                    );
+                    normalized.text.push_str(&replacement[replacement.len() - suffix..]);
+                }
+            }
+            offset += line.len();
+        }
+
+        normalized
+    }
+
+    /// Apply `edits` to the `source`, replacing each range of the original with the
+    /// accompanying string, keeping the rest intact.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ranges aren't sorted, overlap, or don't fall on `char` boundaries
+    /// of the `source`.
+    pub fn from_edits<'a, I>(source: &str, edits: I) -> Self
+    where
+        I: IntoIterator<Item = (Range<usize>, &'a str)>,
+    {
+        let mut normalized = NormalizedSource {
+            text: String::with_capacity(source.len()),
+            edits: Vec::new(),
+        };
+        let mut offset = 0;
+
+        for (range, replacement) in edits {
+            assert!(offset <= range.start, "Edits must be sorted and must not overlap");
+
+            normalized.text.push_str(&source[offset..range.start]);
+            offset = range.end;
+            normalized.replace(range, replacement);
+        }
+        normalized.text.push_str(&source[offset..]);
+
+        normalized
+    }
+
+    /// Get the normalized source.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Convert an offset in the normalized source to an offset in the original.
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let index = self.edits.partition_point(|(_, replacement)| replacement.start <= offset);
+
+        match index.checked_sub(1).map(|index| &self.edits[index]) {
+            Some((original, replacement)) if offset < replacement.end => original.start,
+            Some((original, replacement)) => original.end + (offset - replacement.end),
+            None => offset,
+        }
+    }
+
+    /// Convert a `range` in the normalized source, such as the `range` of a token,
+    /// to a range in the original.
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.original_offset(range.start);
+        let index = self.edits.partition_point(|(_, replacement)| replacement.start < range.end);
+
+        let end = match index.checked_sub(1).map(|index| &self.edits[index]) {
+            Some((original, replacement)) if range.end <= replacement.end => original.end,
+            Some((original, replacement)) => original.end + (range.end - replacement.end),
+            None => range.end,
+        };
+
+        start..end.max(start)
+    }
+
+    fn replace(&mut self, original: Range<usize>, replacement: &str) {
+        let start = self.text.len();
+
+        self.text.push_str(replacement);
+
+        if original.is_empty() && replacement.is_empty() {
+            return;
+        }
+
+        match self.edits.last_mut() {
+            Some((last, last_replacement)) if last.end == original.start && last_replacement.end == start => {
+                last.end = original.end;
+                last_replacement.end = self.text.len();
+            }
+            _ => self.edits.push((original, start..self.text.len())),
+        }
+    }
+}
+
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map(|((index, _), _)| index)
+        .unwrap_or_else(|| a.len().min(b.len()))
+}
+
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .rev()
+        .zip(b.chars().rev())
+        .find(|((_, x), y)| x != y)
+        .map(|((index, x), _)| a.len() - index - x.len_utf8())
+        .unwrap_or_else(|| a.len().min(b.len()))
+}
+
+impl<'source> Source<'source> for &'source NormalizedSource {
+    type Slice = &'source str;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    #[inline]
+    fn read<Chunk>(&self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'source>,
+    {
+        let source: &'source str = self.as_str();
+
+        Source::read(&source, offset)
+    }
+
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<&'source str> {
+        let source: &'source str = self.as_str();
+
+        source.get(range)
+    }
+
+    #[inline]
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source str {
+        let source: &'source str = self.as_str();
+
+        Source::slice_unchecked(&source, range)
+    }
+
+    #[inline]
+    fn find_boundary(&self, index: usize) -> usize {
+        let source: &'source str = self.as_str();
+
+        Source::find_boundary(&source, index)
+    }
+}
+
+impl<'source, Token> Lexer<Token, &'source NormalizedSource>
+where
+    Token: Logos + WithSource<&'source NormalizedSource>,
+{
+    /// Get the range of the current token in the original, not normalized, source.
+    #[inline]
+    pub fn range_original(&self) -> Range<usize> {
+        self.source.original_range(self.range())
+    }
+}
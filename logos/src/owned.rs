@@ -0,0 +1,134 @@
+use alloc::string::String;
+use std::mem;
+
+use super::source::WithSource;
+use super::{Lexer, Logos};
+
+/// A `Lexer` that owns the `String` it's reading from.
+///
+/// Unlike a `Lexer` over `&str`, this can be freely moved around and returned
+/// from functions without having to keep the source alive separately.
+///
+/// ```rust
+/// use logos::{Logos, OwnedLexer};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// fn lex_file() -> OwnedLexer<Token> {
+///     let contents = String::from("hello world");
+///
+///     OwnedLexer::new(contents)
+/// }
+///
+/// fn main() {
+///     let mut lexer = lex_file();
+///
+///     assert_eq!(lexer.lexer().token, Token::Word);
+///     assert_eq!(lexer.lexer().slice(), "hello");
+///
+///     lexer.advance();
+///
+///     assert_eq!(lexer.lexer().token, Token::Word);
+///     assert_eq!(lexer.lexer().slice(), "world");
+/// }
+/// ```
+///
+/// Tokens borrowing from the source can't be used with an `OwnedLexer`, since they
+/// could outlive the `String` it owns:
+///
+/// ```rust,compile_fail
+/// use logos::{Logos, Lexer, OwnedLexer, Slice, Source};
+///
+/// fn word<'s, S: Source<'s>>(lex: &mut Lexer<Token<'s>, S>) -> Result<&'s str, ()> {
+///     lex.slice().as_str().ok_or(())
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token<'source> {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex("[a-z]+", callback = "word")]
+///     Word(&'source str),
+/// }
+///
+/// fn main() {
+///     let lexer = OwnedLexer::<Token<'static>>::new(String::from("hello"));
+/// }
+/// ```
+pub struct OwnedLexer<Token: Logos> {
+    /// Borrows from the heap allocation of `source`, and must never hand out
+    /// the `'static` lifetime. Declared first so that it's dropped first.
+    lexer: Lexer<Token, &'static str>,
+    source: String,
+}
+
+impl<Token> OwnedLexer<Token>
+where
+    Token: Logos + for<'a> WithSource<&'a str>,
+{
+    /// Create a new `OwnedLexer`, taking ownership of the `source`.
+    pub fn new(source: String) -> Self
+    where
+        Token::Extras: Default,
+    {
+        // The contents of the `String` live on the heap, and are never mutated
+        // or reallocated while the `Lexer` exists, so moving `OwnedLexer`
+        // around doesn't invalidate the reference.
+        let borrowed: &'static str = unsafe { mem::transmute::<&str, _>(source.as_str()) };
+
+        OwnedLexer {
+            lexer: Lexer::new(borrowed),
+            source,
+        }
+    }
+
+    /// Advance the `Lexer` and attempt to produce the next `Token`.
+    #[inline]
+    pub fn advance(&mut self) {
+        self.lexer.advance();
+    }
+
+    /// Get the underlying `Lexer`, with its slices bound to the lifetime of
+    /// this `OwnedLexer`.
+    #[inline]
+    pub fn lexer(&self) -> &Lexer<Token, &str> {
+        &self.lexer
+    }
+
+    /// Get the `Extras` of the underlying `Lexer`.
+    #[inline]
+    pub fn extras(&self) -> &Token::Extras {
+        &self.lexer.extras
+    }
+
+    /// Get mutable access to the `Extras` of the underlying `Lexer`.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut Token::Extras {
+        &mut self.lexer.extras
+    }
+
+    /// Get the source this `OwnedLexer` is reading from.
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Consume the `OwnedLexer`, returning the source `String`.
+    pub fn into_source(self) -> String {
+        self.source
+    }
+}
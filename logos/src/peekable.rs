@@ -0,0 +1,171 @@
+use alloc::collections::VecDeque;
+use std::ops::Range;
+
+use super::source::{Source, WithSource};
+use super::{Lexer, Logos, Span};
+
+/// A `Lexer` that can look up to `k` tokens past the current one, created
+/// with `Lexer::peekable`.
+///
+/// Tokens peeked at are lexed right away, which means their callbacks run, and
+/// their `Extras` hooks are invoked, before the parser gets to them. To keep the
+/// `Extras` consistent with the current token, a copy of them is kept for every
+/// buffered token, and `extras` returns them as they were right after the current
+/// token was produced. Side effects of callbacks outside of `Extras` can't be
+/// undone, and happen when a token is first peeked at.
+///
+/// ```rust
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Ident,
+///
+///     #[token = "::"]
+///     PathSep,
+///
+///     #[token = "("]
+///     ParenOpen,
+/// }
+///
+/// fn main() {
+///     let mut lex = Token::lexer("foo::bar(").peekable(2);
+///
+///     assert_eq!(lex.token(), &Token::Ident);
+///     assert_eq!(lex.peek_nth(0), (&Token::PathSep, 3..5));
+///     assert_eq!(lex.peek_nth(1), (&Token::Ident, 5..8));
+///
+///     lex.advance();
+///
+///     assert_eq!(lex.token(), &Token::PathSep);
+///     assert_eq!(lex.slice(), "::");
+///     assert_eq!(lex.peek_nth(1), (&Token::ParenOpen, 8..9));
+/// }
+/// ```
+pub struct PeekableLexer<Token: Logos, Source> {
+    /// Positioned at the last buffered token.
+    lexer: Lexer<Token, Source>,
+    /// The current token, followed by the tokens lexed ahead of it.
+    buffer: VecDeque<Lexed<Token>>,
+    k: usize,
+}
+
+struct Lexed<Token: Logos> {
+    /// `None` for the last buffered token, which stays in the `Lexer` until it
+    /// advances, so that callbacks lexing ahead see it as the `last` token.
+    token: Option<Token>,
+    range: Range<usize>,
+    extras: Token::Extras,
+}
+
+impl<'source, Token, Source> PeekableLexer<Token, Source>
+where
+    Token: Logos + WithSource<Source>,
+    Token::Extras: Clone,
+    Source: self::Source<'source>,
+{
+    pub(crate) fn new(lexer: Lexer<Token, Source>, k: usize) -> Self {
+        let mut peekable = PeekableLexer {
+            lexer,
+            buffer: VecDeque::with_capacity(k + 1),
+            k,
+        };
+
+        peekable.push();
+        peekable
+    }
+
+    /// Add the token the inner `Lexer` is at to the buffer.
+    fn push(&mut self) {
+        self.buffer.push_back(Lexed {
+            token: None,
+            range: self.lexer.range(),
+            extras: self.lexer.extras.clone(),
+        });
+    }
+
+    /// Lex the next token, moving the previous one out of the `Lexer` and into the buffer.
+    fn lex(&mut self) {
+        let token = self.lexer.advance_take();
+
+        if let Some(lexed) = self.buffer.back_mut() {
+            lexed.token = Some(token);
+        }
+
+        self.push();
+    }
+
+    /// Get the `n`-th buffered token.
+    fn token_at(&self, n: usize) -> &Token {
+        self.buffer[n].token.as_ref().unwrap_or(&self.lexer.token)
+    }
+
+    /// Advance to the next token, lexing it only if it hasn't been peeked at yet.
+    #[inline]
+    pub fn advance(&mut self) {
+        self.buffer.pop_front();
+
+        if self.buffer.is_empty() {
+            self.lex();
+        }
+    }
+
+    /// Get the `n`-th token following the current one, along with its range,
+    /// with `0` being the next token. Past the end this is the `#[end]` token.
+    ///
+    /// # Panics
+    ///
+    /// When `n` isn't smaller than the `k` the `PeekableLexer` was created with.
+    pub fn peek_nth(&mut self, n: usize) -> (&Token, Range<usize>) {
+        assert!(n < self.k, "Can only peek {} tokens ahead, tried peeking at #{}", self.k, n);
+
+        while self.buffer.len() <= n + 1 {
+            self.lex();
+        }
+
+        (self.token_at(n + 1), self.buffer[n + 1].range.clone())
+    }
+
+    /// Get the current token.
+    #[inline]
+    pub fn token(&self) -> &Token {
+        self.token_at(0)
+    }
+
+    /// Get the range for the current token in `Source`.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.buffer[0].range.clone()
+    }
+
+    /// Get the `Span` of the current token in `Source`, same as `range`.
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.range().into()
+    }
+
+    /// Get a slice of the current token.
+    #[inline]
+    pub fn slice(&self) -> Source::Slice {
+        unsafe { self.lexer.source.slice_unchecked(self.range()) }
+    }
+
+    /// Get the `Extras` as they were right after the current token was produced.
+    #[inline]
+    pub fn extras(&self) -> &Token::Extras {
+        &self.buffer[0].extras
+    }
+
+    /// Get the whole `Source` this lexer is reading from.
+    #[inline]
+    pub fn source(&self) -> &Source {
+        &self.lexer.source
+    }
+}
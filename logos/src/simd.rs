@@ -0,0 +1,83 @@
+//! Vectorized scanning used by the loops generated for `#[derive(Logos)]`
+//! with the `simd` feature enabled.
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Count how many leading bytes of `chunk` fall into any of the inclusive `ranges`.
+///
+/// ```rust
+/// use logos::internal::prefix_len;
+///
+/// let chunk = b"foo_bar42 + baz_qux_and_so_on_yy";
+/// let ident = &[(b'a', b'z'), (b'A', b'Z'), (b'0', b'9'), (b'_', b'_')];
+///
+/// assert_eq!(prefix_len(chunk, ident), 9);
+/// assert_eq!(prefix_len(chunk, &[(b'\0', b'\xFF')]), 32);
+/// assert_eq!(prefix_len(chunk, &[]), 0);
+/// ```
+#[inline]
+pub fn prefix_len(chunk: &[u8; 32], ranges: &[(u8, u8)]) -> usize {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { prefix_len_avx2(chunk, ranges) };
+        }
+    }
+
+    prefix_len_fallback(chunk, ranges)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+fn prefix_len_fallback(chunk: &[u8; 32], ranges: &[(u8, u8)]) -> usize {
+    // SSE2 is part of the x86_64 baseline, no need to detect it
+    unsafe { prefix_len_sse2(chunk, ranges) }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+#[inline]
+fn prefix_len_fallback(chunk: &[u8; 32], ranges: &[(u8, u8)]) -> usize {
+    chunk
+        .iter()
+        .position(|&byte| !ranges.iter().any(|&(start, end)| start <= byte && byte <= end))
+        .unwrap_or(32)
+}
+
+/// A byte is within `start..=end` if clamping it to the range leaves it unchanged.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn prefix_len_sse2(chunk: &[u8; 32], ranges: &[(u8, u8)]) -> usize {
+    let lo = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+    let hi = _mm_loadu_si128(chunk.as_ptr().add(16) as *const __m128i);
+    let mut lo_mask = _mm_setzero_si128();
+    let mut hi_mask = _mm_setzero_si128();
+
+    for &(start, end) in ranges {
+        let start = _mm_set1_epi8(start as i8);
+        let end = _mm_set1_epi8(end as i8);
+
+        lo_mask = _mm_or_si128(lo_mask, _mm_cmpeq_epi8(_mm_min_epu8(_mm_max_epu8(lo, start), end), lo));
+        hi_mask = _mm_or_si128(hi_mask, _mm_cmpeq_epi8(_mm_min_epu8(_mm_max_epu8(hi, start), end), hi));
+    }
+
+    let mask = (_mm_movemask_epi8(lo_mask) as u32 & 0xFFFF) | ((_mm_movemask_epi8(hi_mask) as u32) << 16);
+
+    (!mask).trailing_zeros() as usize
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+#[target_feature(enable = "avx2")]
+unsafe fn prefix_len_avx2(chunk: &[u8; 32], ranges: &[(u8, u8)]) -> usize {
+    let bytes = _mm256_loadu_si256(chunk.as_ptr() as *const __m256i);
+    let mut mask = _mm256_setzero_si256();
+
+    for &(start, end) in ranges {
+        let start = _mm256_set1_epi8(start as i8);
+        let end = _mm256_set1_epi8(end as i8);
+
+        mask = _mm256_or_si256(mask, _mm256_cmpeq_epi8(_mm256_min_epu8(_mm256_max_epu8(bytes, start), end), bytes));
+    }
+
+    (!(_mm256_movemask_epi8(mask) as u32)).trailing_zeros() as usize
+}
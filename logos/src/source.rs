@@ -1,12 +1,74 @@
 //! This module contains a bunch of traits necessary for processing byte strings.
 //!
 //! Most notable are:
-//! * `Source` - implemented by default for `&str` and `&[u8]`, used by the `Lexer`.
+//! * `Source` - implemented by default for `&str`, `&[u8]`, `&Cow<str>`, `&Arc<str>` and `&Rc<str>`,
+//!   used by the `Lexer`.
 //! * `Slice` - slices of `Source`, returned by `Lexer::slice`.
+//! * `LineIndex` - converts byte offsets to lines and columns for reporting errors.
+//!
+//! The state machines generated by `#[derive(Logos)]` always operate on bytes, matching
+//! non-ASCII characters by their UTF-8 encoding, so any `Source` has to be able to expose
+//! its contents as bytes. Input that has already been decoded into `char`s, such as
+//! a `Vec<char>`, needs to be collected into a `String` first, which can then be lexed
+//! in place or handed over to an `OwnedLexer`. UTF-16 input can be lexed through
+//! a `Utf16Source`, which transcodes it and maps ranges back to UTF-16 code units.
+//! Similarly, input that needs to be normalized before lexing, such as by turning CRLF
+//! line breaks into LF, can go through a `NormalizedSource` that maps ranges back to
+//! the original.
+//!
+//! There is no `Source` for `&mut [u8]`, since the slices of tokens borrow from the source
+//! for as long as it lives, and mutating the buffer under them wouldn't be sound. Buffers
+//! decoded in place, such as by unescaping strings into them, can be lexed through a shared
+//! reborrow instead. Once the `Lexer` is dropped the buffer can be written to again, and
+//! lexing can pick up where it left off with `Logos::lexer_in`, as long as the input past
+//! that point is left untouched:
+//!
+//! ```rust
+//! use logos::Logos;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex(br#""([^"\\]|\\")*""#)]
+//!     String,
+//! }
+//!
+//! fn main() {
+//!     let mut buf = *br#""a\"b" "c""#;
+//!     let (mut read, mut write) = (0, 0);
+//!
+//!     loop {
+//!         let lex = Token::lexer_in(&buf[..], read..buf.len());
+//!         let range = lex.range();
+//!
+//!         if lex.token == Token::End {
+//!             break;
+//!         }
+//!
+//!         // Drop the backslashes escaping quotes, only writing over what's been lexed
+//!         for i in read..range.end {
+//!             if buf[i] != b'\\' {
+//!                 buf[write] = buf[i];
+//!                 write += 1;
+//!             }
+//!         }
+//!         read = range.end;
+//!     }
+//!
+//!     assert_eq!(&buf[..write], br#""a"b" "c""#);
+//! }
+//! ```
 
 use std::fmt::Debug;
 use std::ops::Range;
 
+use crate::{Lexer, Logos};
+
 /// Trait for a `Slice` of a `Source` that the `Lexer` can consume.
 ///
 /// Most commonly, those will be the same types:
@@ -16,12 +78,35 @@ pub trait Slice<'source>: Sized + PartialEq + Eq + Debug {
     /// In all implementations we should at least be able to obtain a
     /// slice of bytes as the lowest level common denominator.
     fn as_bytes(&self) -> &'source [u8];
+
+    /// Get the slice as a string slice borrowed from the source, such as for a token
+    /// holding it, or `None` if it isn't valid UTF-8, which can only be the case for
+    /// binary sources such as `&[u8]`.
+    ///
+    /// ```rust
+    /// use logos::Slice;
+    ///
+    /// fn main() {
+    ///     assert_eq!(Slice::as_str(&"foo"), Some("foo"));
+    ///     assert_eq!(Slice::as_str(&&b"foo"[..]), Some("foo"));
+    ///     assert_eq!(Slice::as_str(&&b"\xFF"[..]), None);
+    /// }
+    /// ```
+    #[inline]
+    fn as_str(&self) -> Option<&'source str> {
+        std::str::from_utf8(self.as_bytes()).ok()
+    }
 }
 
 impl<'source> Slice<'source> for &'source str {
     fn as_bytes(&self) -> &'source [u8] {
         (*self).as_bytes()
     }
+
+    #[inline]
+    fn as_str(&self) -> Option<&'source str> {
+        Some(self)
+    }
 }
 
 impl<'source> Slice<'source> for &'source [u8] {
@@ -59,6 +144,11 @@ pub trait Source<'source> {
     ///     assert_eq!(foo.read(2), Some(b'o'));       // Option<u8>
     ///     assert_eq!(foo.read::<&[u8; 4]>(0), None); // Out of bounds
     ///     assert_eq!(foo.read::<&[u8; 2]>(2), None); // Out of bounds
+    ///
+    ///     let long = "supercalifragilisticexpialidocious";
+    ///
+    ///     assert_eq!(long.read::<&[u8; 32]>(2), Some(b"percalifragilisticexpialidocious"));
+    ///     assert_eq!(long.read::<&[u8; 32]>(3), None);
     /// }
     /// ```
     fn read<Chunk>(&self, offset: usize) -> Option<Chunk>
@@ -79,11 +169,34 @@ pub trait Source<'source> {
     /// ```
     fn slice(&self, range: Range<usize>) -> Option<Self::Slice>;
 
+    /// Get the bytes of the source at given range, the same as `slice` followed
+    /// by `Slice::as_bytes`, for code generic over the `Source`.
+    ///
+    /// ```rust
+    /// use logos::Source;
+    ///
+    /// fn main() {
+    ///     let foo = "zażółć";
+    ///
+    ///     assert_eq!(Source::slice_bytes(&foo, 0..4), Some(&b"za\xC5\xBC"[..]));
+    ///     assert_eq!(Source::slice_bytes(&&b"za\xC5\xBC"[..], 1..3), Some(&b"a\xC5"[..]));
+    ///     assert_eq!(Source::slice_bytes(&foo, 1..3), None); // Not on a char boundary
+    /// }
+    /// ```
+    #[inline]
+    fn slice_bytes(&self, range: Range<usize>) -> Option<&'source [u8]> {
+        self.slice(range).map(|slice| slice.as_bytes())
+    }
+
     /// Get a slice of the source at given range. This is analogous to
     /// `slice::get_unchecked(range)`.
     ///
     /// **Using this method with range out of bounds is undefined behavior!**
     ///
+    /// The implementations for `&str` and `&[u8]` check the range with a `debug_assert!`.
+    /// Enabling the `debug-bounds` feature makes them check it in release builds as well,
+    /// panicking with the range, such as when fuzzing token definitions.
+    ///
     /// ```rust
     /// use logos::Source;
     ///
@@ -108,6 +221,12 @@ pub trait Source<'source> {
 
 /// Marker trait for any `Source` that can be sliced into arbitrary byte chunks,
 /// with no regard for UTF-8 (or any other) character encoding.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` isn't a binary source, which the token enum requires",
+    label = "the token enum has definitions that can match invalid UTF-8",
+    note = "definitions that can match invalid UTF-8 restrict lexing to binary sources, such as `&[u8]`",
+    note = "add #[logos(utf8)] to the token enum to point out those definitions"
+)]
 pub trait BinarySource<'source>: Source<'source> {}
 
 /// Marker trait for any `Logos`, which will constrain it to a specific subset of
@@ -115,11 +234,29 @@ pub trait BinarySource<'source>: Source<'source> {}
 ///
 /// In particular, if your token definitions would allow reading invalid UTF-8,
 /// the `Logos` derive macro will restrict you to lexing on `Source`s that also
-/// implement the `BinarySource` marker (`&[u8]` is provided).
+/// implement the `BinarySource` marker (`&[u8]` is provided). Byte string definitions
+/// that can only match valid UTF-8, such as `b"let"`, don't restrict the enum. Adding
+/// `#[logos(utf8)]` to the enum turns every definition that can match invalid UTF-8
+/// into a compile error pointing at it, along with the bytes responsible.
 ///
 /// **Note:** You shouldn't implement this trait yourself, `#[derive(Logos)]` will
 /// do it for you.
-pub trait WithSource<Source> {}
+pub trait WithSource<Source> {
+    /// Produce the next token, as `Logos::lex` does. The generated code lives here,
+    /// where the lifetime of a token enum holding borrowed data is tied to the `Source`.
+    #[doc(hidden)]
+    fn lex_with(lex: &mut Lexer<Self, Source>)
+    where
+        Self: Logos;
+}
+
+/// Panic on an invalid `range` given to `Source::slice_unchecked` with the `debug-bounds`
+/// feature enabled.
+#[cold]
+#[inline(never)]
+fn invalid_slice(range: Range<usize>, len: usize) -> ! {
+    panic!("Invalid range {:?} for a source of {} bytes!", range, len)
+}
 
 impl<'source> Source<'source> for &'source str {
     type Slice = &'source str;
@@ -148,6 +285,10 @@ impl<'source> Source<'source> for &'source str {
 
     #[inline]
     unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source str {
+        if cfg!(feature = "debug-bounds") {
+            return self.get(range.clone()).unwrap_or_else(|| invalid_slice(range, self.len()));
+        }
+
         debug_assert!(
             range.start <= self.len() && range.end <= self.len(),
             "Reading out of bounds {:?} for {}!",
@@ -195,6 +336,10 @@ impl<'source> Source<'source> for &'source [u8] {
 
     #[inline]
     unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source [u8] {
+        if cfg!(feature = "debug-bounds") {
+            return self.get(range.clone()).unwrap_or_else(|| invalid_slice(range, self.len()));
+        }
+
         debug_assert!(
             range.start <= self.len() && range.end <= self.len(),
             "Reading out of bounds {:?} for {}!",
@@ -208,9 +353,319 @@ impl<'source> Source<'source> for &'source [u8] {
 
 impl<'source> BinarySource<'source> for &'source [u8] {}
 
+/// `Source` for a reference to a `Cow`, so that borrowed and owned input can be lexed
+/// the same way. The `Lexer` borrows the `Cow` itself, so returned slices live for as
+/// long as that borrow, and not for the lifetime of the data inside a `Cow::Borrowed`.
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use logos::Source;
+///
+/// fn normalize(input: &str) -> Cow<'_, str> {
+///     match input.contains('\t') {
+///         true => Cow::Owned(input.replace('\t', " ")),
+///         false => Cow::Borrowed(input),
+///     }
+/// }
+///
+/// fn main() {
+///     let normalized = normalize("Eschaton\timmanentized");
+///     let source = &normalized; // Slices are tied to this borrow
+///
+///     assert_eq!(Source::slice(&source, 8..9), Some(" "));
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+impl<'source, 'cow> Source<'source> for &'source alloc::borrow::Cow<'cow, str> {
+    type Slice = &'source str;
+
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    #[inline]
+    fn read<Chunk>(&self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'source>,
+    {
+        let source: &'source str = self;
+
+        Source::read(&source, offset)
+    }
+
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<&'source str> {
+        let source: &'source str = self;
+
+        source.get(range)
+    }
+
+    #[inline]
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source str {
+        let source: &'source str = self;
+
+        Source::slice_unchecked(&source, range)
+    }
+
+    #[inline]
+    fn find_boundary(&self, index: usize) -> usize {
+        let source: &'source str = self;
+
+        Source::find_boundary(&source, index)
+    }
+}
+
+macro_rules! impl_shared_source {
+    ($($shared:ty),*) => ($(
+        /// `Source` for a reference to a reference counted string. Same as with `&Cow<str>`,
+        /// slices returned by the `Lexer` are tied to the borrow of the pointer. To keep
+        /// a slice around past that borrow, such as when sending it to another thread,
+        /// get a `SharedSlice` with `Lexer::shared_slice`.
+        #[cfg(feature = "alloc")]
+        impl<'source> Source<'source> for &'source $shared {
+            type Slice = &'source str;
+
+            #[inline]
+            fn len(&self) -> usize {
+                (**self).len()
+            }
+
+            #[inline]
+            fn read<Chunk>(&self, offset: usize) -> Option<Chunk>
+            where
+                Chunk: self::Chunk<'source>,
+            {
+                let source: &'source str = self;
+
+                Source::read(&source, offset)
+            }
+
+            #[inline]
+            fn slice(&self, range: Range<usize>) -> Option<&'source str> {
+                let source: &'source str = self;
+
+                source.get(range)
+            }
+
+            #[inline]
+            unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source str {
+                let source: &'source str = self;
+
+                Source::slice_unchecked(&source, range)
+            }
+
+            #[inline]
+            fn find_boundary(&self, index: usize) -> usize {
+                let source: &'source str = self;
+
+                Source::find_boundary(&source, index)
+            }
+        }
+    )*);
+}
+
+impl_shared_source!(alloc::sync::Arc<str>, alloc::rc::Rc<str>);
+
+/// Slice of a reference counted string, keeping the whole string alive. This is
+/// cheap to clone, and for `Arc<str>` can be sent to other threads.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use logos::Logos;
+/// use logos::source::SharedSlice;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// fn first_word(document: &Arc<str>) -> SharedSlice<Arc<str>> {
+///     Token::lexer(document).shared_slice()
+/// }
+///
+/// fn main() {
+///     let document: Arc<str> = "hello world".into();
+///     let word = first_word(&document);
+///
+///     drop(document);
+///
+///     assert_eq!(&*word, "hello");
+///     assert_eq!(word.range(), 0..5);
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct SharedSlice<Shared> {
+    source: Shared,
+    range: Range<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> SharedSlice<Shared> {
+    /// Create a new `SharedSlice` of `source`, returning `None` if the `range` is out
+    /// of bounds or not on `char` boundaries.
+    pub fn new(source: Shared, range: Range<usize>) -> Option<Self> {
+        source.get(range.clone())?;
+
+        Some(SharedSlice { source, range })
+    }
+
+    /// Range of this slice in the whole string.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get the whole string this is a slice of.
+    pub fn source(&self) -> &Shared {
+        &self.source
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> std::ops::Deref for SharedSlice<Shared> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        // Bounds and boundaries are checked when creating the slice, but `Shared`
+        // could be any `Deref`, which isn't bound to return the same string again
+        self.source
+            .get(self.range.clone())
+            .expect("The source of a SharedSlice must not change")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> Debug for SharedSlice<Shared> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> std::fmt::Display for SharedSlice<Shared> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> PartialEq for SharedSlice<Shared> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> Eq for SharedSlice<Shared> {}
+
+#[cfg(feature = "alloc")]
+impl<Shared: std::ops::Deref<Target = str>> PartialEq<str> for SharedSlice<Shared> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, Shared: std::ops::Deref<Target = str>> PartialEq<&'a str> for SharedSlice<Shared> {
+    fn eq(&self, other: &&'a str) -> bool {
+        **self == **other
+    }
+}
+
+/// Get the line and column of the `byte` offset in `source`, both 1-based.
+///
+/// Lines are broken on `\n`, and columns count UTF-8 characters, the same as in
+/// `Lexer::position`. This scans the source up to `byte`, so when converting many
+/// offsets of the same source, build a `LineIndex` once instead.
+///
+/// ```rust
+/// use logos::source::line_col;
+///
+/// let source = "let foo;\nlet żółw = foo;";
+///
+/// assert_eq!(line_col(source, 4), (1, 5));
+/// assert_eq!(line_col(source, 23), (2, 12));
+/// ```
+pub fn line_col<Source: AsRef<[u8]> + ?Sized>(source: &Source, byte: usize) -> (usize, usize) {
+    let source = source.as_ref();
+    let before = &source[..byte.min(source.len())];
+    let line_start = before
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+
+    (line, column(&before[line_start..]))
+}
+
+/// Index of line starts in a source, converting byte offsets to lines and columns
+/// with a binary search, which makes it useful for reporting many errors at once.
+///
+/// ```rust
+/// use logos::source::LineIndex;
+///
+/// let source = "let foo;\r\nlet żółw = foo;\n";
+/// let index = LineIndex::new(source);
+///
+/// assert_eq!(index.lines(), 3);
+/// assert_eq!(index.line_col(0), (1, 1));
+/// assert_eq!(index.line_col(10), (2, 1));
+/// assert_eq!(index.line_col(24), (2, 12));
+/// assert_eq!(index.line_col(29), (3, 1));
+/// ```
+#[cfg(feature = "alloc")]
+pub struct LineIndex<'source> {
+    source: &'source [u8],
+    starts: alloc::vec::Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'source> LineIndex<'source> {
+    /// Create a new `LineIndex`, scanning the whole `source` for line breaks.
+    pub fn new<Source: AsRef<[u8]> + ?Sized>(source: &'source Source) -> Self {
+        let source = source.as_ref();
+        let starts = std::iter::once(0)
+            .chain(source.iter().enumerate().filter(|(_, &byte)| byte == b'\n').map(|(idx, _)| idx + 1))
+            .collect();
+
+        LineIndex { source, starts }
+    }
+
+    /// Number of lines in the source. A line break at the very end starts an empty line.
+    pub fn lines(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Get the line and column of the `byte` offset, both 1-based, see `line_col`.
+    pub fn line_col(&self, byte: usize) -> (usize, usize) {
+        let byte = byte.min(self.source.len());
+        let line = match self.starts.binary_search(&byte) {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+
+        (line + 1, column(&self.source[self.starts[line]..byte]))
+    }
+}
+
+/// Count the UTF-8 characters in `line`, plus one for a 1-based column past them.
+pub(crate) fn column(line: &[u8]) -> usize {
+    line.iter().filter(|&&byte| byte & 0xC0 != 0x80).count() + 1
+}
+
 /// A fixed, statically sized chunk of data that can be read from the `Source`.
 ///
-/// This is implemented for `u8`, as well as byte arrays `&[u8; 1]` to `&[u8; 16]`.
+/// This is implemented for `u8`, as well as byte arrays `&[u8; 1]` to `&[u8; 32]`.
 pub trait Chunk<'source>: Sized + Copy + PartialEq + Eq {
     /// Size of the chunk being accessed in bytes.
     const SIZE: usize;
@@ -241,4 +696,7 @@ macro_rules! impl_array {
     )*);
 }
 
-impl_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
\ No newline at end of file
+impl_array!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32
+);
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+/// Byte positions of a token in the `Source`, returned by `Lexer::span`.
+///
+/// Unlike `Range<usize>` this type is `Copy`, and can be merged with other spans,
+/// to cover a whole expression made of multiple tokens:
+///
+/// ```rust
+/// use logos::{Logos, Span};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(trivia = " ")]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[0-9]+"]
+///     Number,
+///
+///     #[token = "+"]
+///     Plus,
+/// }
+///
+/// fn main() {
+///     let mut lex = Token::lexer("1 + 20");
+///     let first = lex.span();
+///
+///     lex.advance();
+///     lex.advance();
+///
+///     let expr = first.merge(lex.span());
+///
+///     assert_eq!(expr, Span { start: 0, end: 6 });
+///     assert_eq!(expr.len(), 6);
+///     assert_eq!(Range::from(expr), 0..6);
+/// }
+/// # use std::ops::Range;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    /// Position of the first byte.
+    pub start: usize,
+    /// Position right after the last byte.
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a new `Span` from `start` to `end`.
+    #[inline]
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Length of the span in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Check if the span covers no bytes at all, which is the case for the `#[end]` token.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Smallest span covering both this span and the `other` one, along with
+    /// anything in between.
+    #[inline]
+    pub fn merge(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl From<Range<usize>> for Span {
+    #[inline]
+    fn from(range: Range<usize>) -> Self {
+        Span {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl From<Span> for Range<usize> {
+    #[inline]
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
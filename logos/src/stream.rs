@@ -0,0 +1,222 @@
+use std::io::{self, Read};
+use std::mem;
+use std::ops::Range;
+
+use super::lexer::Extras;
+use super::source::WithSource;
+use super::{Lexer, Logos, Span};
+
+/// Smallest window of input kept buffered, covering the largest chunk the
+/// generated code can read at once a couple of times over.
+const MIN_WINDOW: usize = 64;
+
+/// A lexer reading its input from an `io::Read`, keeping only a window of it
+/// in memory at any time.
+///
+/// Since the buffer is discarded as the lexer moves forward, slices are only
+/// available for the current token. If lexing a token reads up to the end of the
+/// buffered window, more input is read and the token is lexed again from a clone
+/// of the `Extras` it started with, which means callbacks can run more than once
+/// for such tokens. Callbacks also only see the buffered window through
+/// `Lexer::remainder`.
+///
+/// ```rust
+/// use logos::{Logos, StreamingLexer};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+/// }
+///
+/// fn main() -> std::io::Result<()> {
+///     let file = &b"hello streaming world"[..]; // Any `io::Read` will do
+///     let mut lexer = StreamingLexer::<Token, _>::new(file)?;
+///
+///     assert_eq!(lexer.token, Token::Word);
+///     assert_eq!(lexer.slice(), b"hello");
+///
+///     lexer.advance()?;
+///
+///     assert_eq!(lexer.token, Token::Word);
+///     assert_eq!(lexer.slice(), b"streaming");
+///     assert_eq!(lexer.range(), 6..15);
+///
+///     Ok(())
+/// }
+/// ```
+pub struct StreamingLexer<Token: Logos, Reader> {
+    /// Current token. Call the `advance` method to get a new token.
+    pub token: Token,
+
+    /// Extras associated with the `Token`.
+    pub extras: Token::Extras,
+
+    reader: Reader,
+    buffer: Vec<u8>,
+    /// Size the buffer is filled up to before lexing.
+    window: usize,
+    /// Size of the window to go back to after a token needed a larger one.
+    capacity: usize,
+    /// Offset of the start of `buffer` in the stream.
+    offset: usize,
+    token_start: usize,
+    token_end: usize,
+    /// Token queued with `Lexer::emit_next`, and its range in the stream.
+    queued: Option<(Token, Range<usize>)>,
+    eof: bool,
+}
+
+impl<Token, Reader> StreamingLexer<Token, Reader>
+where
+    Token: Logos + for<'source> WithSource<&'source [u8]>,
+    Token::Extras: Default + Clone,
+    Reader: Read,
+{
+    /// Create a new `StreamingLexer` with the default window of 8KiB, reading
+    /// the first token.
+    pub fn new(reader: Reader) -> io::Result<Self> {
+        Self::with_capacity(reader, 8 * 1024)
+    }
+
+    /// Create a new `StreamingLexer` that keeps at least `capacity` bytes of
+    /// input buffered, reading the first token.
+    pub fn with_capacity(reader: Reader, capacity: usize) -> io::Result<Self> {
+        let window = capacity.max(MIN_WINDOW);
+        let mut lex = StreamingLexer {
+            token: Token::ERROR,
+            extras: Default::default(),
+            reader,
+            buffer: Vec::with_capacity(window),
+            window,
+            capacity: window,
+            offset: 0,
+            token_start: 0,
+            token_end: 0,
+            queued: None,
+            eof: false,
+        };
+
+        lex.advance()?;
+
+        Ok(lex)
+    }
+
+    /// Advance the `StreamingLexer` and attempt to produce the next `Token`,
+    /// reading more input if necessary.
+    pub fn advance(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.token_end);
+        self.offset += self.token_end;
+        self.token_start = 0;
+        self.token_end = 0;
+        self.extras.on_advance();
+
+        if let Some((token, range)) = self.queued.take() {
+            self.token_start = range.start - self.offset;
+            self.token_end = range.end - self.offset;
+            self.token = token;
+            self.extras.on_token(&self.token, &&self.buffer[self.token_start..self.token_end]);
+
+            return Ok(());
+        }
+
+        loop {
+            self.fill()?;
+
+            // Past the end of input there is nothing to retry with
+            let extras = match self.eof {
+                true => mem::take(&mut self.extras),
+                false => self.extras.clone(),
+            };
+            let mut lex = Lexer::prepare_at(&self.buffer[..], extras, self.offset);
+
+            Token::lex(&mut lex);
+
+            if self.eof || !lex.touched_end() {
+                let (token, extras, range, queued) = lex.into_parts();
+
+                self.extras = extras;
+                self.window = self.capacity;
+                self.token_start = range.start;
+                self.token_end = range.end;
+                self.token = match mem::discriminant(&token) == mem::discriminant(&Token::ERROR) {
+                    true => Token::error(self.range()),
+                    false => token,
+                };
+                self.extras.on_token(&self.token, &&self.buffer[range]);
+                self.queued = queued.map(|(token, range)| {
+                    (token, self.offset + range.start..self.offset + range.end)
+                });
+
+                return Ok(());
+            }
+
+            // The token could continue past the end of the buffer, read more
+            // input and try again with the `Extras` from before the attempt.
+            self.window = self.buffer.len() * 2;
+        }
+    }
+
+    /// Get the range of the current token in the stream.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.offset + self.token_start..self.offset + self.token_end
+    }
+
+    /// Get the `Span` of the current token in the stream, same as `range`.
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.range().into()
+    }
+
+    /// Get a slice of the current token.
+    #[inline]
+    pub fn slice(&self) -> &[u8] {
+        &self.buffer[self.token_start..self.token_end]
+    }
+
+    /// Get the `Extras` associated with the `Token`.
+    #[inline]
+    pub fn extras(&self) -> &Token::Extras {
+        &self.extras
+    }
+
+    /// Get mutable access to the `Extras` associated with the `Token`.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut Token::Extras {
+        &mut self.extras
+    }
+
+    /// Read from the `Reader` until the buffer is filled up to the window size,
+    /// or the end of the input has been reached.
+    fn fill(&mut self) -> io::Result<()> {
+        while !self.eof && self.buffer.len() < self.window {
+            let len = self.buffer.len();
+
+            self.buffer.resize(self.window, 0);
+
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(0) => {
+                    self.buffer.truncate(len);
+                    self.eof = true;
+                }
+                Ok(read) => self.buffer.truncate(len + read),
+                Err(err) => {
+                    self.buffer.truncate(len);
+
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
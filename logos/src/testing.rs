@@ -0,0 +1,73 @@
+//! Helpers for testing token definitions, enabled with the `testing` feature.
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use crate::source::{self, WithSource};
+use crate::Logos;
+
+/// Lex `source` and assert that it produces exactly the expected `tokens`, each
+/// with its slice and range, followed by the `#[end]` token.
+///
+/// On mismatch this panics with the position of the offending token and both the
+/// expected and the actual token, slice and range.
+///
+/// ```rust
+/// use logos::Logos;
+/// use logos::testing::assert_lex;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex = "[a-z]+"]
+///     Word,
+///
+///     #[token = "="]
+///     Assign,
+/// }
+///
+/// fn main() {
+///     assert_lex(
+///         "foo = bar",
+///         &[
+///             (Token::Word, "foo", 0..3),
+///             (Token::Assign, "=", 4..5),
+///             (Token::Word, "bar", 6..9),
+///         ],
+///     );
+/// }
+/// ```
+#[track_caller]
+pub fn assert_lex<'source, Token, Source>(source: Source, tokens: &[(Token, Source::Slice, Range<usize>)])
+where
+    Token: Logos + WithSource<Source> + Debug + PartialEq,
+    Token::Extras: Default,
+    Source: source::Source<'source>,
+{
+    let mut lex = Token::lexer(source);
+
+    for (index, (token, slice, range)) in tokens.iter().enumerate() {
+        let actual = lex.slice();
+
+        if lex.token != *token || actual != *slice || lex.range() != *range {
+            panic!(
+                "token #{} doesn't match\n\n  expected: {:?} {:?} at {:?}\n    actual: {:?} {:?} at {:?}\n",
+                index, token, slice, range, lex.token, actual, lex.range(),
+            );
+        }
+
+        lex.advance();
+    }
+
+    if lex.token != Token::END {
+        panic!(
+            "expected the end after {} tokens\n\n    actual: {:?} {:?} at {:?}\n",
+            tokens.len(), lex.token, lex.slice(), lex.range(),
+        );
+    }
+}
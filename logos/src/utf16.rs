@@ -0,0 +1,161 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::ops::Range;
+
+use crate::source::{Chunk, Source, WithSource};
+use crate::{Lexer, Logos};
+
+/// A `Source` decoded from UTF-16, such as strings handed over by a JavaScript host
+/// or Windows APIs, that can report ranges in UTF-16 code units.
+///
+/// The state machines generated by `#[derive(Logos)]` operate on UTF-8, so the input
+/// is transcoded once up front, keeping the UTF-16 offset of every non-ASCII character
+/// to map ranges back. Unpaired surrogates are replaced with `U+FFFD`, which takes up
+/// a single code unit just like them, so the offsets of everything following stay intact.
+///
+/// `Lexer::range` and `Lexer::span` remain in bytes of the decoded source, as with any
+/// other `Source`, use `Lexer::range_utf16` for ranges in code units.
+///
+/// ```rust
+/// use logos::{Logos, Utf16Source};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[end]
+///     End,
+///
+///     #[error]
+///     Error,
+///
+///     #[regex(r"\w+", unicode)]
+///     Word,
+/// }
+///
+/// fn main() {
+///     let units: Vec<u16> = "🦀 żółw".encode_utf16().collect();
+///     let source = Utf16Source::new(&units);
+///     let mut lex = Token::lexer(&source);
+///
+///     lex.advance();
+///
+///     assert_eq!(lex.token, Token::Word);
+///     assert_eq!(lex.slice(), "żółw");
+///     assert_eq!(lex.range(), 5..12);
+///     assert_eq!(lex.range_utf16(), 3..7);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf16Source {
+    text: String,
+    /// Byte and UTF-16 offsets of every non-ASCII character in `text`. ASCII between
+    /// them takes up one code unit per byte.
+    offsets: Vec<(usize, usize)>,
+}
+
+impl Utf16Source {
+    /// Decode the UTF-16 code `units`.
+    pub fn new(units: &[u16]) -> Self {
+        let mut text = String::with_capacity(units.len());
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+
+        for ch in std::char::decode_utf16(units.iter().copied()) {
+            let ch = ch.unwrap_or(std::char::REPLACEMENT_CHARACTER);
+
+            if !ch.is_ascii() {
+                offsets.push((text.len(), offset));
+            }
+            text.push(ch);
+            offset += ch.len_utf16();
+        }
+
+        Utf16Source { text, offsets }
+    }
+
+    /// Get the decoded source.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Convert a byte offset in the decoded source to an offset in UTF-16 code units.
+    /// Offsets in the middle of a character map to the start of that character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `offset` is past the end of the source.
+    #[inline]
+    pub fn utf16_offset(&self, offset: usize) -> usize {
+        assert!(offset <= self.text.len(), "Offset past the end of the source");
+
+        let idx = self.offsets.partition_point(|&(byte, _)| byte <= offset);
+        let (byte, utf16) = match idx {
+            0 => return offset,
+            idx => self.offsets[idx - 1],
+        };
+        let ch = self.text[byte..].chars().next().expect("Offsets point at characters");
+
+        match offset < byte + ch.len_utf8() {
+            true => utf16,
+            false => utf16 + ch.len_utf16() + (offset - byte - ch.len_utf8()),
+        }
+    }
+
+    /// Convert a `range` of bytes in the decoded source, such as the `range` of
+    /// a token, to UTF-16 code units.
+    #[inline]
+    pub fn utf16_range(&self, range: Range<usize>) -> Range<usize> {
+        self.utf16_offset(range.start)..self.utf16_offset(range.end)
+    }
+}
+
+impl<'source> Source<'source> for &'source Utf16Source {
+    type Slice = &'source str;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    #[inline]
+    fn read<Chunk>(&self, offset: usize) -> Option<Chunk>
+    where
+        Chunk: self::Chunk<'source>,
+    {
+        let source: &'source str = self.as_str();
+
+        Source::read(&source, offset)
+    }
+
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<&'source str> {
+        let source: &'source str = self.as_str();
+
+        source.get(range)
+    }
+
+    #[inline]
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'source str {
+        let source: &'source str = self.as_str();
+
+        Source::slice_unchecked(&source, range)
+    }
+
+    #[inline]
+    fn find_boundary(&self, index: usize) -> usize {
+        let source: &'source str = self.as_str();
+
+        Source::find_boundary(&source, index)
+    }
+}
+
+impl<'source, Token> Lexer<Token, &'source Utf16Source>
+where
+    Token: Logos + WithSource<&'source Utf16Source>,
+{
+    /// Get the range of the current token in UTF-16 code units of the original source.
+    #[inline]
+    pub fn range_utf16(&self) -> Range<usize> {
+        self.source.utf16_range(self.range())
+    }
+}
@@ -9,3 +9,4 @@ cd ..
 cd logos
 cargo test || exit
 cargo test --no-default-features --features export_derive || exit
+cargo test --no-default-features --features export_derive,alloc || exit
@@ -103,6 +103,45 @@ foobar(protected primitive private instanceof in) { + ++ = == === => }
 foobar(protected primitive private instanceof in) { + ++ = == === => }
 ";
 
+#[derive(Debug, Clone, Copy, PartialEq, Logos)]
+pub enum Keyword {
+    #[error]
+    InvalidToken,
+
+    #[end]
+    EndOfProgram,
+
+    #[regex = " +"]
+    Whitespace,
+
+    #[token = "function"]
+    Function,
+
+    #[token = "fallback"]
+    Fallback,
+
+    #[token = "synchronized"]
+    Synchronized,
+
+    #[token = "serializable"]
+    Serializable,
+
+    #[token = "internal_package"]
+    InternalPackage,
+
+    #[token = "implicit_virtual"]
+    ImplicitVirtual,
+}
+
+static KEYWORDS_8: &str = "function fallback function fallback function fallback function fallback \
+                           function fallback function fallback function fallback function fallback";
+
+static KEYWORDS_12: &str = "synchronized serializable synchronized serializable synchronized \
+                            serializable synchronized serializable synchronized serializable";
+
+static KEYWORDS_16: &str = "internal_package implicit_virtual internal_package implicit_virtual \
+                            internal_package implicit_virtual internal_package implicit_virtual";
+
 static IDENTIFIERS: &str = "It was the year when they finally immanentized the Eschaton \
                             It was the year when they finally immanentized the Eschaton \
                             It was the year when they finally immanentized the Eschaton \
@@ -170,3 +209,116 @@ fn strings(b: &mut Bencher) {
         black_box(lex.token)
     });
 }
+
+fn keywords(b: &mut Bencher, source: &str) {
+    use logos::Logos;
+
+    b.bytes = source.len() as u64;
+
+    b.iter(|| {
+        let mut lex = Keyword::lexer(source);
+
+        while lex.token != Keyword::EndOfProgram {
+            lex.advance();
+        }
+
+        black_box(lex.token)
+    });
+}
+
+#[bench]
+fn keywords_8_bytes(b: &mut Bencher) {
+    keywords(b, KEYWORDS_8);
+}
+
+#[bench]
+fn keywords_12_bytes(b: &mut Bencher) {
+    keywords(b, KEYWORDS_12);
+}
+
+#[bench]
+fn keywords_16_bytes(b: &mut Bencher) {
+    keywords(b, KEYWORDS_16);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Logos)]
+pub enum Text {
+    #[error]
+    InvalidToken,
+
+    #[end]
+    EndOfProgram,
+
+    #[regex = "[a-zA-Z_]+"]
+    Identifier,
+
+    #[regex = r#""[^"]*""#]
+    String,
+
+    #[regex = "//.*"]
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Logos)]
+#[logos(ascii)]
+pub enum AsciiText {
+    #[error]
+    InvalidToken,
+
+    #[end]
+    EndOfProgram,
+
+    #[regex = "[a-zA-Z_]+"]
+    Identifier,
+
+    #[regex = r#""[^"]*""#]
+    String,
+
+    #[regex = "//.*"]
+    Comment,
+}
+
+static TEXT: &str = r#"
+// It was the year when they finally immanentized the Eschaton
+print "tree to a graph that can more adequately represent loops and arbitrary state jumps"
+// with the explosive nature of trying to build up all possible permutations in a tree
+print "It was the year when they finally immanentized the Eschaton"
+// It was the year when they finally immanentized the Eschaton
+print "tree to a graph that can more adequately represent loops and arbitrary state jumps"
+// with the explosive nature of trying to build up all possible permutations in a tree
+print "It was the year when they finally immanentized the Eschaton"
+"#;
+
+#[bench]
+fn text_utf8(b: &mut Bencher) {
+    use logos::Logos;
+
+    b.bytes = TEXT.len() as u64;
+
+    b.iter(|| {
+        let mut lex = Text::lexer(TEXT);
+
+        while lex.token != Text::EndOfProgram {
+            lex.advance();
+        }
+
+        black_box(lex.token)
+    });
+}
+
+#[bench]
+fn text_ascii(b: &mut Bencher) {
+    use logos::Logos;
+
+    b.bytes = TEXT.len() as u64;
+
+    b.iter(|| {
+        let mut lex = AsciiText::lexer(TEXT);
+
+        while lex.token != AsciiText::EndOfProgram {
+            lex.advance();
+        }
+
+        black_box(lex.token)
+    });
+}
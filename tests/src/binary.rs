@@ -68,6 +68,12 @@ enum Token {
 
     #[token = b"\x00"]
     Zero,
+
+    #[regex = b"\x01."]
+    Escaped,
+
+    #[regex(b"\x02(?u:\\w+)\x03", unicode)]
+    Text,
 }
 
 #[test]
@@ -90,3 +96,27 @@ fn handles_non_utf8() {
         ],
     );
 }
+
+#[test]
+fn dot_matches_any_byte() {
+    assert_lex(
+        &[0x01, b'\n', 0x01, 0xFF, 0x01, 0x01][..],
+        &[
+            (Token::Escaped, &[0x01, b'\n'], 0..2),
+            (Token::Escaped, &[0x01, 0xFF], 2..4),
+            (Token::Escaped, &[0x01, 0x01], 4..6),
+        ],
+    );
+}
+
+#[test]
+fn utf8_subexpression() {
+    assert_lex(
+        &b"\x02za\xC5\xBC\xC3\xB3\xC5\x82\xC4\x87\x03\x02\xC5\x03"[..],
+        &[
+            (Token::Text, "\x02zażółć\x03".as_bytes(), 0..12),
+            (Token::Error, &[0x02, 0xC5], 12..14),
+            (Token::Error, &[0x03], 14..15),
+        ],
+    );
+}
@@ -1,22 +1,6 @@
-use std::fmt;
-use std::ops::Range;
-
 mod binary;
+mod lookahead;
+pub mod non_exhaustive;
+mod priority;
 
-pub fn assert_lex<'a, Source, Token>(
-    source: Source,
-    tokens: &[(Token, Source::Slice, Range<usize>)],
-) where
-    Token: logos::Logos + logos::source::WithSource<Source> + fmt::Debug + PartialEq + Clone + Copy,
-    Source: logos::Source<'a>,
-{
-    let mut lex = Token::lexer(source);
-
-    for tuple in tokens {
-        assert_eq!(&(lex.token, lex.slice(), lex.range()), tuple);
-
-        lex.advance();
-    }
-
-    assert_eq!(lex.token, Token::END);
-}
+pub use logos::testing::assert_lex;
@@ -0,0 +1,20 @@
+//! ```compile_fail
+//! use logos::Logos;
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("a|b(?=c)")]
+//!     AB,
+//! }
+//!
+//! fn main() {
+//!     Token::lexer("This shouldn't work with a lookahead after a top-level `|`!");
+//! }
+//! ```
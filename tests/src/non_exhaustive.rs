@@ -0,0 +1,19 @@
+//! Token enum marked `#[non_exhaustive]`, for testing it from other crates.
+
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+}
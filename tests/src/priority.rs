@@ -0,0 +1,109 @@
+//! Definitions of different variants must not tie with each other:
+//!
+//! ```compile_fail
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//!
+//!     #[regex("[a-z0-9]+")]
+//!     Alphanumeric,
+//! }
+//! ```
+//!
+//! Not even if both priorities were set explicitly:
+//!
+//! ```compile_fail
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+", priority = 3)]
+//!     Word,
+//!
+//!     #[regex("[a-z0-9]+", priority = 3)]
+//!     Alphanumeric,
+//! }
+//! ```
+//!
+//! Same, but with a literal:
+//!
+//! ```compile_fail
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//!
+//!     #[token("let", priority = 1)]
+//!     Let,
+//! }
+//! ```
+//!
+//! A definition that loses to others on every input it could match is reported with a
+//! warning, which can be turned into an error:
+//!
+//! ```compile_fail
+//! #![deny(deprecated)]
+//!
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+", priority = 10)]
+//!     Word,
+//!
+//!     #[token("let")]
+//!     Let,
+//! }
+//! ```
+//!
+//! Unless the definition allows it:
+//!
+//! ```
+//! #![deny(deprecated)]
+//!
+//! use logos_derive::Logos;
+//!
+//! #[derive(Logos)]
+//! enum Token {
+//!     #[end]
+//!     End,
+//!
+//!     #[error]
+//!     Error,
+//!
+//!     #[regex("[a-z]+", priority = 10)]
+//!     Word,
+//!
+//!     #[token("let", allow(unreachable))]
+//!     Let,
+//! }
+//! ```
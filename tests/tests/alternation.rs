@@ -0,0 +1,52 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("foo|foobar")]
+    Foo,
+
+    #[regex("(a|ab)(c|bcd)")]
+    Abcd,
+
+    #[regex("x(y|yy|yyy)z?")]
+    Xyz,
+}
+
+mod alternation {
+    use super::*;
+
+    #[test]
+    fn longest_alternative() {
+        assert_lex(
+            "foobar foo foob",
+            &[
+                (Token::Foo, "foobar", 0..6),
+                (Token::Foo, "foo", 7..10),
+                (Token::Foo, "foo", 11..14),
+                (Token::Error, "b", 14..15),
+            ],
+        );
+    }
+
+    #[test]
+    fn longest_across_concatenation() {
+        assert_lex(
+            "abcd ac abc xyyyz xyy",
+            &[
+                (Token::Abcd, "abcd", 0..4),
+                (Token::Abcd, "ac", 5..7),
+                (Token::Abcd, "abc", 8..11),
+                (Token::Xyz, "xyyyz", 12..17),
+                (Token::Xyz, "xyy", 18..21),
+            ],
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("^#![^\n]*")]
+    Shebang,
+
+    #[token = "#"]
+    Hash,
+
+    #[token = "!"]
+    Bang,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[a-z]+$")]
+    LastWord,
+
+    #[regex(";$")]
+    FinalSemicolon,
+}
+
+mod anchors {
+    use super::*;
+
+    #[test]
+    fn start_of_input() {
+        assert_lex(
+            "#!/bin/sh\nfoo #!bar",
+            &[
+                (Token::Shebang, "#!/bin/sh", 0..9),
+                (Token::Word, "foo", 10..13),
+                (Token::Hash, "#", 14..15),
+                (Token::Bang, "!", 15..16),
+                (Token::LastWord, "bar", 16..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn start_after_bom() {
+        let mut lex = Token::lexer_skip_bom("\u{FEFF}#!/bin/sh");
+
+        assert_eq!(lex.token, Token::Shebang);
+        assert_eq!(lex.range(), 3..12);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_lex::<Token, &str>("", &[]);
+    }
+
+    #[test]
+    fn not_at_start_of_input() {
+        assert_lex(
+            " #!foo ",
+            &[
+                (Token::Hash, "#", 1..2),
+                (Token::Bang, "!", 2..3),
+                (Token::Word, "foo", 3..6),
+            ],
+        );
+    }
+
+    #[test]
+    fn end_of_input() {
+        assert_lex(
+            "foo bar",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::LastWord, "bar", 4..7),
+            ],
+        );
+        assert_lex(
+            "foo;",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::FinalSemicolon, ";", 3..4),
+            ],
+        );
+    }
+
+    #[test]
+    fn not_at_end_of_input() {
+        assert_lex(
+            "; foo\n",
+            &[
+                (Token::Error, ";", 0..1),
+                (Token::Word, "foo", 2..5),
+            ],
+        );
+    }
+}
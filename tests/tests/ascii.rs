@@ -0,0 +1,70 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(ascii, trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex(r#""[^"]*""#)]
+    String,
+
+    #[regex("//.*")]
+    Comment,
+
+    #[token("else", ignore(case))]
+    Else,
+}
+
+mod ascii {
+    use super::*;
+
+    #[test]
+    fn lexes_ascii() {
+        assert_lex(
+            r#"foo "bar baz" ELSE // comment"#,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::String, "\"bar baz\"", 4..13),
+                (Token::Else, "ELSE", 14..18),
+                (Token::Comment, "// comment", 19..29),
+            ],
+        );
+    }
+
+    #[test]
+    fn non_ascii_is_error() {
+        assert_lex(
+            "\"żółw\" // ż",
+            &[
+                (Token::Error, "\"", 0..1),
+                (Token::Error, "ż", 1..3),
+                (Token::Error, "ó", 3..5),
+                (Token::Error, "ł", 5..7),
+                (Token::Word, "w", 7..8),
+                (Token::Error, "\" // ", 8..13),
+                (Token::Error, "ż", 13..15),
+            ],
+        );
+    }
+
+    #[test]
+    fn kelvin_sign_is_not_k() {
+        let mut lex = Token::lexer("\u{212A}");
+
+        assert_eq!(lex.token, Token::Error);
+        assert_eq!(lex.slice(), "\u{212A}");
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+    }
+}
@@ -0,0 +1,133 @@
+use logos::{Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+fn ident<'s, S: Source<'s>>(lex: &mut Lexer<Token<'s>, S>) -> Result<&'s str, ()> {
+    lex.slice().as_str().ok_or(())
+}
+
+fn string<'s, S: Source<'s>>(lex: &mut Lexer<Token<'s>, S>) -> Result<&'s str, ()> {
+    let slice = lex.slice().as_str().ok_or(())?;
+
+    Ok(&slice[1..slice.len() - 1])
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token<'source> {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*", callback = "ident")]
+    Ident(&'source str),
+
+    #[regex(r#""[^"]*""#, callback = "string")]
+    String(&'source str),
+
+    #[token("let")]
+    Let,
+
+    #[token("=")]
+    Assign,
+}
+
+fn bytes<'s, S: Source<'s>>(lex: &mut Lexer<Binary<'s>, S>) -> &'s [u8] {
+    lex.slice().as_bytes()
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = " ")]
+enum Binary<'a> {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(b"[a-z]+", callback = "bytes")]
+    Word(&'a [u8]),
+}
+
+fn collect(source: &str) -> Vec<Token<'_>> {
+    let mut lex = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    while lex.token != Token::End {
+        tokens.push(lex.token);
+        lex.advance();
+    }
+
+    tokens
+}
+
+mod borrowed {
+    use super::*;
+
+    #[test]
+    fn tokens_borrow_from_source() {
+        assert_lex(
+            r#"let foo = "bar baz""#,
+            &[
+                (Token::Let, "let", 0..3),
+                (Token::Ident("foo"), "foo", 4..7),
+                (Token::Assign, "=", 8..9),
+                (Token::String("bar baz"), r#""bar baz""#, 10..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn tokens_outlive_lexer() {
+        let source = String::from("let x = y");
+        let tokens = collect(&source);
+
+        assert_eq!(
+            tokens,
+            &[Token::Let, Token::Ident("x"), Token::Assign, Token::Ident("y")]
+        );
+    }
+
+    #[test]
+    fn into_vec() {
+        let source = "a = b";
+        let tokens = Token::lexer(source).into_vec();
+
+        assert_eq!(
+            tokens,
+            &[
+                (Token::Ident("a"), 0..1),
+                (Token::Assign, 2..3),
+                (Token::Ident("b"), 4..5),
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_source() {
+        let source = &b"foo \xFF bar"[..];
+        let mut lex = Binary::lexer(source);
+
+        assert_eq!(lex.token, Binary::Word(b"foo"));
+
+        lex.advance();
+
+        assert_eq!(lex.token, Binary::Error);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Binary::Word(b"bar"));
+    }
+
+    #[test]
+    fn discriminant_and_variants() {
+        use logos::Logos;
+
+        assert_eq!(Token::Ident("foo").discriminant(), 2);
+        assert_eq!(Token::Assign.discriminant(), 5);
+        assert_eq!(Token::Let.name(), "Let");
+        assert!(Token::variants().contains(&Token::Let));
+    }
+}
@@ -0,0 +1,137 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("in", boundary)]
+    In,
+
+    #[token("instanceof", boundary)]
+    Instanceof,
+
+    #[token("is", boundary = "[a-z-]")]
+    Is,
+
+    #[token("IF", ignore(case), boundary)]
+    If,
+
+    #[regex = "[0-9]+"]
+    Number,
+
+    #[token = "("]
+    ParenOpen,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(ident = "[a-zA-Z0-9_$]")]
+enum Js {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("in", boundary)]
+    In,
+
+    #[regex("\\$(?&ident)+")]
+    Var,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(subpattern(letter = "[a-z]"))]
+#[logos(subpattern(digit = "[0-9]"))]
+#[logos(ident = "(?&letter)")]
+enum Sub {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("if", boundary)]
+    If,
+
+    #[token("do", boundary = "(?&digit)")]
+    Do,
+
+    #[token = "x"]
+    X,
+
+    #[regex = "[0-9]+"]
+    Number,
+}
+
+mod boundary {
+    use super::*;
+
+    #[test]
+    fn keywords_at_boundary() {
+        assert_lex(
+            "in instanceof is(if) If",
+            &[
+                (Token::In, "in", 0..2),
+                (Token::Instanceof, "instanceof", 3..13),
+                (Token::Is, "is", 14..16),
+                (Token::ParenOpen, "(", 16..17),
+                (Token::If, "if", 17..19),
+                (Token::Error, ")", 19..20),
+                (Token::If, "If", 21..23),
+            ],
+        );
+    }
+
+    #[test]
+    fn keyword_followed_by_identifier_character() {
+        assert_lex(
+            "in2 is2",
+            &[
+                (Token::Error, "i", 0..1),
+                (Token::Error, "n", 1..2),
+                (Token::Number, "2", 2..3),
+                (Token::Is, "is", 4..6),
+                (Token::Number, "2", 6..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn custom_identifier_class() {
+        assert_lex(
+            "in$ $in in $a_$",
+            &[
+                (Js::Error, "i", 0..1),
+                (Js::Error, "n", 1..2),
+                (Js::Error, "$", 2..3),
+                (Js::Var, "$in", 4..7),
+                (Js::In, "in", 8..10),
+                (Js::Var, "$a_$", 11..15),
+            ],
+        );
+    }
+
+    #[test]
+    fn subpatterns_in_classes() {
+        assert_lex(
+            "if2 ifx do2 dox",
+            &[
+                (Sub::If, "if", 0..2),
+                (Sub::Number, "2", 2..3),
+                (Sub::Error, "i", 4..5),
+                (Sub::Error, "f", 5..6),
+                (Sub::X, "x", 6..7),
+                (Sub::Error, "d", 8..9),
+                (Sub::Error, "o", 9..10),
+                (Sub::Number, "2", 10..11),
+                (Sub::Do, "do", 12..14),
+                (Sub::X, "x", 14..15),
+            ],
+        );
+    }
+}
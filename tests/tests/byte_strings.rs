@@ -0,0 +1,56 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+/// Byte string definitions that only match valid UTF-8 don't restrict the sources.
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(utf8)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token(b"let")]
+    Let,
+
+    #[regex(br"[0-9]+")]
+    Number,
+
+    #[regex("[a-zé]+")]
+    Word,
+}
+
+mod byte_strings {
+    use super::*;
+
+    #[test]
+    fn str_source() {
+        assert_lex(
+            "let café 42",
+            &[
+                (Token::Let, "let", 0..3),
+                (Token::Word, "café", 4..9),
+                (Token::Number, "42", 10..12),
+            ],
+        );
+    }
+
+    #[test]
+    fn byte_source() {
+        assert_lex(
+            &b"let 42 \xFF"[..],
+            &[
+                (Token::Let, &b"let"[..], 0..3),
+                (Token::Number, &b"42"[..], 4..6),
+                (Token::Error, &b"\xFF"[..], 7..8),
+            ],
+        );
+    }
+
+    #[test]
+    fn from_exact() {
+        assert_eq!(Token::from_exact("let"), Some(Token::Let));
+    }
+}
@@ -0,0 +1,240 @@
+use logos::{Filter, Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+/// Consume everything up to, but excluding, the next line break.
+fn rest_of_line<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) {
+    let len = lex
+        .remainder()
+        .as_bytes()
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .unwrap_or_else(|| lex.remainder().as_bytes().len());
+
+    lex.bump(len);
+}
+
+fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> u64 {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .fold(0, |acc, byte| acc * 10 + (byte - b'0') as u64)
+}
+
+fn boolean<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> bool {
+    lex.slice().as_bytes() == b"true"
+}
+
+fn switch<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> bool {
+    matches!(lex.slice().as_bytes(), b"on" | b"yes")
+}
+
+fn byte<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<u8, &'static str> {
+    lex.slice().as_bytes()[2..]
+        .iter()
+        .map(|byte| match byte {
+            b'0'..=b'9' => byte - b'0',
+            _ => byte - b'a' + 10,
+        })
+        .try_fold(0u8, |acc, digit| {
+            acc.checked_mul(16)
+                .map(|acc| acc + digit)
+                .ok_or("byte literal too large")
+        })
+}
+
+/// Tell known directives apart from other `@` prefixed words.
+fn directive<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Token {
+    match lex.slice().as_bytes() {
+        b"@include" => Token::Include,
+        _ => Token::Directive,
+    }
+}
+
+/// Only produce tokens for numbers that fit in a `u8`, skip anything larger.
+fn small<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Filter<u8> {
+    match lex.slice().as_bytes()[1..]
+        .iter()
+        .try_fold(0u8, |acc, byte| acc.checked_mul(10)?.checked_add(byte - b'0'))
+    {
+        Some(value) => Filter::Emit(value),
+        None => Filter::Skip,
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[token("#", callback = "rest_of_line")]
+    Comment,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(u64),
+
+    #[token = "true"]
+    #[token = "false"]
+    #[callback = "boolean"]
+    Bool(bool),
+
+    #[token("on", "off", "yes", "no", callback = "switch")]
+    Switch(bool),
+
+    #[regex("0x[0-9a-f]+", callback = "byte")]
+    Byte(u8),
+
+    #[regex("~[0-9]+", callback = "small")]
+    Small(u8),
+
+    #[regex("@[a-z]+", callback = "directive")]
+    Directive,
+
+    Include,
+}
+
+mod callbacks {
+    use super::*;
+    use tests::assert_lex;
+
+    #[test]
+    fn bump_in_callback() {
+        assert_lex(
+            "foo # anything goes: ĄĘ 42!\nbar #",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Comment, "# anything goes: ĄĘ 42!", 4..29),
+                (Token::Word, "bar", 30..33),
+                (Token::Comment, "#", 34..35),
+            ],
+        );
+    }
+
+    #[test]
+    fn bump_outside_callback() {
+        let mut lex = Token::lexer("foo 42 bar");
+
+        assert_eq!(lex.token, Token::Word);
+
+        lex.bump(3);
+
+        assert_eq!(lex.slice(), "foo 42");
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Word);
+        assert_eq!(lex.slice(), "bar");
+        assert_eq!(lex.range(), 7..10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer bump")]
+    fn bump_out_of_bounds() {
+        let mut lex = Token::lexer("foo");
+
+        lex.bump(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer bump")]
+    fn bump_into_code_point() {
+        let mut lex = Token::lexer("foo ĄĘ");
+
+        lex.bump(2);
+    }
+
+    #[test]
+    fn values() {
+        assert_lex(
+            "42 true 9001 false",
+            &[
+                (Token::Number(42), "42", 0..2),
+                (Token::Bool(true), "true", 3..7),
+                (Token::Number(9001), "9001", 8..12),
+                (Token::Bool(false), "false", 13..18),
+            ],
+        );
+    }
+
+    #[test]
+    fn shared_callback() {
+        assert_lex(
+            "on no yes onward off",
+            &[
+                (Token::Switch(true), "on", 0..2),
+                (Token::Switch(false), "no", 3..5),
+                (Token::Switch(true), "yes", 6..9),
+                (Token::Word, "onward", 10..16),
+                (Token::Switch(false), "off", 17..20),
+            ],
+        );
+    }
+
+    #[test]
+    fn result_values() {
+        assert_lex(
+            "0x0 0xff 0x100 0x2a",
+            &[
+                (Token::Byte(0), "0x0", 0..3),
+                (Token::Byte(255), "0xff", 4..8),
+                (Token::Error, "0x100", 9..14),
+                (Token::Byte(42), "0x2a", 15..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn filter_values() {
+        assert_lex(
+            "~42 ~256 ~255 ~1000",
+            &[
+                (Token::Small(42), "~42", 0..3),
+                (Token::Small(255), "~255", 9..13),
+            ],
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Token::Number(42).name(), "Number");
+        assert_eq!(Token::Bool(true).name(), "Bool");
+    }
+
+    #[test]
+    fn variants_without_fields() {
+        assert_eq!(
+            Token::variants(),
+            &[Token::End, Token::Error, Token::Word, Token::Comment, Token::Directive, Token::Include],
+        );
+    }
+
+    #[test]
+    fn callback_returning_token() {
+        assert_lex(
+            "@include @define include",
+            &[
+                (Token::Include, "@include", 0..8),
+                (Token::Directive, "@define", 9..16),
+                (Token::Word, "include", 17..24),
+            ],
+        );
+    }
+
+    #[test]
+    fn from_exact() {
+        assert_eq!(Token::from_exact("1234"), Some(Token::Number(1234)));
+        assert_eq!(Token::from_exact("true"), Some(Token::Bool(true)));
+        assert_eq!(Token::from_exact("@include"), Some(Token::Include));
+        assert_eq!(Token::from_exact(" foo"), None);
+        assert_eq!(Token::from_exact("foo "), None);
+        assert_eq!(Token::from_exact("foo bar"), None);
+        assert_eq!(Token::from_exact("0xfff"), None);
+        assert_eq!(Token::from_exact(""), None);
+    }
+}
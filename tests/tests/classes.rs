@@ -0,0 +1,91 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Ascii {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = r"\d+"]
+    Number,
+
+    #[regex = r"[\w&&\D]\w*"]
+    Word,
+
+    #[regex = r"#[^\d\s]+"]
+    Tag,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(unicode)]
+enum Unicode {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r"\d+", priority = 2)]
+    Number,
+
+    #[regex = r"\w+"]
+    Word,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Mixed {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r"\w+", unicode)]
+    Word,
+}
+
+mod classes {
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        assert_lex(
+            "foo_1 42\t#tag!",
+            &[
+                (Ascii::Word, "foo_1", 0..5),
+                (Ascii::Number, "42", 6..8),
+                (Ascii::Tag, "#tag!", 9..14),
+            ],
+        );
+
+        assert_eq!(Ascii::lexer("żółw").token, Ascii::Error);
+        assert_eq!(Ascii::lexer("٤٢").token, Ascii::Error);
+    }
+
+    #[test]
+    fn unicode() {
+        assert_lex(
+            "żółw ٤٢",
+            &[
+                (Unicode::Word, "żółw", 0..7),
+                (Unicode::Number, "٤٢", 8..12),
+            ],
+        );
+    }
+
+    #[test]
+    fn unicode_per_regex() {
+        assert_lex(
+            "żółw ąę",
+            &[
+                (Mixed::Word, "żółw", 0..7),
+                (Mixed::Word, "ąę", 8..12),
+            ],
+        );
+    }
+}
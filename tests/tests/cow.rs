@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+}
+
+fn normalize(input: &str) -> Cow<'_, str> {
+    match input.contains('\t') {
+        true => Cow::Owned(input.replace('\t', " ")),
+        false => Cow::Borrowed(input),
+    }
+}
+
+mod cow {
+    use super::*;
+
+    #[test]
+    fn borrowed() {
+        let source = normalize("foo bar");
+
+        assert!(matches!(source, Cow::Borrowed(_)));
+        assert_lex(
+            &source,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "bar", 4..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn owned() {
+        let source = normalize("foo\tbar");
+
+        assert!(matches!(source, Cow::Owned(_)));
+        assert_lex(
+            &source,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "bar", 4..7),
+            ],
+        );
+
+        let lex = Token::lexer(&source);
+
+        assert_eq!(lex.remainder(), " bar");
+    }
+}
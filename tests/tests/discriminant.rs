@@ -0,0 +1,88 @@
+use logos::{lookup, Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> u64 {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .fold(0, |acc, byte| acc * 10 + (byte - b'0') as u64)
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(u64),
+
+    #[token("+")]
+    Plus,
+
+    #[token("-")]
+    Minus,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Explicit {
+    #[end]
+    End = 2,
+
+    #[error]
+    Error = 0,
+
+    #[token("a")]
+    A = 1,
+}
+
+static NAMES: [&str; Token::SIZE] = lookup! {
+    Token::Plus => "plus",
+    Token::Minus => "minus",
+    _ => "other",
+};
+
+const NUMBER: usize = Token::Number(0).discriminant();
+
+mod discriminant {
+    use super::*;
+
+    #[test]
+    fn ignores_values() {
+        assert_eq!(Token::End.discriminant(), 0);
+        assert_eq!(Token::Error.discriminant(), 1);
+        assert_eq!(Token::Number(42).discriminant(), 2);
+        assert_eq!(Token::Number(9001).discriminant(), NUMBER);
+        assert_eq!(Token::Plus.discriminant(), 3);
+        assert_eq!(Token::Minus.discriminant(), 4);
+    }
+
+    #[test]
+    fn matches_cast() {
+        assert_eq!(Explicit::End.discriminant(), Explicit::End as usize);
+        assert_eq!(Explicit::Error.discriminant(), Explicit::Error as usize);
+        assert_eq!(Explicit::A.discriminant(), Explicit::A as usize);
+    }
+
+    #[test]
+    fn inherent_methods() {
+        assert_eq!(logos::Logos::discriminant(&Token::Plus), Token::Plus.discriminant());
+        assert_eq!(logos::Logos::priority(&Token::Plus), Token::Plus.priority());
+    }
+
+    #[test]
+    fn lookup_table() {
+        let mut lex = Token::lexer("1 + 2");
+        let mut names = Vec::new();
+
+        while lex.token != Token::End {
+            names.push(NAMES[lex.token.discriminant()]);
+            lex.advance();
+        }
+
+        assert_eq!(names, &["other", "plus", "other"]);
+    }
+}
@@ -0,0 +1,98 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(dispatch = "table")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-zA-Z_][a-zA-Z0-9_]*"]
+    Ident,
+
+    #[regex = "[0-9]+"]
+    Number,
+
+    #[token = "fn"]
+    Fn,
+
+    #[token = "("]
+    ParenOpen,
+
+    #[token = ")"]
+    ParenClose,
+
+    #[token = "{"]
+    BraceOpen,
+
+    #[token = "}"]
+    BraceClose,
+
+    #[token = ","]
+    Comma,
+
+    #[token = ";"]
+    Semicolon,
+
+    #[token = "+"]
+    Plus,
+
+    #[token = "+="]
+    PlusAssign,
+
+    #[token = "="]
+    Assign,
+
+    #[token = "=="]
+    Equals,
+}
+
+mod dispatch {
+    use super::*;
+
+    #[test]
+    fn table() {
+        assert_lex(
+            "fn add(a, b) { a += b; a == 42 }",
+            &[
+                (Token::Fn, "fn", 0..2),
+                (Token::Ident, "add", 3..6),
+                (Token::ParenOpen, "(", 6..7),
+                (Token::Ident, "a", 7..8),
+                (Token::Comma, ",", 8..9),
+                (Token::Ident, "b", 10..11),
+                (Token::ParenClose, ")", 11..12),
+                (Token::BraceOpen, "{", 13..14),
+                (Token::Ident, "a", 15..16),
+                (Token::PlusAssign, "+=", 17..19),
+                (Token::Ident, "b", 20..21),
+                (Token::Semicolon, ";", 21..22),
+                (Token::Ident, "a", 23..24),
+                (Token::Equals, "==", 25..27),
+                (Token::Number, "42", 28..30),
+                (Token::BraceClose, "}", 31..32),
+            ],
+        );
+    }
+
+    #[test]
+    fn table_miss() {
+        assert_lex(
+            "fnord @ ==+",
+            &[
+                (Token::Ident, "fnord", 0..5),
+                (Token::Error, "@", 6..7),
+                (Token::Equals, "==", 8..10),
+                (Token::Plus, "+", 10..11),
+            ],
+        );
+
+        let lex = Token::lexer("");
+
+        assert_eq!(lex.token, Token::End);
+    }
+}
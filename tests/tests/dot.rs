@@ -0,0 +1,53 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "#.*"]
+    Comment,
+
+    #[regex = "'.'"]
+    Char,
+
+    #[regex = "(?s)`.`"]
+    AnyChar,
+}
+
+mod dot {
+    use super::*;
+
+    #[test]
+    fn any_char_but_newline() {
+        assert_lex(
+            "'a' 'ź' '🦀' # comment 'x'\n'a'",
+            &[
+                (Token::Char, "'a'", 0..3),
+                (Token::Char, "'ź'", 4..8),
+                (Token::Char, "'🦀'", 9..15),
+                (Token::Comment, "# comment 'x'", 16..29),
+                (Token::Char, "'a'", 30..33),
+            ],
+        );
+    }
+
+    #[test]
+    fn newline() {
+        assert_eq!(Token::lexer("'\n'").token, Token::Error);
+
+        assert_lex(
+            "`\n` `ź`",
+            &[
+                (Token::AnyChar, "`\n`", 0..3),
+                (Token::AnyChar, "`ź`", 4..8),
+            ],
+        );
+    }
+}
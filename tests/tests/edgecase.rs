@@ -68,6 +68,124 @@ mod numbers {
     }
 }
 
+mod subpatterns {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(subpattern(digits = r"[0-9][0-9_]*"))]
+    #[logos(subpattern(exp = r"[eE][+-]?(?&digits)"))]
+    enum Token {
+        #[end]
+        End,
+        #[error]
+        Error,
+        #[regex = r"(?&digits)"]
+        LiteralUnsignedNumber,
+        #[regex = r"(?&digits)\.(?&digits)[TGMKkmupfa]"]
+        LiteralRealNumberDotScaleChar,
+        #[regex = r"(?&digits)\.(?&digits)(?&exp)"]
+        LiteralRealNumberDotExp,
+        #[regex = r"(?&digits)[TGMKkmupfa]"]
+        LiteralRealNumberScaleChar,
+        #[regex = r"(?&digits)(?&exp)"]
+        LiteralRealNumberExp,
+        #[regex = r"(?&digits)\.(?&digits)"]
+        LiteralRealNumberDot,
+        #[regex = r"\(?&"]
+        NotASubpattern,
+    }
+
+    #[test]
+    fn numbers() {
+        assert_lex(
+            "42.42 42 777777K 90e+8 42.42m 77.77e-29 &",
+            &[
+                (Token::LiteralRealNumberDot, "42.42", 0..5),
+                (Token::LiteralUnsignedNumber, "42", 6..8),
+                (Token::LiteralRealNumberScaleChar, "777777K", 9..16),
+                (Token::LiteralRealNumberExp, "90e+8", 17..22),
+                (Token::LiteralRealNumberDotScaleChar, "42.42m", 23..29),
+                (Token::LiteralRealNumberDotExp, "77.77e-29", 30..39),
+                (Token::NotASubpattern, "&", 40..41),
+            ]
+        )
+    }
+}
+
+mod counted_repetition {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    enum Token {
+        #[end]
+        End,
+        #[error]
+        Error,
+        #[regex = r"\\u[0-9a-fA-F]{4}"]
+        UnicodeEscape,
+        #[regex = "[0-9]{3}"]
+        Opcode,
+        #[regex = "x{2,4}"]
+        Xs,
+        #[regex = "y{2,}"]
+        Ys,
+    }
+
+    #[test]
+    fn counted_repetition() {
+        assert_lex(
+            r"\u00e9 \u12 042 xxx xxxxx yy yyyyyy",
+            &[
+                (Token::UnicodeEscape, r"\u00e9", 0..6),
+                (Token::Error, r"\u12", 7..11),
+                (Token::Opcode, "042", 12..15),
+                (Token::Xs, "xxx", 16..19),
+                (Token::Xs, "xxxx", 20..24),
+                (Token::Error, "x", 24..25),
+                (Token::Ys, "yy", 26..28),
+                (Token::Ys, "yyyyyy", 29..35),
+            ]
+        )
+    }
+}
+
+mod long_literals {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    enum Token {
+        #[end]
+        End,
+        #[error]
+        Error,
+        #[token = "supercalifragilistic"]
+        Twenty,
+        #[token = "supercalifragilisticexpialidocious"]
+        ThirtyFour,
+        #[token = "pneumonoultramicroscopicsilicovolcanoconiosis"]
+        FortyFive,
+        #[regex = "[a-z]+"]
+        Ident,
+    }
+
+    #[test]
+    fn long_literals() {
+        assert_lex(
+            "supercalifragilistic supercalifragilisticexpialidocious \
+             pneumonoultramicroscopicsilicovolcanoconiosis \
+             supercalifragilisticexpialidociou \
+             pneumonoultramicroscopicsilicovolcanoconiosisx",
+            &[
+                (Token::Twenty, "supercalifragilistic", 0..20),
+                (Token::ThirtyFour, "supercalifragilisticexpialidocious", 21..55),
+                (Token::FortyFive, "pneumonoultramicroscopicsilicovolcanoconiosis", 56..101),
+                (Token::Ident, "supercalifragilisticexpialidociou", 102..135),
+                (Token::Ident, "pneumonoultramicroscopicsilicovolcanoconiosisx", 136..182),
+            ]
+        )
+    }
+}
+
 mod benches {
     use super::*;
 
@@ -207,4 +325,32 @@ mod benches {
             ]
         )
     }
-}
\ No newline at end of file
+}
+
+mod implicit_end_and_error {
+    use super::*;
+    use logos::Logos as _;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    enum Token {
+        #[regex = "[a-z]+"]
+        Word,
+        Error,
+        End,
+    }
+
+    #[test]
+    fn variants_by_name() {
+        assert_eq!(Token::ERROR, Token::Error);
+        assert_eq!(Token::END, Token::End);
+
+        assert_lex(
+            "foo ! bar",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "!", 4..5),
+                (Token::Word, "bar", 6..9),
+            ],
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use logos::{Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+", callback = "word")]
+    Word,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(u8),
+
+    #[token("<>", callback = "split")]
+    Pair,
+
+    #[token("<")]
+    Less,
+
+    #[token(">")]
+    Greater,
+}
+
+fn word<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) -> Token {
+    match lex.slice().as_bytes() {
+        b"bad" => Token::Error,
+        _ => Token::Word,
+    }
+}
+
+fn number<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) -> Result<u8, ()> {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .try_fold(0u8, |acc, byte| acc.checked_mul(10)?.checked_add(byte - b'0'))
+        .ok_or(())
+}
+
+fn split<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) -> Token {
+    let end = lex.range().end;
+
+    lex.emit_next(Token::Error, end - 1..end);
+
+    Token::Less
+}
+
+fn count(source: &str) -> usize {
+    let mut lex = Token::lexer(source);
+
+    while lex.token != Token::End {
+        lex.advance();
+    }
+
+    lex.error_count()
+}
+
+mod error_count {
+    use super::*;
+
+    #[test]
+    fn clean_input() {
+        assert_eq!(count(""), 0);
+        assert_eq!(count("foo 42 < >"), 0);
+    }
+
+    #[test]
+    fn unmatched_input() {
+        assert_eq!(count("@ foo # $"), 3);
+    }
+
+    #[test]
+    fn includes_current_token() {
+        let mut lex = Token::lexer("@ foo");
+
+        assert_eq!(lex.token, Token::Error);
+        assert_eq!(lex.error_count(), 1);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Word);
+        assert_eq!(lex.error_count(), 1);
+    }
+
+    #[test]
+    fn callbacks() {
+        assert_eq!(count("999 bad 255"), 2);
+    }
+
+    #[test]
+    fn queued_tokens() {
+        assert_eq!(count("<> <>"), 2);
+    }
+
+    #[test]
+    fn recovered_errors() {
+        let mut lex = Token::lexer("@#$ foo");
+
+        lex.recover();
+
+        assert_eq!(lex.slice(), "@#$");
+        assert_eq!(lex.error_count(), 1);
+    }
+
+    #[test]
+    fn reset() {
+        let mut lex = Token::lexer("@");
+
+        assert_eq!(lex.error_count(), 1);
+
+        lex.reset("foo");
+
+        assert_eq!(lex.error_count(), 0);
+    }
+}
@@ -0,0 +1,282 @@
+use std::ops::Range;
+
+use logos::{Extras, Lexer, Logos as _, Slice, Source, Span};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex = "\"[^\"]*\""]
+    String,
+
+    #[regex = "0x[0-9a-f]+"]
+    Hex,
+
+    #[token = "=>"]
+    Arrow,
+
+    #[token = "::<>"]
+    Turbofish,
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Ranged {
+    #[end]
+    End,
+
+    #[error]
+    Error(Range<usize>),
+
+    #[regex("[0-9]+", callback = "byte")]
+    Byte(u8),
+}
+
+fn byte<'s, S: Source<'s>>(lex: &mut Lexer<Ranged, S>) -> Result<u8, ()> {
+    lex.slice().as_bytes().iter().try_fold(0u8, |acc, digit| {
+        acc.checked_mul(10).and_then(|acc| acc.checked_add(digit - b'0')).ok_or(())
+    })
+}
+
+#[derive(Clone)]
+struct Limit {
+    max: u8,
+}
+
+impl Extras for Limit {}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[extras = "Limit"]
+enum Limited {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[0-9]", callback = "digit")]
+    Digit,
+}
+
+fn digit<'s, S: Source<'s>>(lex: &mut Lexer<Limited, S>) -> Result<(), ()> {
+    match lex.slice().as_bytes()[0] - b'0' <= lex.extras.max {
+        true => Ok(()),
+        false => Err(()),
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Spanned {
+    #[end]
+    End,
+
+    #[error]
+    Error(Span),
+
+    #[regex = "[a-z]+"]
+    Word,
+}
+
+mod errors {
+    use super::*;
+
+    #[test]
+    fn single_byte() {
+        assert_lex(
+            "foo = bar",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "=", 4..5),
+                (Token::Word, "bar", 6..9),
+            ],
+        );
+    }
+
+    #[test]
+    fn whole_character() {
+        assert_lex(
+            "zażółć",
+            &[
+                (Token::Word, "za", 0..2),
+                (Token::Error, "ż", 2..4),
+                (Token::Error, "ó", 4..6),
+                (Token::Error, "ł", 6..8),
+                (Token::Error, "ć", 8..10),
+            ],
+        );
+    }
+
+    #[test]
+    fn partial_literal() {
+        assert_lex(
+            "::<x",
+            &[
+                (Token::Error, ":", 0..1),
+                (Token::Error, ":", 1..2),
+                (Token::Error, "<", 2..3),
+                (Token::Word, "x", 3..4),
+            ],
+        );
+    }
+
+    #[test]
+    fn stuck_prefix() {
+        assert_lex(
+            "0xz 0xff \"unterminated",
+            &[
+                (Token::Error, "0x", 0..2),
+                (Token::Word, "z", 2..3),
+                (Token::Hex, "0xff", 4..8),
+                (Token::Error, "\"unterminated", 9..22),
+            ],
+        );
+    }
+
+    #[test]
+    fn multiple_errors() {
+        assert_lex(
+            "a=>=b",
+            &[
+                (Token::Word, "a", 0..1),
+                (Token::Arrow, "=>", 1..3),
+                (Token::Error, "=", 3..4),
+                (Token::Word, "b", 4..5),
+            ],
+        );
+    }
+
+    #[test]
+    fn errors_make_progress() {
+        let source = "foo 0x€ ::<> ż=>=0xg ::";
+        let mut lex = Token::lexer(source);
+        let mut end = 0;
+        let mut errors = Vec::new();
+
+        while lex.token != Token::End {
+            let range = lex.range();
+
+            assert!(range.start >= end, "{:?} overlaps the previous token", range);
+            assert!(!range.is_empty(), "empty range at {}", range.start);
+
+            if lex.token == Token::Error {
+                errors.push(range.clone());
+            }
+
+            end = range.end;
+            lex.advance();
+        }
+
+        assert_eq!(lex.range(), source.len()..source.len());
+        assert_eq!(errors, &[4..6, 6..9, 15..17, 19..20, 20..22, 24..25, 25..26]);
+    }
+
+    fn recovered(source: &str) -> Vec<(Token, &str, std::ops::Range<usize>)> {
+        let mut lex = Token::lexer(source);
+        let mut tokens = Vec::new();
+
+        while lex.token != Token::End {
+            lex.recover();
+            tokens.push((lex.token, lex.slice(), lex.range()));
+            lex.advance();
+        }
+
+        tokens
+    }
+
+    #[test]
+    fn recover_run() {
+        assert_eq!(
+            recovered("foo @#% bar"),
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "@#%", 4..7),
+                (Token::Word, "bar", 8..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn recover_stops_at_trivia() {
+        assert_eq!(
+            recovered("@@ @@"),
+            &[
+                (Token::Error, "@@", 0..2),
+                (Token::Error, "@@", 3..5),
+            ],
+        );
+    }
+
+    #[test]
+    fn recover_keeps_token_after_error() {
+        assert_eq!(
+            recovered("ż€=>x"),
+            &[
+                (Token::Error, "ż€", 0..5),
+                (Token::Arrow, "=>", 5..7),
+                (Token::Word, "x", 7..8),
+            ],
+        );
+    }
+
+    #[test]
+    fn recover_at_end() {
+        assert_eq!(
+            recovered("foo=@"),
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "=@", 3..5),
+            ],
+        );
+    }
+
+    #[test]
+    fn recover_with_extras() {
+        let mut lex = Limited::lexer_with_extras("9523", Limit { max: 3 });
+
+        lex.recover();
+
+        assert_eq!(lex.token, Limited::Error);
+        assert_eq!(lex.range(), 0..2);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Limited::Digit);
+        assert_eq!(lex.range(), 2..3);
+    }
+
+    #[test]
+    fn error_holds_range() {
+        assert_lex(
+            "12 @ 256 7",
+            &[
+                (Ranged::Byte(12), "12", 0..2),
+                (Ranged::Error(3..4), "@", 3..4),
+                (Ranged::Error(5..8), "256", 5..8),
+                (Ranged::Byte(7), "7", 9..10),
+            ],
+        );
+        assert_eq!(Ranged::ERROR, Ranged::Error(0..0));
+    }
+
+    #[test]
+    fn error_holds_recovered_span() {
+        let mut lex = Spanned::lexer("ab @#% cd");
+
+        lex.advance();
+
+        assert_eq!(lex.token, Spanned::Error(Span::new(3, 4)));
+
+        lex.recover();
+
+        assert_eq!(lex.token, Spanned::Error(Span::new(3, 6)));
+        assert_eq!(lex.slice(), "@#%");
+    }
+}
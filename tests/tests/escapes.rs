@@ -0,0 +1,67 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = " ")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r"[\0-\x08\x0E-\x1F]+")]
+    Control,
+
+    #[regex(r"caf\xE9")]
+    Cafe,
+
+    #[regex(r"\x{1F980}")]
+    Crab,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia())]
+enum Binary {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(br"\0")]
+    Nul,
+
+    #[regex(br"\x04[\x80-\xFF]+")]
+    High,
+}
+
+mod escapes {
+    use super::*;
+
+    #[test]
+    fn utf8_escapes() {
+        assert_lex(
+            "\0\x01\x1F café 🦀 \x0B",
+            &[
+                (Token::Control, "\0\x01\x1F", 0..3),
+                (Token::Cafe, "café", 4..9),
+                (Token::Crab, "🦀", 10..14),
+                (Token::Error, "\x0B", 15..16),
+            ],
+        );
+    }
+
+    #[test]
+    fn byte_escapes() {
+        assert_lex(
+            &[0x00, 0x04, 0x80, 0xFF, 0xC3, 0x04, 0x7F][..],
+            &[
+                (Binary::Nul, &[0x00][..], 0..1),
+                (Binary::High, &[0x04, 0x80, 0xFF, 0xC3][..], 1..5),
+                (Binary::Error, &[0x04][..], 5..6),
+                (Binary::Error, &[0x7F][..], 6..7),
+            ],
+        );
+    }
+}
@@ -0,0 +1,101 @@
+#![forbid(unsafe_code)]
+
+use logos::{Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("^#![^\n]*")]
+    Shebang,
+
+    #[regex("[a-z]+", keywords = "KEYWORDS")]
+    Ident,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(u64),
+
+    #[regex("[0-9]+(?=px)")]
+    Pixels,
+
+    #[regex("[A-Z]", class = "is_upper_or_digit")]
+    Constant,
+
+    #[token("/*", until = "*/")]
+    Comment,
+
+    #[regex(";$")]
+    FinalSemicolon,
+
+    #[token(";")]
+    Semicolon,
+
+    Let,
+}
+
+static KEYWORDS: &[(&str, Token)] = &[("let", Token::Let)];
+
+fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<u64, ()> {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .try_fold(0u64, |n, &digit| n.checked_mul(10)?.checked_add(u64::from(digit - b'0')))
+        .ok_or(())
+}
+
+fn is_upper_or_digit(byte: u8) -> bool {
+    byte.is_ascii_uppercase() || byte.is_ascii_digit()
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(dispatch = "table", optimize = "size")]
+enum Small {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token("=>")]
+    Arrow,
+}
+
+mod forbid_unsafe {
+    use super::*;
+
+    #[test]
+    fn lexes_without_unsafe() {
+        assert_lex(
+            "#!run\nlet x /* y */ MAX2 42 8px;",
+            &[
+                (Token::Shebang, "#!run", 0..5),
+                (Token::Let, "let", 6..9),
+                (Token::Ident, "x", 10..11),
+                (Token::Comment, "/* y */", 12..19),
+                (Token::Constant, "MAX2", 20..24),
+                (Token::Number(42), "42", 25..27),
+                (Token::Pixels, "8", 28..29),
+                (Token::Ident, "px", 29..31),
+                (Token::FinalSemicolon, ";", 31..32),
+            ],
+        );
+    }
+
+    #[test]
+    fn table_dispatch_without_unsafe() {
+        assert_eq!(
+            Small::lexer("foo=>bar").into_vec(),
+            &[(Small::Word, 0..3), (Small::Arrow, 3..5), (Small::Word, 5..8)],
+        );
+    }
+}
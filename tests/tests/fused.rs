@@ -0,0 +1,63 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    #[error]
+    Stop,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+mod fused {
+    use super::*;
+
+    #[test]
+    fn lexes_until_end() {
+        assert_lex(
+            "foo 42 bar",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Number, "42", 4..6),
+                (Token::Word, "bar", 7..10),
+            ],
+        );
+    }
+
+    #[test]
+    fn stops_at_error() {
+        let mut lex = Token::lexer("foo @ bar");
+
+        assert_eq!(lex.token, Token::Word);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Stop);
+        assert_eq!(lex.range(), 4..5);
+        assert!(!lex.is_at_end());
+
+        assert_eq!(Token::lexer("foo @ bar").into_vec(), &[(Token::Word, 0..3)]);
+    }
+
+    #[test]
+    fn recover_at_end() {
+        let mut lex = Token::lexer("foo @#");
+
+        lex.advance();
+        lex.recover();
+
+        assert_eq!(lex.token, Token::Stop);
+        assert_eq!(lex.range(), 4..6);
+
+        lex.advance();
+
+        assert!(lex.is_at_end());
+    }
+}
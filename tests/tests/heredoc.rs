@@ -0,0 +1,106 @@
+use logos::{Filter, Lexer, Slice, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+/// Consume a here-doc body, up to and including the line holding only the
+/// terminator given after `<<`, producing the number of lines in the body.
+fn heredoc<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<usize, ()> {
+    let terminator = lex.slice().as_bytes()[2..].to_vec();
+    let rest = lex.remainder();
+    let mut consumed = 0;
+
+    for (count, line) in rest.as_bytes().split(|&byte| byte == b'\n').enumerate() {
+        if count > 0 && line == &terminator[..] {
+            lex.bump(consumed + line.len());
+
+            return Ok(count - 1);
+        }
+
+        consumed += line.len() + 1;
+    }
+
+    Err(())
+}
+
+/// Skip a nested block comment, which a regular expression can't match.
+fn block_comment<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Filter<()> {
+    let rest = lex.remainder();
+    let bytes = rest.as_bytes();
+    let mut depth = 1;
+    let mut consumed = 0;
+
+    while depth > 0 && consumed < bytes.len() {
+        match &bytes[consumed..bytes.len().min(consumed + 2)] {
+            b"/*" => depth += 1,
+            b"*/" => depth -= 1,
+            _ => {
+                consumed += 1;
+                continue;
+            },
+        }
+
+        consumed += 2;
+    }
+
+    lex.bump(consumed);
+
+    Filter::Skip
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("<<[A-Z]+", callback = "heredoc")]
+    Heredoc(usize),
+
+    #[token("/*", callback = "block_comment")]
+    BlockComment,
+
+    #[regex = "[a-z]+"]
+    Word,
+}
+
+mod heredoc {
+    use super::*;
+
+    #[test]
+    fn callback_extends_token() {
+        assert_lex(
+            "cat <<EOF\nfoo\nbar EOF\nEOF\ndone",
+            &[
+                (Token::Word, "cat", 0..3),
+                (Token::Heredoc(2), "<<EOF\nfoo\nbar EOF\nEOF", 4..25),
+                (Token::Word, "done", 26..30),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated_extension() {
+        assert_lex(
+            "cat <<EOF\nfoo",
+            &[
+                (Token::Word, "cat", 0..3),
+                (Token::Error, "<<EOF", 4..9),
+                (Token::Word, "foo", 10..13),
+            ],
+        );
+    }
+
+    #[test]
+    fn skipped_extension() {
+        assert_lex(
+            "foo /* a /* b */ c */ bar /* */",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "bar", 22..25),
+            ],
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("select", ignore(case))]
+    Select,
+
+    #[token("from", ignore(case))]
+    From,
+
+    #[token("Żółw", ignore(case))]
+    Turtle,
+
+    #[token("0x", ignore(case))]
+    HexPrefix,
+
+    #[regex("(?i)ąę")]
+    Unicode,
+
+    #[regex("(?i:0x)[0-9a-f]+")]
+    Hex,
+
+    #[regex("(?i)ins(?-i:ert)")]
+    Insert,
+
+    #[regex = "[a-zA-Z_]+"]
+    Ident,
+
+    #[token = "*"]
+    Star,
+}
+
+mod ignore_case {
+    use super::*;
+
+    #[test]
+    fn keywords() {
+        assert_lex(
+            "SELECT * FROM Select selected frOm_",
+            &[
+                (Token::Select, "SELECT", 0..6),
+                (Token::Star, "*", 7..8),
+                (Token::From, "FROM", 9..13),
+                (Token::Select, "Select", 14..20),
+                (Token::Ident, "selected", 21..29),
+                (Token::Ident, "frOm_", 30..35),
+            ],
+        );
+    }
+
+    #[test]
+    fn ascii_only() {
+        assert_lex(
+            "ŻółW",
+            &[
+                (Token::Turtle, "ŻółW", 0..7),
+            ],
+        );
+
+        let lex = Token::lexer("żółw");
+
+        assert_eq!(lex.token, Token::Error);
+    }
+
+    #[test]
+    fn digits() {
+        assert_lex(
+            "0x 0X",
+            &[
+                (Token::HexPrefix, "0x", 0..2),
+                (Token::HexPrefix, "0X", 3..5),
+            ],
+        );
+    }
+
+    #[test]
+    fn unicode_regex_flag() {
+        assert_lex(
+            "ąę ĄĘ",
+            &[
+                (Token::Unicode, "ąę", 0..4),
+                (Token::Unicode, "ĄĘ", 5..9),
+            ],
+        );
+    }
+
+    #[test]
+    fn scoped_flag() {
+        assert_lex(
+            "0xff 0Xff 0X09",
+            &[
+                (Token::Hex, "0xff", 0..4),
+                (Token::Hex, "0Xff", 5..9),
+                (Token::Hex, "0X09", 10..14),
+            ],
+        );
+
+        assert_lex(
+            "0XFF",
+            &[
+                (Token::HexPrefix, "0X", 0..2),
+                (Token::Ident, "FF", 2..4),
+            ],
+        );
+    }
+
+    #[test]
+    fn scoped_flag_disabled() {
+        assert_lex(
+            "INSert insert",
+            &[
+                (Token::Insert, "INSert", 0..6),
+                (Token::Insert, "insert", 7..13),
+            ],
+        );
+
+        let mut lex = Token::lexer("INSERT");
+
+        assert_eq!(lex.token, Token::Ident);
+        assert_eq!(lex.slice(), "INSERT");
+        lex.advance();
+        assert_eq!(lex.token, Token::End);
+    }
+}
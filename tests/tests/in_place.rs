@@ -0,0 +1,99 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(br#""([^"\\]|\\["\\nt])*""#)]
+    String,
+
+    #[regex(b"[a-z]+")]
+    Word,
+}
+
+/// Unescape all strings in `buf`, compacting it in place, and return the new length.
+/// Only the part of `buf` that has been lexed already is ever written to.
+fn unescape_in_place(buf: &mut [u8]) -> usize {
+    let len = buf.len();
+    let (mut read, mut write) = (0, 0);
+
+    loop {
+        let lex = Token::lexer_in(&buf[..], read..len);
+        let token = lex.token;
+        let range = lex.range();
+
+        // Whatever was skipped before the token is kept as is
+        buf.copy_within(read..range.start, write);
+        write += range.start - read;
+
+        match token {
+            Token::End => return write,
+            Token::String => {
+                let mut escaped = false;
+
+                for i in range.clone() {
+                    let byte = match (escaped, buf[i]) {
+                        (false, b'\\') => {
+                            escaped = true;
+                            continue;
+                        }
+                        (true, b'n') => b'\n',
+                        (true, b't') => b'\t',
+                        (_, byte) => byte,
+                    };
+
+                    escaped = false;
+                    buf[write] = byte;
+                    write += 1;
+                }
+            }
+            _ => {
+                buf.copy_within(range.clone(), write);
+                write += range.len();
+            }
+        }
+
+        read = range.end;
+    }
+}
+
+mod in_place {
+    use super::*;
+
+    fn unescape(source: &[u8]) -> Vec<u8> {
+        let mut buf = source.to_vec();
+        let len = unescape_in_place(&mut buf);
+
+        buf.truncate(len);
+        buf
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        assert_eq!(
+            unescape(br#"say "a\"b" and "c\\d\n" done"#),
+            b"say \"a\"b\" and \"c\\d\n\" done",
+        );
+    }
+
+    #[test]
+    fn keeps_everything_else() {
+        assert_eq!(unescape(br#"  foo "bar"  "#), br#"  foo "bar"  "#);
+        assert_eq!(unescape(br#"foo "unterminated\"#), br#"foo "unterminated\"#);
+        assert_eq!(unescape(b""), b"");
+    }
+
+    #[test]
+    fn resumes_after_writes() {
+        let mut buf = *br#""\t\t\t\t" foo"#;
+        let len = unescape_in_place(&mut buf);
+
+        assert_eq!(&buf[..len], b"\"\t\t\t\t\" foo");
+        assert_eq!(Token::lexer(&buf[..len]).into_vec(), [(Token::String, 0..6), (Token::Word, 7..10)]);
+    }
+}
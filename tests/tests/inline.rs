@@ -0,0 +1,55 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Ident,
+
+    #[token = "{"]
+    #[logos(inline)]
+    BraceOpen,
+
+    #[token = "}"]
+    #[logos(inline)]
+    BraceClose,
+
+    #[token = ":"]
+    #[logos(inline)]
+    Colon,
+
+    #[token = "instanceof"]
+    #[logos(noinline)]
+    InstanceOf,
+
+    #[token = "typeof"]
+    #[logos(noinline)]
+    TypeOf,
+}
+
+mod inline {
+    use super::*;
+
+    #[test]
+    fn hints() {
+        assert_lex(
+            "{a:typeof}instanceof instance typeofs",
+            &[
+                (Token::BraceOpen, "{", 0..1),
+                (Token::Ident, "a", 1..2),
+                (Token::Colon, ":", 2..3),
+                (Token::TypeOf, "typeof", 3..9),
+                (Token::BraceClose, "}", 9..10),
+                (Token::InstanceOf, "instanceof", 10..20),
+                (Token::Ident, "instance", 21..29),
+                (Token::Ident, "typeofs", 30..37),
+            ],
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use logos::{Extras, Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Symbol(u32);
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<Vec<u8>, Symbol>,
+    strings: Vec<Vec<u8>>,
+}
+
+impl Interner {
+    fn intern(&mut self, bytes: &[u8]) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(bytes) {
+            return symbol;
+        }
+
+        let symbol = Symbol(self.strings.len() as u32);
+
+        self.symbols.insert(bytes.to_vec(), symbol);
+        self.strings.push(bytes.to_vec());
+
+        symbol
+    }
+
+    fn resolve(&self, symbol: Symbol) -> &[u8] {
+        &self.strings[symbol.0 as usize]
+    }
+}
+
+#[derive(Default)]
+struct State {
+    interner: Interner,
+}
+
+impl Extras for State {}
+
+fn intern<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Symbol {
+    lex.extras.interner.intern(lex.slice().as_bytes())
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[extras = "State"]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*", callback = "intern")]
+    Identifier(Symbol),
+
+    #[token("=")]
+    Assign,
+
+    #[token(";")]
+    Semicolon,
+}
+
+mod interning {
+    use super::*;
+
+    #[test]
+    fn identifiers_share_symbols() {
+        let mut lex = Token::lexer("foo = bar; bar = foo; baz = foo;");
+        let mut tokens = Vec::new();
+
+        while lex.token != Token::End {
+            tokens.push(lex.token);
+            lex.advance();
+        }
+
+        let (foo, bar, baz) = (Symbol(0), Symbol(1), Symbol(2));
+
+        assert_eq!(
+            tokens,
+            &[
+                Token::Identifier(foo),
+                Token::Assign,
+                Token::Identifier(bar),
+                Token::Semicolon,
+                Token::Identifier(bar),
+                Token::Assign,
+                Token::Identifier(foo),
+                Token::Semicolon,
+                Token::Identifier(baz),
+                Token::Assign,
+                Token::Identifier(foo),
+                Token::Semicolon,
+            ]
+        );
+
+        let interner = &lex.extras.interner;
+
+        assert_eq!(interner.strings.len(), 3);
+        assert_eq!(interner.resolve(foo), b"foo");
+        assert_eq!(interner.resolve(bar), b"bar");
+        assert_eq!(interner.resolve(baz), b"baz");
+    }
+
+    #[test]
+    fn interner_seeded_through_extras() {
+        let mut state = State::default();
+        let keyword = state.interner.intern(b"self");
+
+        let mut lex = Token::lexer_with_extras("other self", state);
+
+        assert_eq!(lex.token, Token::Identifier(Symbol(1)));
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Identifier(keyword));
+    }
+}
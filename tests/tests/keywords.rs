@@ -0,0 +1,101 @@
+use logos::{Lexer, Slice, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*", keywords = "KEYWORDS")]
+    Ident,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[token("=")]
+    Assign,
+
+    Fn,
+    Let,
+    Mut,
+    Return,
+}
+
+static KEYWORDS: &[(&str, Token)] = &[
+    ("fn", Token::Fn),
+    ("let", Token::Let),
+    ("mut", Token::Mut),
+    ("return", Token::Return),
+];
+
+fn shout<'s, S: Source<'s>>(lex: &mut Lexer<Shouting, S>) -> bool {
+    lex.slice().as_bytes().iter().all(|byte| byte.is_ascii_uppercase())
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Shouting {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z]+", callback = "shout", keywords = "SHOUTING")]
+    Word(bool),
+
+    Hello,
+}
+
+static SHOUTING: &[(&str, Shouting)] = &[("HELLO", Shouting::Hello)];
+
+mod keywords {
+    use super::*;
+
+    #[test]
+    fn reclassified() {
+        assert_lex(
+            "let mut x = 10 fn return",
+            &[
+                (Token::Let, "let", 0..3),
+                (Token::Mut, "mut", 4..7),
+                (Token::Ident, "x", 8..9),
+                (Token::Assign, "=", 10..11),
+                (Token::Number, "10", 12..14),
+                (Token::Fn, "fn", 15..17),
+                (Token::Return, "return", 18..24),
+            ],
+        );
+    }
+
+    #[test]
+    fn whole_slice_only() {
+        assert_lex(
+            "letter f fns Let returned",
+            &[
+                (Token::Ident, "letter", 0..6),
+                (Token::Ident, "f", 7..8),
+                (Token::Ident, "fns", 9..12),
+                (Token::Ident, "Let", 13..16),
+                (Token::Ident, "returned", 17..25),
+            ],
+        );
+    }
+
+    #[test]
+    fn callback_runs_for_other_slices() {
+        assert_lex(
+            "HELLO hello WORLD",
+            &[
+                (Shouting::Hello, "HELLO", 0..5),
+                (Shouting::Word(false), "hello", 6..11),
+                (Shouting::Word(true), "WORLD", 12..17),
+            ],
+        );
+    }
+}
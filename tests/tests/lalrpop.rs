@@ -0,0 +1,81 @@
+use lalrpop_util::{lalrpop_mod, ParseError};
+use logos::lalrpop::{LalrpopAdapter, LexicalError};
+use logos::{Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+lalrpop_mod!(#[allow(clippy::all)] arithmetic);
+
+fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> i64 {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .fold(0, |acc, byte| acc * 10 + (byte - b'0') as i64)
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(i64),
+
+    #[token = "+"]
+    Plus,
+
+    #[token = "-"]
+    Minus,
+
+    #[token = "*"]
+    Star,
+
+    #[token = "("]
+    ParenOpen,
+
+    #[token = ")"]
+    ParenClose,
+}
+
+fn parse(source: &str) -> Result<i64, ParseError<usize, Token, LexicalError>> {
+    arithmetic::ExprParser::new().parse(LalrpopAdapter::new(Token::lexer(source)))
+}
+
+mod lalrpop {
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        let tokens: Vec<_> = LalrpopAdapter::new(Token::lexer("1 + ?")).collect();
+
+        assert_eq!(tokens, &[
+            Ok((0, Token::Number(1), 1)),
+            Ok((2, Token::Plus, 3)),
+            Err(LexicalError { span: (4..5).into() }),
+        ]);
+    }
+
+    #[test]
+    fn parse_expression() {
+        assert_eq!(parse("2 * (3 + 4) - 5"), Ok(9));
+        assert_eq!(parse("10 - 2 - 3"), Ok(5));
+    }
+
+    #[test]
+    fn lexical_error() {
+        assert_eq!(parse("2 + ?"), Err(ParseError::User {
+            error: LexicalError { span: (4..5).into() },
+        }));
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(parse("2 + )"), Err(ParseError::UnrecognizedToken {
+            token: (4, Token::ParenClose, 5),
+            expected: vec![r#""(""#.into(), r#""number""#.into()],
+        }));
+        assert!(matches!(parse("(1"), Err(ParseError::UnrecognizedEOF { location: 2, .. })));
+    }
+}
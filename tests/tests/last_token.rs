@@ -0,0 +1,93 @@
+use logos::{Lexer, Logos as _, Source};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[token("-", callback = "minus")]
+    Minus,
+
+    Negative,
+}
+
+/// A `-` following a word is a binary minus, otherwise it negates.
+fn minus<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Token {
+    match lex.last() {
+        Some(Token::Word) | Some(Token::Number) => Token::Minus,
+        _ => Token::Negative,
+    }
+}
+
+mod last_token {
+    use super::*;
+
+    #[test]
+    fn follows_advance() {
+        let mut lex = Token::lexer("foo 42 bar");
+
+        assert_eq!(lex.last(), None);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Number);
+        assert_eq!(lex.last(), Some(&Token::Word));
+
+        lex.advance();
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+        assert_eq!(lex.last(), Some(&Token::Word));
+    }
+
+    #[test]
+    fn context_in_callbacks() {
+        assert_eq!(
+            Token::lexer("-1 - a - -2").into_vec(),
+            &[
+                (Token::Negative, 0..1),
+                (Token::Number, 1..2),
+                (Token::Minus, 3..4),
+                (Token::Word, 5..6),
+                (Token::Minus, 7..8),
+                (Token::Negative, 9..10),
+                (Token::Number, 10..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn reset_forgets() {
+        let mut lex = Token::lexer("foo bar");
+
+        lex.advance();
+        lex.reset("baz");
+
+        assert_eq!(lex.token, Token::Word);
+        assert_eq!(lex.last(), None);
+    }
+
+    #[test]
+    fn context_when_peeking() {
+        let mut lex = Token::lexer("a - - b").peekable(2);
+
+        assert_eq!(lex.peek_nth(0).0, &Token::Minus);
+        assert_eq!(lex.peek_nth(1).0, &Token::Negative);
+
+        lex.advance();
+
+        assert_eq!(lex.token(), &Token::Minus);
+        assert_eq!(lex.peek_nth(1).0, &Token::Word);
+    }
+}
@@ -0,0 +1,109 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r"/\*(?s:.)*?\*/")]
+    BlockComment,
+
+    #[regex("<!--.*?-->")]
+    HtmlComment,
+
+    #[regex(r#""""[a-z ]+?""""#)]
+    DocString,
+
+    #[regex("a??ab")]
+    Ab,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token = "*"]
+    Star,
+
+    #[token = "/"]
+    Slash,
+}
+
+mod lazy {
+    use super::*;
+
+    #[test]
+    fn block_comments() {
+        assert_lex(
+            "/* foo */ bar /* ** / */ */ /**/ /***/",
+            &[
+                (Token::BlockComment, "/* foo */", 0..9),
+                (Token::Word, "bar", 10..13),
+                (Token::BlockComment, "/* ** / */", 14..24),
+                (Token::Star, "*", 25..26),
+                (Token::Slash, "/", 26..27),
+                (Token::BlockComment, "/**/", 28..32),
+                (Token::BlockComment, "/***/", 33..38),
+            ],
+        );
+    }
+
+    #[test]
+    fn multiline_and_unicode() {
+        assert_lex(
+            "/* zażółć\n gęślą */",
+            &[
+                (Token::BlockComment, "/* zażółć\n gęślą */", 0..26),
+            ],
+        );
+    }
+
+    #[test]
+    fn overlapping_terminator() {
+        assert_lex(
+            "<!-- a -- b --> <!----> <!-- ---->",
+            &[
+                (Token::HtmlComment, "<!-- a -- b -->", 0..15),
+                (Token::HtmlComment, "<!---->", 16..23),
+                (Token::HtmlComment, "<!-- ---->", 24..34),
+            ],
+        );
+    }
+
+    #[test]
+    fn one_or_more() {
+        assert_lex(
+            r#""""foo bar""" """"""!"#,
+            &[
+                (Token::DocString, r#""""foo bar""""#, 0..13),
+                (Token::Error, r#"""""#, 14..17),
+                (Token::Error, r#"""""#, 17..20),
+                (Token::Error, "!", 20..21),
+            ],
+        );
+    }
+
+    #[test]
+    fn zero_or_one() {
+        assert_lex(
+            "ab aab",
+            &[
+                (Token::Ab, "ab", 0..2),
+                (Token::Ab, "aab", 3..6),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_lex(
+            "/* foo",
+            &[
+                (Token::Error, "/* foo", 0..6),
+            ],
+        );
+    }
+}
@@ -68,3 +68,30 @@ fn main() {
     outer = inner.advance_as();
     assert_eq!(outer.token, Outer::End);
 }
+
+#[test]
+fn morph() {
+    let s = r#""Hello""#;
+    let outer = Outer::lexer(s);
+
+    assert_eq!(outer.token, Outer::StartString);
+    assert_eq!(outer.range(), 0..1);
+
+    // Morphing keeps the span of the current token
+    let mut inner = outer.morph::<Inner>();
+    assert_eq!(inner.token, Inner::Error);
+    assert_eq!(inner.range(), 0..1);
+
+    inner.advance();
+    assert_eq!(inner.token, Inner::Text);
+    assert_eq!(inner.slice(), "Hello");
+
+    inner.advance();
+    assert_eq!(inner.token, Inner::EndString);
+
+    let mut outer = inner.morph::<Outer>();
+    assert_eq!(outer.range(), 6..7);
+
+    outer.advance();
+    assert_eq!(outer.token, Outer::End);
+}
@@ -0,0 +1,72 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = r"\R"]
+    Newline,
+
+    #[regex = r"\\\R"]
+    Continuation,
+
+    #[regex = "[a-z]+"]
+    Word,
+}
+
+mod line_breaks {
+    use super::*;
+
+    #[test]
+    fn single_line_breaks() {
+        assert_lex(
+            "a\nb\r\nc\rd\u{2028}e\u{85}",
+            &[
+                (Token::Word, "a", 0..1),
+                (Token::Newline, "\n", 1..2),
+                (Token::Word, "b", 2..3),
+                (Token::Newline, "\r\n", 3..5),
+                (Token::Word, "c", 5..6),
+                (Token::Newline, "\r", 6..7),
+                (Token::Word, "d", 7..8),
+                (Token::Newline, "\u{2028}", 8..11),
+                (Token::Word, "e", 11..12),
+                (Token::Newline, "\u{85}", 12..14),
+            ],
+        );
+    }
+
+    #[test]
+    fn consecutive_line_breaks() {
+        assert_lex(
+            "\r\n\r\r\n\n\r",
+            &[
+                (Token::Newline, "\r\n", 0..2),
+                (Token::Newline, "\r", 2..3),
+                (Token::Newline, "\r\n", 3..5),
+                (Token::Newline, "\n", 5..6),
+                (Token::Newline, "\r", 6..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn inside_pattern() {
+        assert_lex(
+            "foo \\\r\nbar \\\rbaz",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Continuation, "\\\r\n", 4..7),
+                (Token::Word, "bar", 7..10),
+                (Token::Continuation, "\\\r", 11..13),
+                (Token::Word, "baz", 13..16),
+            ],
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("-(?=[0-9])")]
+    Sign,
+
+    #[token = "-"]
+    Minus,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex("[0-9]+(?=px)")]
+    Pixels,
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[regex(r"[a-z]+(?=\()")]
+    Call,
+
+    #[regex("let(?![a-z])")]
+    Let,
+
+    #[token = "("]
+    ParenOpen,
+
+    #[token = ")"]
+    ParenClose,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Bare {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("x(?!ab)")]
+    X,
+
+    #[regex("y(?![0-9])")]
+    Y,
+
+    #[token = "a"]
+    A,
+
+    #[token = "b"]
+    B,
+
+    #[token = "xabc"]
+    Xabc,
+
+    #[regex("[0-9]")]
+    Digit,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Grouped {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("(?:a|b)(?=c)")]
+    AB,
+
+    #[regex("[a-z]")]
+    Letter,
+}
+
+mod lookahead {
+    use super::*;
+
+    #[test]
+    fn positive() {
+        assert_lex(
+            "-5 - x foo(bar) 12px 7",
+            &[
+                (Token::Sign, "-", 0..1),
+                (Token::Number, "5", 1..2),
+                (Token::Minus, "-", 3..4),
+                (Token::Ident, "x", 5..6),
+                (Token::Call, "foo", 7..10),
+                (Token::ParenOpen, "(", 10..11),
+                (Token::Ident, "bar", 11..14),
+                (Token::ParenClose, ")", 14..15),
+                (Token::Pixels, "12", 16..18),
+                (Token::Ident, "px", 18..20),
+                (Token::Number, "7", 21..22),
+            ],
+        );
+    }
+
+    #[test]
+    fn partial_lookahead() {
+        assert_lex(
+            "12p 3 -",
+            &[
+                (Token::Number, "12", 0..2),
+                (Token::Ident, "p", 2..3),
+                (Token::Number, "3", 4..5),
+                (Token::Minus, "-", 6..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn negative() {
+        assert_lex(
+            "let letter let) let(",
+            &[
+                (Token::Let, "let", 0..3),
+                (Token::Ident, "letter", 4..10),
+                (Token::Let, "let", 11..14),
+                (Token::ParenClose, ")", 14..15),
+                (Token::Call, "let", 16..19),
+                (Token::ParenOpen, "(", 19..20),
+            ],
+        );
+    }
+
+    #[test]
+    fn negative_without_fallback() {
+        assert_lex(
+            "y1 ya xb xa xab xabc",
+            &[
+                (Bare::Error, "y", 0..1),
+                (Bare::Digit, "1", 1..2),
+                (Bare::Y, "y", 3..4),
+                (Bare::A, "a", 4..5),
+                (Bare::X, "x", 6..7),
+                (Bare::B, "b", 7..8),
+                (Bare::X, "x", 9..10),
+                (Bare::A, "a", 10..11),
+                (Bare::Error, "x", 12..13),
+                (Bare::A, "a", 13..14),
+                (Bare::B, "b", 14..15),
+                (Bare::Xabc, "xabc", 16..20),
+            ],
+        );
+    }
+
+    #[test]
+    fn alternatives_in_group() {
+        assert_lex(
+            "ad bc ac",
+            &[
+                (Grouped::Letter, "a", 0..1),
+                (Grouped::Letter, "d", 1..2),
+                (Grouped::AB, "b", 3..4),
+                (Grouped::Letter, "c", 4..5),
+                (Grouped::AB, "a", 6..7),
+                (Grouped::Letter, "c", 7..8),
+            ],
+        );
+    }
+}
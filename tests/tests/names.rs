@@ -0,0 +1,36 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Identifier,
+
+    #[token("{", name = "`{`")]
+    BraceOpen,
+
+    #[token("}", name = "`}`")]
+    BraceClose,
+}
+
+mod names {
+    use super::*;
+
+    #[test]
+    fn variant_names() {
+        assert_eq!(Token::End.name(), "End");
+        assert_eq!(Token::Identifier.name(), "Identifier");
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(Token::BraceOpen.name(), "`{`");
+        assert_eq!(Token::BraceClose.name(), "`}`");
+    }
+}
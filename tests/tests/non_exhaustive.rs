@@ -0,0 +1,48 @@
+use logos::{lookup, Logos};
+use tests::assert_lex;
+use tests::non_exhaustive::Token;
+
+static KINDS: [&str; Token::SIZE] = lookup! {
+    Token::Word => "word",
+    Token::Number => "number",
+    _ => "other",
+};
+
+fn describe(token: Token) -> &'static str {
+    match token {
+        Token::Word => "word",
+        Token::Number => "number",
+        _ => "other",
+    }
+}
+
+mod non_exhaustive {
+    use super::*;
+
+    #[test]
+    fn lexes_across_crates() {
+        assert_lex(
+            "foo 42",
+            &[(Token::Word, "foo", 0..3), (Token::Number, "42", 4..6)],
+        );
+    }
+
+    #[test]
+    fn wildcard_matches() {
+        let mut lex = Token::lexer("foo 42 @");
+        let mut kinds = Vec::new();
+
+        while lex.token != Token::END {
+            kinds.push((describe(lex.token), KINDS[lex.token.discriminant()]));
+            lex.advance();
+        }
+
+        assert_eq!(kinds, &[("word", "word"), ("number", "number"), ("other", "other")]);
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(Token::SIZE, 4);
+        assert_eq!(Token::variants(), &[Token::End, Token::Error, Token::Word, Token::Number]);
+    }
+}
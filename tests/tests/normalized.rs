@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+
+use logos::{Logos as _, NormalizedSource};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = " ")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token("\n")]
+    Newline,
+
+    #[token("\\\n")]
+    Continuation,
+}
+
+fn lex_normalized(source: &NormalizedSource) -> Vec<(Token, String, std::ops::Range<usize>)> {
+    let mut lex = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    while lex.token != Token::End {
+        tokens.push((lex.token, lex.slice().to_owned(), lex.range_original()));
+        lex.advance();
+    }
+
+    tokens
+}
+
+fn crlf(line: &str) -> Cow<'_, str> {
+    match line.strip_suffix("\r\n") {
+        Some(line) => Cow::Owned(format!("{}\n", line)),
+        None => Cow::Borrowed(line),
+    }
+}
+
+mod normalized {
+    use super::*;
+
+    #[test]
+    fn crlf_to_lf() {
+        let source = NormalizedSource::new("foo bar\r\nbaz\r\n\r\nqux", crlf);
+
+        assert_eq!(source.as_str(), "foo bar\nbaz\n\nqux");
+        assert_eq!(
+            lex_normalized(&source),
+            &[
+                (Token::Word, "foo".to_owned(), 0..3),
+                (Token::Word, "bar".to_owned(), 4..7),
+                (Token::Newline, "\n".to_owned(), 8..9),
+                (Token::Word, "baz".to_owned(), 9..12),
+                (Token::Newline, "\n".to_owned(), 13..14),
+                (Token::Newline, "\n".to_owned(), 15..16),
+                (Token::Word, "qux".to_owned(), 16..19),
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        let source = NormalizedSource::new("foo\nbar", |line| Cow::Borrowed(line));
+
+        assert_eq!(source.as_str(), "foo\nbar");
+        assert_eq!(source.original_range(0..7), 0..7);
+        assert_eq!(source.original_offset(5), 5);
+    }
+
+    #[test]
+    fn removed_line_continuations() {
+        let source = NormalizedSource::new("foo\\\nbar\\\nbaz\n", |line| match line.strip_suffix("\\\n") {
+            Some(line) => Cow::Owned(line.to_owned()),
+            None => Cow::Borrowed(line),
+        });
+
+        assert_eq!(source.as_str(), "foobarbaz\n");
+        assert_eq!(source.original_range(0..9), 0..13);
+        assert_eq!(source.original_range(3..6), 5..8);
+        assert_eq!(source.original_offset(3), 5);
+        assert_eq!(source.original_offset(9), 13);
+    }
+
+    #[test]
+    fn expanded_tabs() {
+        let original = "\tfoo\tbar";
+        let tabs = original.match_indices('\t').map(|(index, _)| (index..index + 1, "    "));
+        let source = NormalizedSource::from_edits(original, tabs);
+
+        assert_eq!(source.as_str(), "    foo    bar");
+        assert_eq!(
+            lex_normalized(&source),
+            &[
+                (Token::Word, "foo".to_owned(), 1..4),
+                (Token::Word, "bar".to_owned(), 5..8),
+            ]
+        );
+        assert_eq!(source.original_offset(2), 0);
+        assert_eq!(source.original_range(2..6), 0..3);
+    }
+
+    #[test]
+    fn multi_byte_replacements() {
+        let source = NormalizedSource::new("żółw\r\nżaba", crlf);
+
+        assert_eq!(source.as_str(), "żółw\nżaba");
+        assert_eq!(source.original_range(0..7), 0..7);
+        assert_eq!(source.original_range(7..8), 8..9);
+        assert_eq!(source.original_offset(7), 8);
+        assert_eq!(source.original_range(8..13), 9..14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Edits must be sorted and must not overlap")]
+    fn overlapping_edits() {
+        NormalizedSource::from_edits("foo bar", vec![(2..5, ""), (4..6, "")]);
+    }
+}
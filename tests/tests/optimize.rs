@@ -0,0 +1,71 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(optimize = "size")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-zA-Z_$][a-zA-Z0-9_$]*"]
+    Ident,
+
+    #[regex = "[0-9]+"]
+    Number,
+
+    #[regex = "0[xX][0-9a-fA-F]+"]
+    Hex,
+
+    #[regex = r#""([^"\\]|\\.)*""#]
+    String,
+
+    #[token = "let"]
+    Let,
+
+    #[token = "("]
+    ParenOpen,
+
+    #[token = ")"]
+    ParenClose,
+
+    #[token = "="]
+    Assign,
+
+    #[token = "=="]
+    Equals,
+
+    #[token = "+"]
+    Plus,
+
+    #[token = ";"]
+    Semicolon,
+}
+
+mod optimize {
+    use super::*;
+
+    #[test]
+    fn size() {
+        assert_lex(
+            r#"let $foo_1 = (0xFF + 42) == "a \"b\""; @letter"#,
+            &[
+                (Token::Let, "let", 0..3),
+                (Token::Ident, "$foo_1", 4..10),
+                (Token::Assign, "=", 11..12),
+                (Token::ParenOpen, "(", 13..14),
+                (Token::Hex, "0xFF", 14..18),
+                (Token::Plus, "+", 19..20),
+                (Token::Number, "42", 21..23),
+                (Token::ParenClose, ")", 23..24),
+                (Token::Equals, "==", 25..27),
+                (Token::String, r#""a \"b\"""#, 28..37),
+                (Token::Semicolon, ";", 37..38),
+                (Token::Error, "@", 39..40),
+                (Token::Ident, "letter", 40..46),
+            ],
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use logos::OwnedLexer;
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex = "[0-9]+"]
+    Number,
+}
+
+fn lexer_for(words: &[&str]) -> OwnedLexer<Token> {
+    OwnedLexer::new(words.join(" "))
+}
+
+mod owned {
+    use super::*;
+
+    #[test]
+    fn returned_from_function() {
+        let mut lex = lexer_for(&["foo", "42", "bar"]);
+        let mut tokens = Vec::new();
+
+        while lex.lexer().token != Token::End {
+            let lexer = lex.lexer();
+
+            tokens.push((lexer.token, lexer.slice().to_owned(), lexer.range()));
+            lex.advance();
+        }
+
+        assert_eq!(
+            tokens,
+            &[
+                (Token::Word, "foo".to_owned(), 0..3),
+                (Token::Number, "42".to_owned(), 4..6),
+                (Token::Word, "bar".to_owned(), 7..10),
+            ]
+        );
+        assert_eq!(lex.into_source(), "foo 42 bar");
+    }
+
+    #[test]
+    fn moved_mid_lexing() {
+        let mut lex = lexer_for(&["foo", "bar"]);
+        lex.advance();
+
+        let lex = Box::new(lex);
+
+        assert_eq!(lex.lexer().slice(), "bar");
+        assert_eq!(lex.source(), "foo bar");
+    }
+}
@@ -0,0 +1,117 @@
+use logos::{Lexer, Logos as _, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+/// Decide what a backslash escapes by looking at the byte after it.
+fn escape<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<char, ()> {
+    let ch = match lex.peek_byte() {
+        Some(b'n') => '\n',
+        Some(b't') => '\t',
+        Some(b'\\') => '\\',
+        _ => return Err(()),
+    };
+
+    lex.bump(1);
+
+    Ok(ch)
+}
+
+/// Only produce an arrow for `=` when it's followed by `>`, and a comparison when
+/// it's followed by another `==`.
+fn equals<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Token {
+    if lex.peek_bytes::<2>() == Some(*b"==") {
+        lex.bump(2);
+        return Token::StrictEquals;
+    }
+
+    match lex.peek_byte() {
+        Some(b'>') => {
+            lex.bump(1);
+            Token::Arrow
+        }
+        _ => Token::Assign,
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("\\", callback = "escape")]
+    Escape(char),
+
+    #[token("=", callback = "equals")]
+    Assign,
+
+    Arrow,
+
+    StrictEquals,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+mod peek {
+    use super::*;
+
+    #[test]
+    fn peek_byte() {
+        assert_lex(
+            r"foo\nbar \t\\ \q",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Escape('\n'), r"\n", 3..5),
+                (Token::Word, "bar", 5..8),
+                (Token::Escape('\t'), r"\t", 9..11),
+                (Token::Escape('\\'), r"\\", 11..13),
+                (Token::Error, r"\", 14..15),
+                (Token::Word, "q", 15..16),
+            ],
+        );
+    }
+
+    #[test]
+    fn peek_bytes() {
+        assert_lex(
+            "a = b => c === d ==",
+            &[
+                (Token::Word, "a", 0..1),
+                (Token::Assign, "=", 2..3),
+                (Token::Word, "b", 4..5),
+                (Token::Arrow, "=>", 6..8),
+                (Token::Word, "c", 9..10),
+                (Token::StrictEquals, "===", 11..14),
+                (Token::Word, "d", 15..16),
+                (Token::Assign, "=", 17..18),
+                (Token::Assign, "=", 18..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn end_of_input() {
+        assert_lex(r"foo\", &[(Token::Word, "foo", 0..3), (Token::Error, r"\", 3..4)]);
+        assert_lex("=", &[(Token::Assign, "=", 0..1)]);
+    }
+
+    #[test]
+    fn does_not_consume() {
+        let mut lex = Token::lexer("foo bar");
+
+        assert_eq!(lex.peek_byte(), Some(b' '));
+        assert_eq!(lex.peek_bytes::<4>(), Some(*b" bar"));
+        assert_eq!(lex.peek_bytes::<5>(), None);
+        assert_eq!(lex.range(), 0..3);
+
+        lex.advance();
+
+        assert_eq!(lex.slice(), "bar");
+        assert_eq!(lex.peek_byte(), None);
+        assert_eq!(lex.peek_bytes::<1>(), None);
+    }
+}
@@ -0,0 +1,90 @@
+use logos::{Extras, Logos as _};
+use logos_derive::Logos;
+
+#[derive(Default, Clone)]
+struct Counter {
+    tokens: usize,
+}
+
+impl Extras for Counter {
+    fn on_token<'source, Token, Slice>(&mut self, _: &Token, _: &Slice)
+    where
+        Token: logos::Logos,
+        Slice: logos::Slice<'source>,
+    {
+        self.tokens += 1;
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[extras = "Counter"]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex = "[0-9]+"]
+    Number,
+}
+
+mod peekable {
+    use super::*;
+
+    #[test]
+    fn peek_ahead() {
+        let mut lex = Token::lexer("foo 42 bar").peekable(3);
+
+        assert_eq!(lex.token(), &Token::Word);
+        assert_eq!(lex.peek_nth(2), (&Token::End, 10..10));
+        assert_eq!(lex.peek_nth(1), (&Token::Word, 7..10));
+        assert_eq!(lex.peek_nth(0), (&Token::Number, 4..6));
+        assert_eq!(lex.slice(), "foo");
+        assert_eq!(lex.range(), 0..3);
+
+        lex.advance();
+
+        assert_eq!(lex.token(), &Token::Number);
+        assert_eq!(lex.slice(), "42");
+        assert_eq!(lex.peek_nth(2), (&Token::End, 10..10));
+
+        lex.advance();
+        lex.advance();
+
+        assert_eq!(lex.token(), &Token::End);
+        assert_eq!(lex.peek_nth(0), (&Token::End, 10..10));
+    }
+
+    #[test]
+    fn extras_follow_current_token() {
+        let mut lex = Token::lexer("foo 42 bar").peekable(2);
+
+        assert_eq!(lex.extras().tokens, 1);
+
+        lex.peek_nth(1);
+
+        assert_eq!(lex.extras().tokens, 1);
+
+        lex.advance();
+
+        assert_eq!(lex.extras().tokens, 2);
+
+        lex.advance();
+        lex.advance();
+
+        assert_eq!(lex.token(), &Token::End);
+        assert_eq!(lex.extras().tokens, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can only peek 2 tokens ahead")]
+    fn peek_too_far() {
+        let mut lex = Token::lexer("foo").peekable(2);
+
+        lex.peek_nth(2);
+    }
+}
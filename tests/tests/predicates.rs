@@ -0,0 +1,92 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '.' | '_' | ':' | '\u{B7}')
+}
+
+fn is_digit(byte: u8) -> bool {
+    byte.is_ascii_digit()
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z_:]", class = "is_name_char")]
+    Name,
+
+    #[regex("[0-9]", class = "is_digit")]
+    Number,
+
+    #[token = "<"]
+    Lt,
+
+    #[token = ">"]
+    Gt,
+
+    #[token = "="]
+    Assign,
+}
+
+fn is_lower_or_lead_byte(byte: u8) -> bool {
+    byte.is_ascii_lowercase() || byte == 0xC3
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Partial {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]", class = "is_lower_or_lead_byte")]
+    Word,
+}
+
+mod predicates {
+    use super::*;
+
+    #[test]
+    fn char_predicate() {
+        assert_lex(
+            "<a.żółw-1 b:c·d=42>",
+            &[
+                (Token::Lt, "<", 0..1),
+                (Token::Name, "a.żółw-1", 1..12),
+                (Token::Name, "b:c·d", 13..19),
+                (Token::Assign, "=", 19..20),
+                (Token::Number, "42", 20..22),
+                (Token::Gt, ">", 22..23),
+            ],
+        );
+    }
+
+    #[test]
+    fn byte_predicate() {
+        assert_lex(
+            "1234567890x",
+            &[
+                (Token::Number, "1234567890", 0..10),
+                (Token::Name, "x", 10..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn byte_predicate_stops_at_char_boundary() {
+        assert_lex(
+            "abé",
+            &[
+                (Partial::Word, "ab", 0..2),
+                (Partial::Error, "é", 2..4),
+            ],
+        );
+    }
+}
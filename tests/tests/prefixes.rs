@@ -0,0 +1,253 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token = "+"]
+    Plus,
+
+    #[token = "++"]
+    Increment,
+
+    #[token = "+="]
+    AddAssign,
+
+    #[token = "-"]
+    Minus,
+
+    #[token = "--"]
+    Decrement,
+
+    #[token = "-="]
+    SubAssign,
+
+    #[token = "*"]
+    Star,
+
+    #[token = "**"]
+    Exponent,
+
+    #[token = "*="]
+    MulAssign,
+
+    #[token = "**="]
+    ExponentAssign,
+
+    #[token = "="]
+    Assign,
+
+    #[token = "=="]
+    Equality,
+
+    #[token = "==="]
+    StrictEquality,
+
+    #[token = "=>"]
+    Arrow,
+
+    #[token = "!"]
+    Not,
+
+    #[token = "!="]
+    Inequality,
+
+    #[token = "!=="]
+    StrictInequality,
+
+    #[token = "<"]
+    Less,
+
+    #[token = "<="]
+    LessEquals,
+
+    #[token = "<<"]
+    ShiftLeft,
+
+    #[token = "<<="]
+    ShiftLeftAssign,
+
+    #[token = ">"]
+    Greater,
+
+    #[token = ">="]
+    GreaterEquals,
+
+    #[token = ">>"]
+    ShiftRight,
+
+    #[token = ">>="]
+    ShiftRightAssign,
+
+    #[token = ">>>"]
+    UnsignedShiftRight,
+
+    #[token = ">>>="]
+    UnsignedShiftRightAssign,
+
+    #[token = "&"]
+    BitAnd,
+
+    #[token = "&&"]
+    And,
+
+    #[token = "&="]
+    BitAndAssign,
+
+    #[token = "&&="]
+    AndAssign,
+
+    #[token = "."]
+    Dot,
+
+    #[token = "..."]
+    Spread,
+}
+
+/// Every literal along with its token, for the reference implementation.
+const LITERALS: &[(&str, Token)] = &[
+    ("+", Token::Plus),
+    ("++", Token::Increment),
+    ("+=", Token::AddAssign),
+    ("-", Token::Minus),
+    ("--", Token::Decrement),
+    ("-=", Token::SubAssign),
+    ("*", Token::Star),
+    ("**", Token::Exponent),
+    ("*=", Token::MulAssign),
+    ("**=", Token::ExponentAssign),
+    ("=", Token::Assign),
+    ("==", Token::Equality),
+    ("===", Token::StrictEquality),
+    ("=>", Token::Arrow),
+    ("!", Token::Not),
+    ("!=", Token::Inequality),
+    ("!==", Token::StrictInequality),
+    ("<", Token::Less),
+    ("<=", Token::LessEquals),
+    ("<<", Token::ShiftLeft),
+    ("<<=", Token::ShiftLeftAssign),
+    (">", Token::Greater),
+    (">=", Token::GreaterEquals),
+    (">>", Token::ShiftRight),
+    (">>=", Token::ShiftRightAssign),
+    (">>>", Token::UnsignedShiftRight),
+    (">>>=", Token::UnsignedShiftRightAssign),
+    ("&", Token::BitAnd),
+    ("&&", Token::And),
+    ("&=", Token::BitAndAssign),
+    ("&&=", Token::AndAssign),
+    (".", Token::Dot),
+    ("...", Token::Spread),
+];
+
+/// Maximal munch done the slow way: the longest literal at each position wins,
+/// anything else is a single byte error.
+fn reference(source: &str) -> Vec<(Token, std::ops::Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < source.len() {
+        if source[pos..].starts_with(' ') {
+            pos += 1;
+            continue;
+        }
+
+        let (len, token) = LITERALS
+            .iter()
+            .filter(|(literal, _)| source[pos..].starts_with(literal))
+            .map(|&(literal, token)| (literal.len(), token))
+            .max_by_key(|&(len, _)| len)
+            .unwrap_or((1, Token::Error));
+
+        tokens.push((token, pos..pos + len));
+        pos += len;
+    }
+
+    tokens
+}
+
+/// Small xorshift generator, so failures reproduce without extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+mod prefixes {
+    use super::*;
+
+    #[test]
+    fn longest_match() {
+        assert_lex(
+            "+ ++ += +++= ** **= *** >>>= >>>>= ... .. &&= &&&",
+            &[
+                (Token::Plus, "+", 0..1),
+                (Token::Increment, "++", 2..4),
+                (Token::AddAssign, "+=", 5..7),
+                (Token::Increment, "++", 8..10),
+                (Token::AddAssign, "+=", 10..12),
+                (Token::Exponent, "**", 13..15),
+                (Token::ExponentAssign, "**=", 16..19),
+                (Token::Exponent, "**", 20..22),
+                (Token::Star, "*", 22..23),
+                (Token::UnsignedShiftRightAssign, ">>>=", 24..28),
+                (Token::UnsignedShiftRight, ">>>", 29..32),
+                (Token::GreaterEquals, ">=", 32..34),
+                (Token::Spread, "...", 35..38),
+                (Token::Dot, ".", 39..40),
+                (Token::Dot, ".", 40..41),
+                (Token::AndAssign, "&&=", 42..45),
+                (Token::And, "&&", 46..48),
+                (Token::BitAnd, "&", 48..49),
+            ],
+        );
+    }
+
+    #[test]
+    fn backtracking_to_shorter_prefix() {
+        assert_lex(
+            "!=! ==> <<< >>>> ..=",
+            &[
+                (Token::Inequality, "!=", 0..2),
+                (Token::Not, "!", 2..3),
+                (Token::Equality, "==", 4..6),
+                (Token::Greater, ">", 6..7),
+                (Token::ShiftLeft, "<<", 8..10),
+                (Token::Less, "<", 10..11),
+                (Token::UnsignedShiftRight, ">>>", 12..15),
+                (Token::Greater, ">", 15..16),
+                (Token::Dot, ".", 17..18),
+                (Token::Dot, ".", 18..19),
+                (Token::Assign, "=", 19..20),
+            ],
+        );
+    }
+
+    #[test]
+    fn random_sources() {
+        const ALPHABET: &[u8] = b"+-*=!<>&. ";
+
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+
+        for _ in 0..2000 {
+            let len = (rng.next() % 24) as usize;
+            let source: String = (0..len)
+                .map(|_| ALPHABET[(rng.next() % ALPHABET.len() as u64) as usize] as char)
+                .collect();
+
+            assert_eq!(Token::lexer(&source[..]).into_vec(), reference(&source), "lexing {:?}", source);
+        }
+    }
+}
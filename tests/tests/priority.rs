@@ -0,0 +1,174 @@
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+", priority = 2)]
+    Word,
+
+    #[regex = "[a-z0-9]+"]
+    Alphanumeric,
+
+    #[token("let", priority = 1, allow(unreachable))]
+    Let,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Ambiguous {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex("[0-9a-f]+", allow(ambiguity))]
+    Hex,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Js {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-zA-Z_$][a-zA-Z0-9_$]*")]
+    Ident,
+
+    // Ties with `instanceof` without the keyword flag
+    #[regex("[a-z]{10}")]
+    TenLetters,
+
+    #[token("instanceof", keyword)]
+    InstanceOf,
+
+    #[token("in", keyword)]
+    In,
+}
+
+mod priority {
+    use super::*;
+    use tests::assert_lex;
+
+    #[test]
+    fn override_breaks_tie() {
+        assert_lex(
+            "abc abc1 1abc",
+            &[
+                (Token::Word, "abc", 0..3),
+                (Token::Alphanumeric, "abc1", 4..8),
+                (Token::Alphanumeric, "1abc", 9..13),
+            ],
+        );
+    }
+
+    #[test]
+    fn override_lowers_priority() {
+        assert_lex(
+            "let lets",
+            &[
+                (Token::Word, "let", 0..3),
+                (Token::Word, "lets", 4..8),
+            ],
+        );
+
+        assert_ne!(Token::Let, Token::Word);
+    }
+
+    #[test]
+    fn keywords_beat_classes() {
+        assert_lex(
+            "a instanceof b in instances abcdefghij",
+            &[
+                (Js::Ident, "a", 0..1),
+                (Js::InstanceOf, "instanceof", 2..12),
+                (Js::Ident, "b", 13..14),
+                (Js::In, "in", 15..17),
+                (Js::Ident, "instances", 18..27),
+                (Js::TenLetters, "abcdefghij", 28..38),
+            ],
+        );
+
+        assert_eq!(Js::InstanceOf.priority(), 20);
+        assert_eq!(Js::In.priority(), 4);
+    }
+
+    #[test]
+    fn acknowledged_ambiguity() {
+        assert_lex(
+            "xyz 1234 12ab",
+            &[
+                (Ambiguous::Word, "xyz", 0..3),
+                (Ambiguous::Hex, "1234", 4..8),
+                (Ambiguous::Hex, "12ab", 9..13),
+            ],
+        );
+    }
+}
+
+mod runtime {
+    use super::*;
+    use logos::{Lexer, Slice, Source};
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Multiple {
+        #[end]
+        End,
+
+        #[error]
+        Error,
+
+        #[token = "+"]
+        #[token = "plus"]
+        #[regex("add[0-9]", priority = 9)]
+        Plus,
+
+        #[regex("[0-9]+", callback = "number")]
+        Number(u64),
+    }
+
+    fn number<'s, S: Source<'s>>(lex: &mut Lexer<Multiple, S>) -> u64 {
+        lex.slice()
+            .as_bytes()
+            .iter()
+            .fold(0, |acc, byte| acc * 10 + (byte - b'0') as u64)
+    }
+
+    #[test]
+    fn computed_and_explicit() {
+        assert_eq!(Token::Word.priority(), 2);
+        assert_eq!(Token::Alphanumeric.priority(), 1);
+        assert_eq!(Token::Let.priority(), 1);
+        assert_eq!(Ambiguous::Word.priority(), 1);
+    }
+
+    #[test]
+    fn highest_of_definitions() {
+        assert_eq!(Multiple::Plus.priority(), 9);
+        assert_eq!(Multiple::Number(42).priority(), 1);
+    }
+
+    #[test]
+    fn no_definitions() {
+        assert_eq!(Token::End.priority(), 0);
+        assert_eq!(Token::Error.priority(), 0);
+        assert_eq!(Multiple::End.priority(), 0);
+    }
+
+    #[test]
+    fn const_eval() {
+        const PLUS: u32 = Multiple::Plus.priority();
+
+        assert_eq!(PLUS, 9);
+    }
+}
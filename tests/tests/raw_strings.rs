@@ -0,0 +1,114 @@
+use logos::{Lexer, Slice, Source};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+/// Consume a Rust raw string, closed by a quote followed by as many `#`s as it was
+/// opened with, producing that number.
+fn raw_string<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<usize, ()> {
+    let hashes = lex.slice().as_bytes().len() - 2;
+    let mut terminator = vec![b'#'; hashes + 1];
+
+    terminator[0] = b'"';
+
+    match lex.bump_past(&terminator) {
+        true => Ok(hashes),
+        false => Err(()),
+    }
+}
+
+/// Consume a Lua long bracket, `[==[` being closed by `]==]`.
+fn long_bracket<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> Result<(), ()> {
+    let terminator: Vec<u8> = lex
+        .slice()
+        .as_bytes()
+        .iter()
+        .map(|&byte| if byte == b'[' { b']' } else { byte })
+        .collect();
+
+    match lex.bump_past(&terminator) {
+        true => Ok(()),
+        false => Err(()),
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r##"r#*""##, callback = "raw_string")]
+    RawString(usize),
+
+    #[regex(r"\[=*\[", callback = "long_bracket")]
+    LongBracket,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+mod raw_strings {
+    use super::*;
+
+    #[test]
+    fn matching_hashes() {
+        assert_lex(
+            r####"r"plain" r#"has "quotes""# r##"has "# inside"## foo"####,
+            &[
+                (Token::RawString(0), r#"r"plain""#, 0..8),
+                (Token::RawString(1), r##"r#"has "quotes""#"##, 9..26),
+                (Token::RawString(2), r###"r##"has "# inside"##"###, 27..47),
+                (Token::Word, "foo", 48..51),
+            ],
+        );
+    }
+
+    #[test]
+    fn multiple_lines() {
+        assert_lex(
+            "r#\"foo\n\"bar\"\n\"# baz",
+            &[
+                (Token::RawString(1), "r#\"foo\n\"bar\"\n\"#", 0..15),
+                (Token::Word, "baz", 16..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn excess_hashes() {
+        assert_lex(
+            r###"r#"foo"## bar"###,
+            &[
+                (Token::RawString(1), r##"r#"foo"#"##, 0..8),
+                (Token::Error, "#", 8..9),
+                (Token::Word, "bar", 10..13),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_lex(
+            r###"foo r##"bar"# baz"###,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, r##"r##"bar"# baz"##, 4..17),
+            ],
+        );
+    }
+
+    #[test]
+    fn lua_long_brackets() {
+        assert_lex(
+            "[[foo]] [==[bar]]baz]=]]==] [=[unterminated]]",
+            &[
+                (Token::LongBracket, "[[foo]]", 0..7),
+                (Token::LongBracket, "[==[bar]]baz]=]]==]", 8..27),
+                (Token::Error, "[=[unterminated]]", 28..45),
+            ],
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::cell::Cell;
+use std::ops::Range;
+
+use logos::source::{Chunk, Source};
+use logos::Lexer;
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = " ")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("function")]
+    Function,
+
+    #[token("fallback")]
+    Fallback,
+
+    #[token("synchronized")]
+    Synchronized,
+
+    #[token("serializable")]
+    Serializable,
+
+    #[token("internal_package")]
+    InternalPackage,
+
+    #[token("implicit_virtual")]
+    ImplicitVirtual,
+}
+
+/// Source counting the reads the lexer makes, each of which checks bounds.
+struct Counting<'s> {
+    source: &'s str,
+    reads: Cell<usize>,
+}
+
+impl<'s> Source<'s> for &'s Counting<'s> {
+    type Slice = &'s str;
+
+    fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    fn read<C: Chunk<'s>>(&self, offset: usize) -> Option<C> {
+        self.reads.set(self.reads.get() + 1);
+        Source::read(&self.source, offset)
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<&'s str> {
+        Source::slice(&self.source, range)
+    }
+
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &'s str {
+        Source::slice_unchecked(&self.source, range)
+    }
+
+    fn find_boundary(&self, index: usize) -> usize {
+        Source::find_boundary(&self.source, index)
+    }
+}
+
+fn reads(source: &str) -> (Token, usize) {
+    let counting = Counting { source, reads: Cell::new(0) };
+    let lex = Lexer::<Token, _>::new(&counting);
+
+    (lex.token, counting.reads.get())
+}
+
+mod reads {
+    use super::*;
+
+    #[test]
+    fn keywords_read_once_after_first_byte() {
+        // One read for the first byte, then one for the rest of the keyword,
+        // which is long enough to tell apart the keywords sharing the first byte
+        assert_eq!(reads("function"), (Token::Function, 2));
+        assert_eq!(reads("fallback"), (Token::Fallback, 2));
+        assert_eq!(reads("synchronized"), (Token::Synchronized, 2));
+        assert_eq!(reads("serializable"), (Token::Serializable, 2));
+        assert_eq!(reads("internal_package"), (Token::InternalPackage, 2));
+        assert_eq!(reads("implicit_virtual"), (Token::ImplicitVirtual, 2));
+    }
+
+    #[test]
+    fn truncated_keywords() {
+        assert_eq!(reads("functio").0, Token::Error);
+        assert_eq!(reads("synchronize").0, Token::Error);
+        assert_eq!(reads("implicit_virtua").0, Token::Error);
+        assert_eq!(reads("implicit_virtuaL").0, Token::Error);
+    }
+}
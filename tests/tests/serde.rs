@@ -0,0 +1,56 @@
+use logos::{Lexer, Logos as _, Slice, Source};
+use logos_derive::Logos;
+
+fn number<'s, S: Source<'s>>(lex: &mut Lexer<Token, S>) -> u64 {
+    lex.slice()
+        .as_bytes()
+        .iter()
+        .fold(0, |acc, byte| acc * 10 + (byte - b'0') as u64)
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(serde)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex("[0-9]+", callback = "number")]
+    Number(u64),
+}
+
+mod serde {
+    use super::*;
+
+    #[test]
+    fn serialize_variant_names() {
+        let json = serde_json::to_string(&[Token::Word, Token::Number(42), Token::End]).unwrap();
+
+        assert_eq!(json, r#"["Word",{"Number":42},"End"]"#);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut lex = Token::lexer("foo 42 !");
+        let mut tokens = Vec::new();
+
+        loop {
+            tokens.push(lex.token);
+
+            if lex.token == Token::End {
+                break;
+            }
+            lex.advance();
+        }
+
+        let json = serde_json::to_string(&tokens).unwrap();
+        let replayed: Vec<Token> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(replayed, &[Token::Word, Token::Number(42), Token::Error, Token::End]);
+    }
+}
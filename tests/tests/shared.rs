@@ -0,0 +1,84 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-ząćęłńóśźż]+"]
+    Word,
+}
+
+mod shared {
+    use super::*;
+
+    #[test]
+    fn arc_source() {
+        let source: Arc<str> = "foo żółw".into();
+
+        assert_lex(
+            &source,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "żółw", 4..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn rc_source() {
+        let source: Rc<str> = "foo bar".into();
+
+        assert_lex(
+            &source,
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "bar", 4..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn slices_outlive_lexer() {
+        let mut words = Vec::new();
+
+        {
+            let source: Arc<str> = "foo żółw".into();
+            let mut lex = Token::lexer(&source);
+
+            while lex.token != Token::End {
+                words.push(lex.shared_slice());
+                lex.advance();
+            }
+        }
+
+        let words = thread::spawn(move || words.iter().map(|word| word.to_string()).collect::<Vec<_>>())
+            .join()
+            .unwrap();
+
+        assert_eq!(words, &["foo", "żółw"]);
+    }
+
+    #[test]
+    fn rc_slices() {
+        let source: Rc<str> = "foo bar".into();
+        let mut lex = Token::lexer(&source);
+
+        lex.advance();
+
+        let slice = lex.shared_slice();
+
+        assert_eq!(slice, "bar");
+        assert_eq!(slice.range(), 4..7);
+        assert!(Rc::ptr_eq(slice.source(), &source));
+    }
+}
@@ -1,4 +1,4 @@
-use logos::{Extras, Lexer};
+use logos::{Extras, Lexer, Slice};
 use logos_derive::Logos;
 
 #[derive(Default)]
@@ -7,6 +7,10 @@ struct MockExtras {
     tokens: usize,
     numbers: usize,
     byte_size: u8,
+    resets: usize,
+    identifiers: usize,
+    longest: usize,
+    source_len: usize,
 }
 
 impl Extras for MockExtras {
@@ -17,6 +21,24 @@ impl Extras for MockExtras {
     fn on_whitespace(&mut self) {
         self.spaces += 1;
     }
+
+    fn on_token<'s, T: logos::Logos, S: Slice<'s>>(&mut self, token: &T, slice: &S) {
+        if token.name() == "Identifier" {
+            self.identifiers += 1;
+        }
+        self.longest = self.longest.max(slice.as_bytes().len());
+    }
+
+    fn on_source(&mut self, source: &[u8]) {
+        self.source_len = source.len();
+    }
+
+    fn reset(&mut self) {
+        *self = MockExtras {
+            resets: self.resets + 1,
+            ..MockExtras::default()
+        };
+    }
 }
 
 fn count_numbers<S>(lex: &mut Lexer<Token, S>) {
@@ -121,6 +143,12 @@ enum Token {
 
     #[token = "=>"]
     FatArrow,
+
+    #[token = "<"]
+    OpLess,
+
+    #[token("<>", "!=", "!==")]
+    OpInequality,
 }
 
 mod simple {
@@ -159,6 +187,21 @@ mod simple {
         );
     }
 
+    #[test]
+    fn multiple_spellings() {
+        assert_lex(
+            "<> != < !== <<>",
+            &[
+                (Token::OpInequality, "<>", 0..2),
+                (Token::OpInequality, "!=", 3..5),
+                (Token::OpLess, "<", 6..7),
+                (Token::OpInequality, "!==", 8..11),
+                (Token::OpLess, "<", 12..13),
+                (Token::OpInequality, "<>", 13..15),
+            ],
+        );
+    }
+
     #[test]
     fn punctation() {
         assert_lex(
@@ -244,6 +287,28 @@ mod simple {
         );
     }
 
+    #[test]
+    fn long_numbers() {
+        let short = "1234567890123456789012345678901";
+        let long = "12345678901234567890123456789012345678901234567890123456789012345678901";
+
+        assert_lex(
+            format!("{} {}", short, long).as_str(),
+            &[
+                (Token::Number, short, 0..31),
+                (Token::Number, long, 32..103),
+            ],
+        );
+        assert_lex(
+            format!("{}.{}", long, short).as_str(),
+            &[
+                (Token::Number, long, 0..71),
+                (Token::Accessor, ".", 71..72),
+                (Token::Number, short, 72..103),
+            ],
+        );
+    }
+
     #[test]
     fn invalid_tokens() {
         assert_lex(
@@ -334,6 +399,50 @@ mod simple {
         assert_eq!(lex.extras.tokens, 7); // End counts as a token
 
         assert_eq!(lex.extras.numbers, 2);
+        assert_eq!(lex.extras.identifiers, 3);
+        assert_eq!(lex.extras.longest, 4);
+    }
+
+    #[test]
+    fn extras_accessors() {
+        let mut lex = Token::lexer("foo 42 bar");
+
+        lex.advance();
+        assert_eq!(lex.extras().numbers, 1);
+
+        lex.extras_mut().numbers = 0;
+        lex.advance();
+        lex.advance();
+
+        assert_eq!(lex.extras().numbers, 0);
+        assert_eq!(lex.extras().identifiers, 2);
+    }
+
+    #[test]
+    fn reset() {
+        let mut lex = Token::lexer("foo  bar 42");
+
+        assert_eq!(lex.extras.source_len, 11);
+
+        while lex.token != Token::End {
+            lex.advance();
+        }
+
+        lex.reset("HAL 9000");
+
+        assert_eq!(lex.token, Token::Identifier);
+        assert_eq!(lex.slice(), "HAL");
+        assert_eq!(lex.range(), 0..3);
+        assert_eq!(lex.extras.resets, 1);
+        assert_eq!(lex.extras.source_len, 8);
+        assert_eq!(lex.extras.numbers, 0);
+        assert_eq!(lex.extras.tokens, 1);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Number);
+        assert_eq!(lex.slice(), "9000");
+        assert_eq!(lex.extras.numbers, 1);
     }
 
     #[test]
@@ -424,4 +533,99 @@ mod simple {
 
         assert_eq!(lex.token, Token::End);
     }
+
+    #[test]
+    fn remainder() {
+        let mut lex = Token::lexer("foo --- raw ĄĘ blob");
+
+        assert_eq!(lex.token, Token::Identifier);
+        assert_eq!(lex.remainder(), " --- raw ĄĘ blob");
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Error);
+        assert_eq!(lex.remainder(), "-- raw ĄĘ blob");
+
+        while lex.token != Token::End {
+            lex.advance();
+        }
+
+        assert_eq!(lex.remainder(), "");
+    }
+
+    #[test]
+    fn into_vec() {
+        let mut lex = Token::lexer("foo --- 42");
+
+        lex.advance();
+
+        assert_eq!(
+            lex.into_vec(),
+            &[
+                (Token::Error, 4..5),
+                (Token::Error, 5..6),
+                (Token::Error, 6..7),
+                (Token::Number, 8..10),
+            ]
+        );
+        assert_eq!(Token::lexer("").into_vec(), &[]);
+    }
+
+    #[test]
+    fn skip_bom() {
+        assert_eq!(
+            Token::lexer_skip_bom("\u{FEFF}foo 42").into_vec(),
+            &[(Token::Identifier, 3..6), (Token::Number, 7..9)]
+        );
+        assert_eq!(
+            Token::lexer_skip_bom("foo\u{FEFF}").into_vec(),
+            &[(Token::Identifier, 0..3), (Token::Error, 3..6)]
+        );
+        assert_eq!(Token::lexer_skip_bom("\u{FEFF}").range(), 3..3);
+        assert_eq!(Token::lexer_skip_bom("\u{FEFF}").token, Token::End);
+    }
+
+    #[test]
+    fn at_start_and_end() {
+        let mut lex = Token::lexer("foo 42");
+
+        assert!(lex.at_start());
+        assert!(!lex.is_at_end());
+
+        lex.advance();
+
+        assert!(!lex.at_start());
+        assert!(!lex.is_at_end());
+
+        lex.advance();
+
+        assert!(lex.is_at_end());
+        assert_eq!(lex.token, Token::End);
+
+        let lex = Token::lexer("  foo");
+
+        assert!(!lex.at_start());
+
+        let lex = Token::lexer("");
+
+        assert!(lex.at_start());
+        assert!(lex.is_at_end());
+    }
+
+    #[test]
+    fn position() {
+        let mut lex = Token::lexer("foo\n  bar\r\nĄĘ baz\r\n\nżółw 42");
+        let mut next = |slice| {
+            while lex.slice() != slice {
+                lex.advance();
+            }
+            lex.position()
+        };
+
+        assert_eq!(next("foo"), (1, 1));
+        assert_eq!(next("bar"), (2, 3));
+        assert_eq!(next("\r"), (2, 6));
+        assert_eq!(next("baz"), (3, 4));
+        assert_eq!(next("42"), (5, 6));
+    }
 }
@@ -0,0 +1,88 @@
+use logos::{Filter, Lexer, Logos as _, Source};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia())]
+#[skip = r"[ \t\n\r]+"]
+#[skip = r"//[^\n]*"]
+#[skip = r"/\*[^*]*\*/"]
+enum Token {
+    #[error]
+    Error,
+
+    #[end]
+    End,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[token = "/"]
+    Slash,
+
+    #[token = "*"]
+    Star,
+}
+
+fn skip_x<'s, S: Source<'s>>(_: &mut Lexer<Filtered, S>) -> Filter<()> {
+    Filter::Skip
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Filtered {
+    #[error]
+    Error,
+
+    #[end]
+    End,
+
+    #[token("x", callback = "skip_x")]
+    X,
+}
+
+mod skip {
+    use super::*;
+    use tests::assert_lex;
+
+    #[test]
+    fn whitespace_and_comments() {
+        assert_lex(
+            "foo // comment\n\tbar /* block\n comment */ / * baz\r\n//",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Word, "bar", 16..19),
+                (Token::Slash, "/", 41..42),
+                (Token::Star, "*", 43..44),
+                (Token::Word, "baz", 45..48),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        assert_lex(
+            "foo /* bar",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "/* bar", 4..10),
+            ],
+        );
+    }
+
+    #[test]
+    fn many_consecutive_comments() {
+        let source = "/**/".repeat(1_000_000) + "foo";
+        let lex = Token::lexer(source.as_str());
+
+        assert_eq!(lex.token, Token::Word);
+        assert_eq!(lex.range(), 4_000_000..4_000_003);
+    }
+
+    #[test]
+    fn many_consecutive_filter_skips() {
+        let source = "x".repeat(2_000_000);
+        let lex = Filtered::lexer(source.as_str());
+
+        assert_eq!(lex.token, Filtered::End);
+        assert_eq!(lex.range(), 2_000_000..2_000_000);
+    }
+}
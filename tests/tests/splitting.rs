@@ -0,0 +1,177 @@
+use logos::{Extras, Lexer, Logos as _, Source, StreamingLexer};
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Default)]
+struct Generics {
+    depth: usize,
+}
+
+impl Extras for Generics {}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[extras = "Generics"]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("<", callback = "open")]
+    Less,
+
+    #[token = ">"]
+    Greater,
+
+    #[token(">>", callback = "shift")]
+    ShiftRight,
+
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+fn open<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) {
+    lex.extras.depth += 1;
+}
+
+fn shift<'source, S: Source<'source>>(lex: &mut Lexer<Token, S>) -> Token {
+    if lex.extras.depth < 2 {
+        return Token::ShiftRight;
+    }
+
+    let end = lex.range().end;
+
+    lex.extras.depth -= 2;
+    lex.emit_next(Token::Greater, end - 1..end);
+
+    Token::Greater
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Pair {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("ab", callback = "pair")]
+    A,
+
+    B,
+}
+
+fn pair<'source, S: Source<'source>>(lex: &mut Lexer<Pair, S>) -> Pair {
+    let start = lex.range().start;
+
+    lex.emit_next(Pair::B, start + 1..start + 2);
+
+    Pair::A
+}
+
+mod splitting {
+    use super::*;
+
+    #[test]
+    fn splits_inside_generics() {
+        assert_lex(
+            "a<b<c>> d",
+            &[
+                (Token::Ident, "a", 0..1),
+                (Token::Less, "<", 1..2),
+                (Token::Ident, "b", 2..3),
+                (Token::Less, "<", 3..4),
+                (Token::Ident, "c", 4..5),
+                (Token::Greater, ">", 5..6),
+                (Token::Greater, ">", 6..7),
+                (Token::Ident, "d", 8..9),
+            ],
+        );
+    }
+
+    #[test]
+    fn keeps_shift_outside_generics() {
+        assert_lex(
+            "a >> b",
+            &[
+                (Token::Ident, "a", 0..1),
+                (Token::ShiftRight, ">>", 2..4),
+                (Token::Ident, "b", 5..6),
+            ],
+        );
+    }
+
+    #[test]
+    fn emits_at_end_of_source() {
+        let mut lex = Token::lexer("<<>>");
+
+        lex.advance();
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Greater);
+        assert_eq!(lex.range(), 2..3);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::Greater);
+        assert_eq!(lex.range(), 3..4);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+    }
+
+    #[test]
+    fn reset_drops_queued_token() {
+        let mut lex = Token::lexer("<<>>");
+
+        lex.advance();
+        lex.advance();
+        lex.reset("a");
+
+        assert_eq!(lex.token, Token::Ident);
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range for a queued token")]
+    fn rejects_range_past_end() {
+        let mut lex = Token::lexer("a");
+
+        lex.emit_next(Token::Greater, 1..2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range for a queued token")]
+    fn rejects_range_after_token() {
+        let mut lex = Token::lexer("a b");
+
+        lex.emit_next(Token::Ident, 2..3);
+    }
+
+    #[test]
+    fn streaming_keeps_queued_token() {
+        let mut lex = StreamingLexer::<Pair, _>::new(&b"abab"[..]).unwrap();
+        let mut tokens = Vec::new();
+
+        while lex.token != Pair::End {
+            tokens.push((lex.token, lex.range()));
+            lex.advance().unwrap();
+        }
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Pair::A, 0..1),
+                (Pair::B, 1..2),
+                (Pair::A, 2..3),
+                (Pair::B, 3..4),
+            ]
+        );
+    }
+}
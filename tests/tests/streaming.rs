@@ -0,0 +1,186 @@
+use std::io::{self, Read};
+
+use logos::{Extras, Logos as _, StreamingLexer};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "[a-z]+"]
+    Word,
+
+    #[regex = "[0-9]+"]
+    Number,
+
+    #[token = "extraordinarily"]
+    Long,
+
+    #[regex = "^#[a-z]+"]
+    Header,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Backtrack {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("a(b{100}c)?")]
+    A,
+
+    #[token = "b"]
+    B,
+
+    #[token = "c"]
+    C,
+}
+
+#[derive(Default, Clone)]
+struct Spaces {
+    count: usize,
+}
+
+impl Extras for Spaces {
+    fn on_whitespace(&mut self) {
+        self.count += 1;
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[extras = "Spaces"]
+enum Counted {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = "x+"]
+    Xs,
+}
+
+/// Reader handing out at most 3 bytes at a time.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(3);
+
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+
+        Ok(len)
+    }
+}
+
+fn collect<R: Read>(mut lex: StreamingLexer<Token, R>) -> Vec<(Token, Vec<u8>, std::ops::Range<usize>)> {
+    let mut tokens = Vec::new();
+
+    while lex.token != Token::End {
+        tokens.push((lex.token, lex.slice().to_vec(), lex.range()));
+        lex.advance().unwrap();
+    }
+
+    tokens
+}
+
+mod streaming {
+    use super::*;
+
+    #[test]
+    fn tokens_across_refills() {
+        let source = "foo 42\nextraordinarily bar";
+        let lex = StreamingLexer::with_capacity(Trickle(source.as_bytes()), 0).unwrap();
+
+        assert_eq!(
+            collect(lex),
+            vec![
+                (Token::Word, b"foo".to_vec(), 0..3),
+                (Token::Number, b"42".to_vec(), 4..6),
+                (Token::Long, b"extraordinarily".to_vec(), 7..22),
+                (Token::Word, b"bar".to_vec(), 23..26),
+            ]
+        );
+    }
+
+    #[test]
+    fn token_longer_than_window() {
+        let word = "x".repeat(1000);
+        let source = format!("{} 7", word);
+        let lex = StreamingLexer::with_capacity(Trickle(source.as_bytes()), 64).unwrap();
+
+        assert_eq!(
+            collect(lex),
+            vec![
+                (Token::Word, word.into_bytes(), 0..1000),
+                (Token::Number, b"7".to_vec(), 1001..1002),
+            ]
+        );
+    }
+
+    #[test]
+    fn anchor_only_at_start_of_stream() {
+        let source = "#foo#bar";
+        let lex = StreamingLexer::with_capacity(Trickle(source.as_bytes()), 0).unwrap();
+
+        assert_eq!(
+            collect(lex),
+            vec![
+                (Token::Header, b"#foo".to_vec(), 0..4),
+                (Token::Error, b"#".to_vec(), 4..5),
+                (Token::Word, b"bar".to_vec(), 5..8),
+            ]
+        );
+    }
+
+    #[test]
+    fn backtracking_past_window() {
+        let source = format!("a{}c", "b".repeat(100));
+        let mut lex = StreamingLexer::<Backtrack, _>::with_capacity(Trickle(source.as_bytes()), 64).unwrap();
+
+        assert_eq!(lex.token, Backtrack::A);
+        assert_eq!(lex.range(), 0..102);
+        assert_eq!(Backtrack::lexer(source.as_str()).range(), 0..102);
+
+        lex.advance().unwrap();
+
+        assert_eq!(lex.token, Backtrack::End);
+    }
+
+    #[test]
+    fn extras_on_retry() {
+        let source = format!("{}{}", " ".repeat(10), "x".repeat(100));
+        let mut lex = StreamingLexer::<Counted, _>::with_capacity(Trickle(source.as_bytes()), 64).unwrap();
+
+        assert_eq!(lex.token, Counted::Xs);
+        assert_eq!(lex.range(), 10..110);
+        assert_eq!(lex.extras.count, 10);
+
+        lex.advance().unwrap();
+
+        assert_eq!(lex.token, Counted::End);
+        assert_eq!(lex.extras.count, 10);
+        assert_eq!(Counted::lexer(source.as_str()).extras.count, 10);
+    }
+
+    #[test]
+    fn errors_are_propagated() {
+        struct Broken;
+
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken"))
+            }
+        }
+
+        assert!(StreamingLexer::<Token, _>::new(Broken).is_err());
+    }
+}
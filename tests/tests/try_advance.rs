@@ -0,0 +1,93 @@
+use logos::{LexError, Logos as _, Span};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token(",")]
+    Comma,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Strict {
+    #[end]
+    #[error]
+    Stop,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+/// Count the words in a comma separated list, failing on the first lexical error.
+fn count_words(source: &str) -> Result<usize, LexError<&str>> {
+    let mut lex = Token::lexer(source);
+    let mut words = 0;
+
+    if lex.token == Token::Word {
+        words += 1;
+    }
+
+    loop {
+        match lex.try_advance()? {
+            Token::Word => words += 1,
+            Token::End => return Ok(words),
+            _ => (),
+        }
+    }
+}
+
+mod try_advance {
+    use super::*;
+
+    #[test]
+    fn propagates_errors() {
+        assert_eq!(count_words("foo, bar, baz"), Ok(3));
+        assert_eq!(
+            count_words("foo, b@r"),
+            Err(LexError {
+                span: Span::new(6, 7),
+                slice: "@",
+            })
+        );
+    }
+
+    #[test]
+    fn continues_after_error() {
+        let mut lex = Token::lexer("foo ?! bar");
+
+        assert_eq!(lex.try_advance().unwrap_err().slice, "?");
+        assert_eq!(lex.try_advance().unwrap_err().slice, "!");
+        assert_eq!(lex.try_advance(), Ok(&Token::Word));
+        assert_eq!(lex.slice(), "bar");
+        assert_eq!(lex.try_advance(), Ok(&Token::End));
+    }
+
+    #[test]
+    fn end_shared_with_error() {
+        let mut lex = Strict::lexer("foo bar");
+
+        assert_eq!(lex.try_advance(), Ok(&Strict::Word));
+        assert_eq!(lex.try_advance(), Ok(&Strict::Stop));
+
+        let mut lex = Strict::lexer("foo 1");
+
+        assert!(lex.try_advance().is_err());
+    }
+
+    #[test]
+    fn display() {
+        let err = Token::lexer("foo @").try_advance().unwrap_err();
+
+        assert_eq!(err.to_string(), "unrecognized input \"@\" at 4..5");
+    }
+}
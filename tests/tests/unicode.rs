@@ -0,0 +1,41 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex = r"\p{XID_Start}\p{XID_Continue}*"]
+    Ident,
+
+    #[regex(r"\p{Sm}", priority = 2)]
+    MathSymbol,
+}
+
+mod unicode {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        assert_lex(
+            "żółw Δx_1 東京 +∑",
+            &[
+                (Token::Ident, "żółw", 0..7),
+                (Token::Ident, "Δx_1", 8..13),
+                (Token::Ident, "東京", 14..20),
+                (Token::MathSymbol, "+", 21..22),
+                (Token::MathSymbol, "∑", 22..25),
+            ],
+        );
+    }
+
+    #[test]
+    fn not_a_start() {
+        assert_eq!(Token::lexer("1x").token, Token::Error);
+    }
+}
@@ -0,0 +1,87 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("straße", ignore(unicode_case))]
+    Strasse,
+
+    #[token("über", ignore(unicode_case))]
+    Ueber,
+
+    #[token("λέξη", ignore(unicode_case))]
+    Lexi,
+
+    #[token("σύνταξη", ignore(unicode_case))]
+    Syntaxi,
+
+    #[token("select", ignore(unicode_case))]
+    Select,
+
+    #[regex("(ά|ό)ρος", ignore(unicode_case))]
+    Term,
+
+    #[regex(r"\w+", unicode)]
+    Word,
+}
+
+mod unicode_case {
+    use super::*;
+
+    #[test]
+    fn german() {
+        assert_lex(
+            "straße STRAẞE Straße ÜBER über Über",
+            &[
+                (Token::Strasse, "straße", 0..7),
+                (Token::Strasse, "STRAẞE", 8..16),
+                (Token::Strasse, "Straße", 17..24),
+                (Token::Ueber, "ÜBER", 25..30),
+                (Token::Ueber, "über", 31..36),
+                (Token::Ueber, "Über", 37..42),
+            ],
+        );
+    }
+
+    #[test]
+    fn multiple_characters_dont_fold() {
+        assert_lex(
+            "STRASSE",
+            &[(Token::Word, "STRASSE", 0..7)],
+        );
+    }
+
+    #[test]
+    fn greek() {
+        assert_lex(
+            "λέξη ΛΈΞΗ Λέξη ΣΎΝΤΑΞΗ ΌΡΟΣ όρος",
+            &[
+                (Token::Lexi, "λέξη", 0..8),
+                (Token::Lexi, "ΛΈΞΗ", 9..17),
+                (Token::Lexi, "Λέξη", 18..26),
+                (Token::Syntaxi, "ΣΎΝΤΑΞΗ", 27..41),
+                (Token::Term, "ΌΡΟΣ", 42..50),
+                (Token::Term, "όρος", 51..59),
+            ],
+        );
+    }
+
+    #[test]
+    fn ascii() {
+        assert_lex(
+            "SELECT Select selects",
+            &[
+                (Token::Select, "SELECT", 0..6),
+                (Token::Select, "Select", 7..13),
+                (Token::Word, "selects", 14..21),
+            ],
+        );
+    }
+}
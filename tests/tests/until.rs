@@ -0,0 +1,164 @@
+use logos_derive::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("/*", until = "*/")]
+    BlockComment,
+
+    #[regex("<!-+", until = "-->")]
+    HtmlComment,
+
+    #[token("/")]
+    Slash,
+
+    #[token("*")]
+    Star,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Binary {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token(b"\x00", until = b"\xFF\xFF")]
+    Frame,
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Nested {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[token("/*", until = "*/", nested = "/*")]
+    BlockComment,
+
+    #[token("(*", until = "*)", nested = "(*")]
+    MlComment,
+
+    #[token("*")]
+    Star,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+mod until {
+    use super::*;
+
+    #[test]
+    fn block_comments() {
+        assert_lex(
+            "foo /* bar * / baz */ qux /**/ /***/",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::BlockComment, "/* bar * / baz */", 4..21),
+                (Token::Word, "qux", 22..25),
+                (Token::BlockComment, "/**/", 26..30),
+                (Token::BlockComment, "/***/", 31..36),
+            ],
+        );
+    }
+
+    #[test]
+    fn terminator_isnt_nested() {
+        assert_lex(
+            "/* /* */ */",
+            &[
+                (Token::BlockComment, "/* /* */", 0..8),
+                (Token::Star, "*", 9..10),
+                (Token::Slash, "/", 10..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn regex_until() {
+        assert_lex(
+            "<!-- foo -- bar --> baz <!---- x -->",
+            &[
+                (Token::HtmlComment, "<!-- foo -- bar -->", 0..19),
+                (Token::Word, "baz", 20..23),
+                (Token::HtmlComment, "<!---- x -->", 24..36),
+            ],
+        );
+    }
+
+    #[test]
+    fn unterminated() {
+        assert_lex(
+            "foo /* bar ż",
+            &[
+                (Token::Word, "foo", 0..3),
+                (Token::Error, "/* bar ż", 4..13),
+            ],
+        );
+    }
+
+    #[test]
+    fn binary() {
+        assert_lex(
+            &[0x00, 0x01, 0xFF, 0x02, 0xFF, 0xFF, 0x00, 0xFF][..],
+            &[
+                (Binary::Frame, &[0x00, 0x01, 0xFF, 0x02, 0xFF, 0xFF][..], 0..6),
+                (Binary::Error, &[0x00, 0xFF][..], 6..8),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_lex(
+            "/* a /* b */ c */ d /* /* /* */ */ */ (* (* x *) *) /**/",
+            &[
+                (Nested::BlockComment, "/* a /* b */ c */", 0..17),
+                (Nested::Word, "d", 18..19),
+                (Nested::BlockComment, "/* /* /* */ */ */", 20..37),
+                (Nested::MlComment, "(* (* x *) *)", 38..51),
+                (Nested::BlockComment, "/**/", 52..56),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_other_delimiters() {
+        assert_lex(
+            "/* (* */ (* /* *) * foo",
+            &[
+                (Nested::BlockComment, "/* (* */", 0..8),
+                (Nested::MlComment, "(* /* *)", 9..17),
+                (Nested::Star, "*", 18..19),
+                (Nested::Word, "foo", 20..23),
+            ],
+        );
+    }
+
+    #[test]
+    fn nested_unbalanced() {
+        assert_lex(
+            "foo /* /* bar */ ż",
+            &[
+                (Nested::Word, "foo", 0..3),
+                (Nested::Error, "/* /* bar */ ż", 4..19),
+            ],
+        );
+        assert_lex("*/ foo", &[(Nested::Star, "*", 0..1), (Nested::Error, "/", 1..2), (Nested::Word, "foo", 3..6)]);
+    }
+}
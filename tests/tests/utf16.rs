@@ -0,0 +1,103 @@
+use logos::{Logos as _, Utf16Source};
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"\s")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex(r"\p{L}+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[token("🦀")]
+    Crab,
+}
+
+fn lex_utf16(units: &[u16]) -> Vec<(Token, String, std::ops::Range<usize>)> {
+    let source = Utf16Source::new(units);
+    let mut lex = Token::lexer(&source);
+    let mut tokens = Vec::new();
+
+    while lex.token != Token::End {
+        tokens.push((lex.token, lex.slice().to_owned(), lex.range_utf16()));
+        lex.advance();
+    }
+
+    tokens
+}
+
+mod utf16 {
+    use super::*;
+
+    #[test]
+    fn ranges_in_code_units() {
+        let units: Vec<u16> = "zażółć 🦀42 🦀".encode_utf16().collect();
+
+        assert_eq!(
+            lex_utf16(&units),
+            &[
+                (Token::Word, "zażółć".to_owned(), 0..6),
+                (Token::Crab, "🦀".to_owned(), 7..9),
+                (Token::Number, "42".to_owned(), 9..11),
+                (Token::Crab, "🦀".to_owned(), 12..14),
+            ]
+        );
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        let mut units: Vec<u16> = "foo ".encode_utf16().collect();
+
+        units.push(0xD83E);
+        units.extend(" bar".encode_utf16());
+
+        assert_eq!(
+            lex_utf16(&units),
+            &[
+                (Token::Word, "foo".to_owned(), 0..3),
+                (Token::Error, "\u{FFFD}".to_owned(), 4..5),
+                (Token::Word, "bar".to_owned(), 6..9),
+            ]
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let units: Vec<u16> = "a🦀b".encode_utf16().collect();
+        let source = Utf16Source::new(&units);
+
+        assert_eq!(source.as_str(), "a🦀b");
+        assert_eq!(source.utf16_offset(0), 0);
+        assert_eq!(source.utf16_offset(1), 1);
+        assert_eq!(source.utf16_offset(3), 1);
+        assert_eq!(source.utf16_offset(5), 3);
+        assert_eq!(source.utf16_offset(6), 4);
+        assert_eq!(source.utf16_range(1..5), 1..3);
+    }
+
+    #[test]
+    fn offsets_of_every_byte() {
+        let text = "ab żółw 🦀🦀 x€y";
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let source = Utf16Source::new(&units);
+        let mut expected = Vec::new();
+        let mut offset = 0;
+
+        for ch in text.chars() {
+            expected.extend((0..ch.len_utf8()).map(|_| offset));
+            offset += ch.len_utf16();
+        }
+        expected.push(offset);
+
+        let offsets: Vec<usize> = (0..=text.len()).map(|byte| source.utf16_offset(byte)).collect();
+
+        assert_eq!(offsets, expected);
+    }
+}
@@ -0,0 +1,92 @@
+use logos::Logos as _;
+use logos_derive::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(trivia = r"[ \t\n]")]
+enum Token {
+    #[end]
+    End,
+
+    #[error]
+    Error,
+
+    #[regex("^#[a-z]+")]
+    Directive,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[a-z]+$")]
+    LastWord,
+}
+
+mod window {
+    use super::*;
+
+    #[test]
+    fn per_line() {
+        let source = "foo bar\n#baz qux\n";
+        let mut tokens = Vec::new();
+        let mut start = 0;
+
+        for line in source.split_inclusive('\n') {
+            let end = start + line.len() - 1;
+
+            tokens.extend(Token::lexer_in(source, start..end).into_vec());
+            start = end + 1;
+        }
+
+        assert_eq!(
+            tokens,
+            &[
+                (Token::Word, 0..3),
+                (Token::LastWord, 4..7),
+                (Token::Directive, 8..12),
+                (Token::LastWord, 13..16),
+            ]
+        );
+    }
+
+    #[test]
+    fn ends_at_window_end() {
+        let mut lex = Token::lexer_in("foobar", 0..3);
+
+        assert_eq!(lex.token, Token::LastWord);
+        assert_eq!(lex.slice(), "foo");
+
+        lex.advance();
+
+        assert_eq!(lex.token, Token::End);
+        assert_eq!(lex.range(), 3..3);
+        assert!(lex.is_at_end());
+        assert_eq!(lex.remainder(), "");
+    }
+
+    #[test]
+    fn empty_window() {
+        let lex = Token::lexer_in("foo", 2..2);
+
+        assert_eq!(lex.token, Token::End);
+        assert_eq!(lex.range(), 2..2);
+    }
+
+    #[test]
+    fn bytes_window() {
+        let source: &[u8] = b"foo bar baz";
+        let tokens = Token::lexer_in(source, 4..11).into_vec();
+
+        assert_eq!(tokens, &[(Token::Word, 4..7), (Token::LastWord, 8..11)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer window")]
+    fn rejects_window_past_end() {
+        Token::lexer_in("foo", 1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid Lexer window")]
+    fn rejects_window_inside_char() {
+        Token::lexer_in("żółw", 1..4);
+    }
+}